@@ -0,0 +1,402 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * Holiday calendars for the business day arithmetic.
+ *
+ * A `HolidayCalendar` decides which days of week are weekends and which dates are holidays;
+ * the other days are business days. `RuleCalendar` is made of `HolidayRule`s
+ * (e.g. the fourth Thursday of November, or two days before Easter) and individual dates,
+ * and the calendars can be combined with `Union` and `Intersection`.
+ * The business days are counted via `NaiveDate::add_business_days`,
+ * `NaiveDate::is_business_day` and `NaiveDate::business_days_between`.
+ */
+
+use std::num::Int;
+
+use {Weekday, Datelike};
+use naive::date::NaiveDate;
+
+/// A calendar which decides the weekends and holidays. Other days are business days.
+pub trait HolidayCalendar {
+    /// Returns true if `weekday` is a weekend. Saturday and Sunday by default.
+    fn is_weekend(&self, weekday: Weekday) -> bool {
+        weekday == Weekday::Sat || weekday == Weekday::Sun
+    }
+
+    /// Returns true if `date` is a holiday. It does not have to consider the weekends.
+    fn is_holiday(&self, date: &NaiveDate) -> bool;
+
+    /// Returns true if `date` is neither a weekend nor a holiday.
+    fn is_business_day(&self, date: &NaiveDate) -> bool {
+        !self.is_weekend(date.weekday()) && !self.is_holiday(date)
+    }
+}
+
+impl<'a, C: HolidayCalendar> HolidayCalendar for &'a C {
+    fn is_weekend(&self, weekday: Weekday) -> bool { (**self).is_weekend(weekday) }
+    fn is_holiday(&self, date: &NaiveDate) -> bool { (**self).is_holiday(date) }
+    fn is_business_day(&self, date: &NaiveDate) -> bool { (**self).is_business_day(date) }
+}
+
+/// How a holiday falling on a weekend is observed.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum Observance {
+    /// On the next day which is not a weekend, e.g. Monday from Saturday and Sunday.
+    NextWeekday,
+    /// On the nearest day which is not a weekend, the later one when both are as near,
+    /// e.g. Friday from Saturday and Monday from Sunday.
+    NearestWeekday,
+}
+
+/// A rule giving the date of a holiday in each year.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub enum HolidayRule {
+    /// The fixed month and day (e.g. `Fixed(12, 25)` for December 25).
+    /// February 29 only occurs in the leap years.
+    Fixed(u32, u32),
+    /// The `n`-th day of week in the month, from 1 to 5
+    /// (e.g. `NthWeekday(11, 4, Weekday::Thu)` for the fourth Thursday of November).
+    /// It does not occur when the month has less than `n` such days.
+    NthWeekday(u32, u32, Weekday),
+    /// The last day of week in the month
+    /// (e.g. `LastWeekday(5, Weekday::Mon)` for the last Monday of May).
+    LastWeekday(u32, Weekday),
+    /// Given number of days after the Western Easter Sunday, negative for before
+    /// (e.g. `Easter(-2)` for Good Friday).
+    Easter(i32),
+    /// Given number of days after the Orthodox Easter Sunday, negative for before
+    /// (e.g. `OrthodoxEaster(1)` for Orthodox Easter Monday).
+    OrthodoxEaster(i32),
+    /// The holiday given by the rule, moved off the weekend of the calendar by the observance
+    /// when it falls on the weekend. The moved date can be in the adjacent year.
+    Observed(Box<HolidayRule>, Observance),
+}
+
+impl HolidayRule {
+    /// Returns the date of the holiday in given year, or `None` if it does not occur.
+    /// The observed holidays are moved off Saturday and Sunday.
+    pub fn date_in(&self, year: i32) -> Option<NaiveDate> {
+        self.date_in_calendar(year, &RuleCalendar::new())
+    }
+
+    /// Returns the date of the holiday in given year, or `None` if it does not occur.
+    /// The observed holidays are moved off the weekends of `calendar`.
+    pub fn date_in_calendar<C: HolidayCalendar>(&self, year: i32,
+                                                calendar: &C) -> Option<NaiveDate> {
+        match *self {
+            HolidayRule::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+            HolidayRule::NthWeekday(month, n, weekday) => {
+                if n < 1 || n > 5 { return None; }
+                let first = match NaiveDate::from_ymd_opt(year, month, 1) {
+                    Some(first) => first,
+                    None => return None,
+                };
+                let day = 1 + (weekday.num_days_from_monday() + 7 -
+                               first.weekday().num_days_from_monday()) % 7 + (n - 1) * 7;
+                first.with_day(day)
+            }
+            HolidayRule::LastWeekday(month, weekday) => {
+                let next = if month == 12 { NaiveDate::from_ymd_opt(year + 1, 1, 1) }
+                           else { NaiveDate::from_ymd_opt(year, month + 1, 1) };
+                let last = match next.and_then(|next| next.pred_opt()) {
+                    Some(last) if last.month() == month => last,
+                    _ => return None,
+                };
+                let back = (last.weekday().num_days_from_monday() + 7 -
+                            weekday.num_days_from_monday()) % 7;
+                last.with_day(last.day() - back)
+            }
+            HolidayRule::Easter(days) => {
+                NaiveDate::easter_opt(year)
+                    .and_then(|easter| easter.num_days_from_ce().checked_add(days))
+                    .and_then(|days| NaiveDate::from_num_days_from_ce_opt(days))
+            }
+            HolidayRule::OrthodoxEaster(days) => {
+                NaiveDate::orthodox_easter_opt(year)
+                    .and_then(|easter| easter.num_days_from_ce().checked_add(days))
+                    .and_then(|days| NaiveDate::from_num_days_from_ce_opt(days))
+            }
+            HolidayRule::Observed(ref rule, observance) => {
+                let date = match rule.date_in_calendar(year, calendar) {
+                    Some(date) => date,
+                    None => return None,
+                };
+                if !calendar.is_weekend(date.weekday()) { return Some(date); }
+                let after = days_to_weekday(calendar, date.weekday(), 1);
+                let days = match observance {
+                    Observance::NextWeekday => after,
+                    Observance::NearestWeekday => {
+                        match (days_to_weekday(calendar, date.weekday(), -1), after) {
+                            (Some(before), Some(after)) if -before < after => Some(before),
+                            (_, after) => after,
+                        }
+                    }
+                };
+                match days {
+                    Some(days) => NaiveDate::from_num_days_from_ce_opt(date.num_days_from_ce() +
+                                                                       days),
+                    None => Some(date), // every day is a weekend
+                }
+            }
+        }
+    }
+}
+
+/// Returns the number of days from `weekday` to the nearest day which is not a weekend
+/// of `calendar`, forward if `dir` is 1 and backward (negative) if `dir` is -1.
+/// Returns `None` if every day is a weekend.
+fn days_to_weekday<C: HolidayCalendar>(calendar: &C, weekday: Weekday, dir: i32) -> Option<i32> {
+    let mut weekday = weekday;
+    for days in range(1, 7) {
+        weekday = if dir > 0 { weekday.succ() } else { weekday.pred() };
+        if !calendar.is_weekend(weekday) { return Some(days * dir); }
+    }
+    None
+}
+
+/// A calendar of weekends, holidays by `HolidayRule`s and individual holidays.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct RuleCalendar {
+    /// The weekends as bits, indexed by the number of days from Monday.
+    weekend: u8,
+    rules: Vec<HolidayRule>,
+    dates: Vec<NaiveDate>,
+}
+
+impl RuleCalendar {
+    /// Makes a new calendar with Saturday and Sunday as weekends and no holidays.
+    pub fn new() -> RuleCalendar {
+        RuleCalendar::with_weekend(&[Weekday::Sat, Weekday::Sun])
+    }
+
+    /// Makes a new calendar with given weekends and no holidays.
+    pub fn with_weekend(weekend: &[Weekday]) -> RuleCalendar {
+        let weekend = weekend.iter().fold(0, |bits, w| {
+            bits | 1 << w.num_days_from_monday() as usize
+        });
+        RuleCalendar { weekend: weekend, rules: Vec::new(), dates: Vec::new() }
+    }
+
+    /// Adds a holiday rule.
+    pub fn add_rule(&mut self, rule: HolidayRule) {
+        self.rules.push(rule);
+    }
+
+    /// Adds an individual holiday.
+    pub fn add_date(&mut self, date: NaiveDate) {
+        self.dates.push(date);
+    }
+
+    /// Returns the holidays in given year in the ascending order, including those on weekends.
+    pub fn holidays_in(&self, year: i32) -> Vec<NaiveDate> {
+        let mut holidays: Vec<NaiveDate> = self.dates.iter().filter(|d| d.year() == year)
+                                                   .map(|&d| d).collect();
+        for rule in self.rules.iter() {
+            // the observed holidays can move from the adjacent years
+            for y in [year - 1, year, year + 1].iter() {
+                match rule.date_in_calendar(*y, self) {
+                    Some(date) if date.year() == year => holidays.push(date),
+                    _ => {}
+                }
+            }
+        }
+        holidays.sort();
+        holidays.dedup();
+        holidays
+    }
+}
+
+impl HolidayCalendar for RuleCalendar {
+    fn is_weekend(&self, weekday: Weekday) -> bool {
+        self.weekend & (1 << weekday.num_days_from_monday() as usize) != 0
+    }
+
+    fn is_holiday(&self, date: &NaiveDate) -> bool {
+        let year = date.year();
+        self.dates.contains(date) ||
+            self.rules.iter().any(|rule| {
+                [year - 1, year, year + 1].iter()
+                    .any(|&y| rule.date_in_calendar(y, self) == Some(*date))
+            })
+    }
+}
+
+/// A calendar where a day is a business day only if it is in both calendars,
+/// e.g. for the settlement between two markets.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct Union<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: HolidayCalendar, B: HolidayCalendar> Union<A, B> {
+    /// Makes a new calendar with the weekends and holidays of both calendars.
+    pub fn new(a: A, b: B) -> Union<A, B> {
+        Union { a: a, b: b }
+    }
+}
+
+impl<A: HolidayCalendar, B: HolidayCalendar> HolidayCalendar for Union<A, B> {
+    fn is_weekend(&self, weekday: Weekday) -> bool {
+        self.a.is_weekend(weekday) || self.b.is_weekend(weekday)
+    }
+
+    fn is_holiday(&self, date: &NaiveDate) -> bool {
+        self.a.is_holiday(date) || self.b.is_holiday(date)
+    }
+}
+
+/// A calendar where a day is a business day if it is in either calendar.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct Intersection<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: HolidayCalendar, B: HolidayCalendar> Intersection<A, B> {
+    /// Makes a new calendar with the weekends and holidays common to both calendars.
+    pub fn new(a: A, b: B) -> Intersection<A, B> {
+        Intersection { a: a, b: b }
+    }
+}
+
+impl<A: HolidayCalendar, B: HolidayCalendar> HolidayCalendar for Intersection<A, B> {
+    fn is_weekend(&self, weekday: Weekday) -> bool {
+        self.a.is_weekend(weekday) && self.b.is_weekend(weekday)
+    }
+
+    // a weekend of one calendar can be a holiday of another
+    fn is_holiday(&self, date: &NaiveDate) -> bool {
+        !self.a.is_business_day(date) && !self.b.is_business_day(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HolidayCalendar, HolidayRule, Observance, RuleCalendar, Union, Intersection};
+    use Weekday;
+    use naive::date::NaiveDate;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(y, m, d)
+    }
+
+    fn us_federal() -> RuleCalendar {
+        let mut calendar = RuleCalendar::new();
+        let observed = |&: rule: HolidayRule| {
+            HolidayRule::Observed(Box::new(rule), Observance::NearestWeekday)
+        };
+        calendar.add_rule(observed(HolidayRule::Fixed(1, 1)));
+        calendar.add_rule(HolidayRule::NthWeekday(1, 3, Weekday::Mon));
+        calendar.add_rule(HolidayRule::LastWeekday(5, Weekday::Mon));
+        calendar.add_rule(observed(HolidayRule::Fixed(7, 4)));
+        calendar.add_rule(HolidayRule::NthWeekday(11, 4, Weekday::Thu));
+        calendar.add_rule(observed(HolidayRule::Fixed(12, 25)));
+        calendar
+    }
+
+    #[test]
+    fn test_holiday_rule() {
+        assert_eq!(HolidayRule::Fixed(2, 29).date_in(2015), None);
+        assert_eq!(HolidayRule::Fixed(2, 29).date_in(2016), Some(ymd(2016, 2, 29)));
+        assert_eq!(HolidayRule::NthWeekday(11, 4, Weekday::Thu).date_in(2015),
+                   Some(ymd(2015, 11, 26)));
+        assert_eq!(HolidayRule::NthWeekday(3, 5, Weekday::Sun).date_in(2015),
+                   Some(ymd(2015, 3, 29)));
+        assert_eq!(HolidayRule::NthWeekday(2, 5, Weekday::Sun).date_in(2015), None);
+        assert_eq!(HolidayRule::LastWeekday(5, Weekday::Mon).date_in(2015),
+                   Some(ymd(2015, 5, 25)));
+        assert_eq!(HolidayRule::LastWeekday(12, Weekday::Thu).date_in(2015),
+                   Some(ymd(2015, 12, 31)));
+        assert_eq!(HolidayRule::Easter(0).date_in(2015), Some(ymd(2015, 4, 5)));
+        assert_eq!(HolidayRule::Easter(-2).date_in(2016), Some(ymd(2016, 3, 25)));
+        assert_eq!(HolidayRule::Easter(1).date_in(2019), Some(ymd(2019, 4, 22)));
+        assert_eq!(HolidayRule::OrthodoxEaster(-2).date_in(2016), Some(ymd(2016, 4, 29)));
+
+        let observed = |&: month: u32, day: u32, observance: Observance| {
+            HolidayRule::Observed(Box::new(HolidayRule::Fixed(month, day)), observance)
+        };
+        // 2015-07-04 is Saturday, 2016-12-25 is Sunday
+        assert_eq!(observed(7, 4, Observance::NearestWeekday).date_in(2015),
+                   Some(ymd(2015, 7, 3)));
+        assert_eq!(observed(7, 4, Observance::NextWeekday).date_in(2015), Some(ymd(2015, 7, 6)));
+        assert_eq!(observed(12, 25, Observance::NearestWeekday).date_in(2016),
+                   Some(ymd(2016, 12, 26)));
+        assert_eq!(observed(12, 25, Observance::NextWeekday).date_in(2015),
+                   Some(ymd(2015, 12, 25)));
+
+        // 2015-01-16 is Friday, 2015-01-17 is Saturday and 2015-01-18 is Sunday
+        let gulf = RuleCalendar::with_weekend(&[Weekday::Fri, Weekday::Sat]);
+        assert_eq!(observed(1, 16, Observance::NextWeekday).date_in_calendar(2015, &gulf),
+                   Some(ymd(2015, 1, 18)));
+        assert_eq!(observed(1, 16, Observance::NearestWeekday).date_in_calendar(2015, &gulf),
+                   Some(ymd(2015, 1, 15)));
+        assert_eq!(observed(1, 17, Observance::NearestWeekday).date_in_calendar(2015, &gulf),
+                   Some(ymd(2015, 1, 18)));
+        assert_eq!(observed(1, 18, Observance::NearestWeekday).date_in_calendar(2015, &gulf),
+                   Some(ymd(2015, 1, 18)));
+        let sunday = RuleCalendar::with_weekend(&[Weekday::Sun]);
+        assert_eq!(observed(1, 18, Observance::NearestWeekday).date_in_calendar(2015, &sunday),
+                   Some(ymd(2015, 1, 19)));
+        let never = RuleCalendar::with_weekend(&[Weekday::Mon, Weekday::Tue, Weekday::Wed,
+                                                 Weekday::Thu, Weekday::Fri, Weekday::Sat,
+                                                 Weekday::Sun]);
+        assert_eq!(observed(1, 18, Observance::NextWeekday).date_in_calendar(2015, &never),
+                   Some(ymd(2015, 1, 18)));
+    }
+
+    #[test]
+    fn test_rule_calendar() {
+        let calendar = us_federal();
+        assert_eq!(calendar.holidays_in(2015),
+                   [ymd(2015, 1, 1), ymd(2015, 1, 19), ymd(2015, 5, 25), ymd(2015, 7, 3),
+                    ymd(2015, 11, 26), ymd(2015, 12, 25)]);
+        // 2022-01-01 is Saturday, observed in the last year
+        assert_eq!(calendar.holidays_in(2021).last(), Some(&ymd(2021, 12, 31)));
+        assert!(calendar.is_holiday(&ymd(2021, 12, 31)));
+        assert!(!calendar.is_business_day(&ymd(2015, 7, 3)));
+        assert!(!calendar.is_business_day(&ymd(2015, 7, 4)));
+        assert!(calendar.is_business_day(&ymd(2015, 7, 6)));
+
+        let mut calendar = RuleCalendar::with_weekend(&[Weekday::Fri, Weekday::Sat]);
+        calendar.add_date(ymd(2015, 1, 20));
+        assert!(!calendar.is_business_day(&ymd(2015, 1, 16)));
+        assert!(calendar.is_business_day(&ymd(2015, 1, 18)));
+        assert!(!calendar.is_business_day(&ymd(2015, 1, 20)));
+        assert_eq!(calendar.holidays_in(2015), [ymd(2015, 1, 20)]);
+
+        // the observed holidays follow the weekends of the calendar
+        let mut calendar = RuleCalendar::with_weekend(&[Weekday::Fri, Weekday::Sat]);
+        calendar.add_rule(HolidayRule::Observed(Box::new(HolidayRule::Fixed(1, 16)),
+                                                Observance::NextWeekday));
+        calendar.add_rule(HolidayRule::Observed(Box::new(HolidayRule::Fixed(1, 25)),
+                                                Observance::NextWeekday));
+        assert_eq!(calendar.holidays_in(2015), [ymd(2015, 1, 18), ymd(2015, 1, 25)]);
+        assert!(!calendar.is_business_day(&ymd(2015, 1, 18))); // Sunday
+        assert!(calendar.is_business_day(&ymd(2015, 1, 26)));
+    }
+
+    #[test]
+    fn test_union_intersection() {
+        let us = us_federal();
+        let mut gulf = RuleCalendar::with_weekend(&[Weekday::Fri, Weekday::Sat]);
+        gulf.add_date(ymd(2015, 1, 20));
+
+        let both = Union::new(&us, &gulf);
+        assert!(!both.is_business_day(&ymd(2015, 1, 16))); // Friday
+        assert!(!both.is_business_day(&ymd(2015, 1, 18))); // Sunday
+        assert!(!both.is_business_day(&ymd(2015, 1, 19))); // U.S. holiday
+        assert!(!both.is_business_day(&ymd(2015, 1, 20))); // the other holiday
+        assert!(both.is_business_day(&ymd(2015, 1, 21)));
+
+        let either = Intersection::new(&us, &gulf);
+        assert!(either.is_business_day(&ymd(2015, 1, 16)));
+        assert!(either.is_business_day(&ymd(2015, 1, 18)));
+        assert!(either.is_business_day(&ymd(2015, 1, 19)));
+        assert!(either.is_business_day(&ymd(2015, 1, 20)));
+        assert!(!either.is_business_day(&ymd(2015, 1, 17))); // Saturday
+        assert!(!either.is_business_day(&ymd(2015, 7, 3))); // U.S. holiday and Friday
+    }
+}
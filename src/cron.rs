@@ -0,0 +1,746 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * Cron schedules (e.g. `0,30 9-17 * * MON-FRI`).
+ *
+ * A `Schedule` is parsed from the cron expression of 5 fields (minute, hour, day of month,
+ * month and day of week), 6 fields (with the second at first) or 7 fields (with the year
+ * at last), and gives the matching date and time right after or before given `DateTime`.
+ *
+ * Besides `*`, lists (`1,5`), ranges (`1-5`) and steps (`10-30/5`, also after `*`), the extensions
+ * from Quartz are supported: `?` for the day of month or week, `L` (the last day of month),
+ * `L-n`, `LW` (the last weekday of month), `nW` (the weekday nearest to the day `n`),
+ * `dL` (the last day `d` of week in the month) and `d#n` (the `n`-th day `d` of week).
+ * The months and days of week can be written in English (`JAN`, `MON`), and the days of
+ * week are numbered from 0 (Sunday) to 7 (Sunday again) as crontab does.
+ * When both the day of month and day of week are restricted, either of them can match.
+ * The macros `@yearly` (`@annually`), `@monthly`, `@weekly`, `@daily` (`@midnight`)
+ * and `@hourly` are also recognized.
+ *
+ * The schedule applies to the local date and time. The local date and time occurring twice
+ * matches only at the earlier instant, and the nonexistent local date and time
+ * (e.g. in the gap of the daylight saving time) matches at the end of the gap.
+ */
+
+use std::{cmp, fmt};
+use std::num::Int;
+use std::ascii::AsciiExt;
+use std::str::FromStr;
+
+use {Datelike, Timelike};
+use offset::Offset;
+use naive::date::NaiveDate;
+use naive::time::NaiveTime;
+use naive::datetime::NaiveDateTime;
+use datetime::DateTime;
+use months::days_in_month;
+use parse::{ParseError, ParseErrorKind, ParseResult};
+use resolve::{add_seconds, resolve_datetime};
+
+macro_rules! try_opt {
+    ($e:expr) => (match $e { Some(v) => v, None => return None })
+}
+
+/// The names of months in cron expressions, starting from January.
+static MONTH_NAMES: [&'static str; 12] =
+    ["JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC"];
+
+/// The names of days of week in cron expressions, starting from Sunday.
+static WEEKDAY_NAMES: [&'static str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// The first and last years allowed in the year field.
+const MIN_YEAR: u32 = 1970;
+const MAX_YEAR: u32 = 2099;
+
+/// The schedule described by a cron expression.
+/// It is parsed via `FromStr` and printed back via `Display` as given.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// The expression, without the surrounding whitespace.
+    source: String,
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    /// The days of month as bits, and whether the field is `*` or `?`.
+    days: u64,
+    any_day: bool,
+    /// The days for `L` and `L-n`, as the number of days before the last day of month.
+    days_before_last: Vec<u32>,
+    /// The days for `nW`, which match the nearest weekday (Monday to Friday) in the month.
+    nearest_weekdays: Vec<u32>,
+    /// Whether `LW`, the last weekday of month, is included.
+    last_weekday: bool,
+    months: u64,
+    /// The days of week as bits from Sunday, and whether the field is `*` or `?`.
+    weekdays: u64,
+    any_weekday: bool,
+    /// The days of week for `dL`, which match the last such day in the month.
+    last_weekdays: u64,
+    /// The days of week and their ordinals for `d#n`.
+    nth_weekdays: Vec<(u32, u32)>,
+    /// The years in the ascending order, or empty when any year is allowed.
+    years: Vec<i32>,
+}
+
+/// The fields of cron expressions.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Field {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Weekday,
+    Year,
+}
+
+impl Field {
+    /// Returns the range of values and the names of values starting from the first.
+    fn values(&self) -> (u32, u32, &'static [&'static str]) {
+        static NO_NAMES: [&'static str; 0] = [];
+        match *self {
+            Field::Second | Field::Minute => (0, 59, &NO_NAMES),
+            Field::Hour => (0, 23, &NO_NAMES),
+            Field::Day => (1, 31, &NO_NAMES),
+            Field::Month => (1, 12, &MONTH_NAMES),
+            Field::Weekday => (0, 7, &WEEKDAY_NAMES),
+            Field::Year => (MIN_YEAR, MAX_YEAR, &NO_NAMES),
+        }
+    }
+}
+
+/// The scanner over a field of the cron expression.
+struct Scanner<'a> {
+    s: &'a [u8],
+    pos: usize,
+    /// The byte offset of the field in the expression, for the errors.
+    base: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).map(|&c| c)
+    }
+
+    /// Reads `c`, ignoring the case of letters.
+    fn eat(&mut self, c: u8) -> bool {
+        match self.peek() {
+            Some(c2) if upper(c2) == c => { self.pos += 1; true }
+            _ => false,
+        }
+    }
+
+    fn error<T>(&self, kind: ParseErrorKind, pos: usize) -> ParseResult<T> {
+        Err(ParseError { kind: kind, pos: self.base + pos })
+    }
+
+    fn unexpected<T>(&self) -> ParseResult<T> {
+        let kind = if self.pos < self.s.len() { ParseErrorKind::Invalid }
+                   else { ParseErrorKind::TooShort };
+        self.error(kind, self.pos)
+    }
+
+    /// Reads a decimal number of at most 4 digits, which is from `lo` to `hi`.
+    fn number(&mut self, lo: u32, hi: u32) -> ParseResult<u32> {
+        let start = self.pos;
+        let mut n = 0;
+        while self.pos - start < 4 {
+            match self.peek() {
+                Some(c @ b'0'...b'9') => { n = n * 10 + (c - b'0') as u32; self.pos += 1; }
+                _ => break,
+            }
+        }
+        if self.pos == start { return self.unexpected(); }
+        if n < lo || n > hi { return self.error(ParseErrorKind::OutOfRange, start); }
+        Ok(n)
+    }
+
+    /// Reads a value of `field`, either a number or a name.
+    fn value(&mut self, field: Field) -> ParseResult<u32> {
+        let (lo, hi, names) = field.values();
+        match self.peek() {
+            Some(c) if (c as char).is_alphabetic() && !names.is_empty() => {
+                let name = &self.s[self.pos..cmp::min(self.pos + 3, self.s.len())];
+                for (i, expected) in names.iter().enumerate() {
+                    if name.len() == 3 &&
+                       name.iter().zip(expected.bytes()).all(|(&c, e)| upper(c) == e) {
+                        self.pos += 3;
+                        return Ok(lo + i as u32);
+                    }
+                }
+                self.unexpected()
+            }
+            _ => self.number(lo, hi),
+        }
+    }
+}
+
+/// Converts an ASCII lowercase letter to uppercase.
+fn upper(c: u8) -> u8 {
+    if b'a' <= c && c <= b'z' { c - b'a' + b'A' } else { c }
+}
+
+/// Returns a set of `lo` to `hi` by `step` as bits.
+fn bits(lo: u32, hi: u32, step: u32) -> u64 {
+    let mut set = 0;
+    let mut v = lo;
+    while v <= hi { set |= 1 << v as usize; v += step; }
+    set
+}
+
+/// Returns the least value in `set` which is at least `from`.
+fn next_in(set: u64, from: u32) -> Option<u32> {
+    if from >= 64 { return None; }
+    let rest = (set >> from as usize) << from as usize;
+    if rest == 0 { None } else { Some(rest.trailing_zeros() as u32) }
+}
+
+/// Returns the greatest value in `set` which is at most `from`.
+fn prev_in(set: u64, from: u32) -> Option<u32> {
+    let rest = if from >= 63 { set } else { set & ((1 << (from + 1) as usize) - 1) };
+    if rest == 0 { None } else { Some(63 - rest.leading_zeros() as u32) }
+}
+
+/// Returns true if `v` is in `set`.
+fn has(set: u64, v: u32) -> bool {
+    set & (1 << v as usize) != 0
+}
+
+/// Returns the day of the weekday nearest to `day` in the month, without leaving the month.
+/// `first` is the day of week of the first day from Sunday, and `last` is the last day.
+fn nearest_weekday(day: u32, first: u32, last: u32) -> Option<u32> {
+    if day > last { return None; }
+    Some(match (first + day - 1) % 7 {
+        6 if day == 1 => 3,     // Saturday the 1st, then Monday the 3rd
+        6 => day - 1,
+        0 if day == last => day - 2,
+        0 => day + 1,
+        _ => day,
+    })
+}
+
+/// Returns the local date and time of `dt` without the fraction of second.
+fn local_seconds<Off: Offset>(dt: &DateTime<Off>) -> Option<NaiveDateTime> {
+    let utc = try_opt!(NaiveDateTime::from_num_seconds_from_unix_epoch_opt(
+        dt.num_seconds_from_unix_epoch(), 0));
+    Some(dt.offset().to_local_datetime(&utc))
+}
+
+impl Schedule {
+    /// Returns true if the local date and time matches the schedule.
+    /// The fraction of second is ignored.
+    pub fn includes(&self, local: &NaiveDateTime) -> bool {
+        let first = local.date().with_day(1).unwrap();
+        has(self.seconds, local.second()) && has(self.minutes, local.minute()) &&
+            has(self.hours, local.hour()) && self.has_year(local.year()) &&
+            has(self.months, local.month()) &&
+            self.has_day(&first, days_in_month(local.year(), local.month()), local.day())
+    }
+
+    /// Returns the first date and time matching the schedule strictly after `after`,
+    /// in the offset of `after`.
+    ///
+    /// Returns `None` when there is no such date and time, or when no date matches
+    /// for 400 years (the Gregorian calendar repeats itself).
+    pub fn next_after<Off: Offset>(&self, after: &DateTime<Off>) -> Option<DateTime<Off>> {
+        let mut local = try_opt!(local_seconds(after).and_then(|dt| add_seconds(&dt, 1)));
+        loop {
+            let next = try_opt!(self.next_local(&local));
+            match resolve_datetime(after.offset(), &next) {
+                Some(dt) => if dt > *after { return Some(dt); },
+                None => {}
+            }
+            // the local date and time occurred twice and `after` is in the second time
+            local = try_opt!(add_seconds(&next, 1));
+        }
+    }
+
+    /// Returns the last date and time matching the schedule strictly before `before`,
+    /// in the offset of `before`.
+    ///
+    /// Returns `None` when there is no such date and time, or when no date matches
+    /// for 400 years (the Gregorian calendar repeats itself).
+    pub fn prev_before<Off: Offset>(&self, before: &DateTime<Off>) -> Option<DateTime<Off>> {
+        // the local date and time occurring twice matches at the earlier instant,
+        // so the local date and time of `before` in the earlier offset can still match
+        let local = try_opt!(local_seconds(before));
+        let earlier = try_opt!(before.offset().from_local_datetime(&local).earliest());
+        let shift = (earlier.offset().local_minus_utc() - before.offset().local_minus_utc())
+            .num_seconds();
+        let mut local = try_opt!(add_seconds(&local, shift));
+        loop {
+            let prev = try_opt!(self.prev_local(&local));
+            match resolve_datetime(before.offset(), &prev) {
+                Some(dt) => if dt < *before { return Some(dt); },
+                None => {}
+            }
+            local = try_opt!(add_seconds(&prev, -1));
+        }
+    }
+
+    /// Returns an iterator over the dates and times matching the schedule after `after`,
+    /// in the ascending order.
+    pub fn iter_after<'a, Off: Offset>(&'a self, after: &DateTime<Off>) -> ScheduleIter<'a, Off> {
+        ScheduleIter { schedule: self, current: Some(after.clone()), forward: true }
+    }
+
+    /// Returns an iterator over the dates and times matching the schedule before `before`,
+    /// in the descending order.
+    pub fn iter_before<'a, Off: Offset>(&'a self,
+                                        before: &DateTime<Off>) -> ScheduleIter<'a, Off> {
+        ScheduleIter { schedule: self, current: Some(before.clone()), forward: false }
+    }
+
+    fn has_year(&self, year: i32) -> bool {
+        self.years.is_empty() || self.years.contains(&year)
+    }
+
+    /// Returns true if the day `day` of the month starting at `first` matches.
+    /// `last` is the last day of the month.
+    fn has_day(&self, first: &NaiveDate, last: u32, day: u32) -> bool {
+        let first = first.weekday().num_days_from_sunday();
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => self.has_weekday(first, last, day),
+            (false, true) => self.has_day_of_month(first, last, day),
+            (false, false) => self.has_day_of_month(first, last, day) ||
+                              self.has_weekday(first, last, day),
+        }
+    }
+
+    /// Returns true if the day of month field matches. `first` is the day of week
+    /// of the first day from Sunday, and `last` is the last day of the month.
+    fn has_day_of_month(&self, first: u32, last: u32, day: u32) -> bool {
+        has(self.days, day) ||
+            self.days_before_last.iter().any(|&n| n + day == last) ||
+            self.nearest_weekdays.iter().any(|&n| nearest_weekday(n, first, last) == Some(day)) ||
+            (self.last_weekday && nearest_weekday(last, first, last) == Some(day))
+    }
+
+    /// Returns true if the day of week field matches. `first` is the day of week
+    /// of the first day from Sunday, and `last` is the last day of the month.
+    fn has_weekday(&self, first: u32, last: u32, day: u32) -> bool {
+        let weekday = (first + day - 1) % 7;
+        has(self.weekdays, weekday) ||
+            (has(self.last_weekdays, weekday) && day + 7 > last) ||
+            self.nth_weekdays.iter().any(|&(w, n)| w == weekday && (day - 1) / 7 + 1 == n)
+    }
+
+    /// Returns the first matching date at or after `date`.
+    fn next_date(&self, date: &NaiveDate) -> Option<NaiveDate> {
+        let (mut year, mut month, mut day) = (date.year(), date.month(), date.day());
+        let mut years = 0;
+        loop {
+            if !self.has_year(year) {
+                year = *try_opt!(self.years.iter().find(|&&y| y > year));
+                month = 1;
+                day = 1;
+            }
+            match next_in(self.months, month) {
+                Some(m) if m != month => { month = m; day = 1; }
+                Some(_) => {}
+                None => {
+                    years += 1;
+                    if years > 400 { return None; }
+                    year = try_opt!(year.checked_add(1));
+                    month = 1;
+                    day = 1;
+                    continue;
+                }
+            }
+            let first = try_opt!(NaiveDate::from_ymd_opt(year, month, 1));
+            let last = days_in_month(year, month);
+            for d in range(day, last + 1) {
+                if self.has_day(&first, last, d) { return first.with_day(d); }
+            }
+            month += 1;
+            day = 1;
+        }
+    }
+
+    /// Returns the last matching date at or before `date`.
+    fn prev_date(&self, date: &NaiveDate) -> Option<NaiveDate> {
+        let (mut year, mut month, mut day) = (date.year(), date.month(), date.day());
+        let mut years = 0;
+        loop {
+            if !self.has_year(year) {
+                year = *try_opt!(self.years.iter().rev().find(|&&y| y < year));
+                month = 12;
+                day = 31;
+            }
+            match prev_in(self.months, month) {
+                Some(m) if m != month => { month = m; day = 31; }
+                Some(_) => {}
+                None => {
+                    years += 1;
+                    if years > 400 { return None; }
+                    year = try_opt!(year.checked_sub(1));
+                    month = 12;
+                    day = 31;
+                    continue;
+                }
+            }
+            let first = try_opt!(NaiveDate::from_ymd_opt(year, month, 1));
+            let last = days_in_month(year, month);
+            for d in range(1, cmp::min(day, last) + 1).rev() {
+                if self.has_day(&first, last, d) { return first.with_day(d); }
+            }
+            month -= 1;
+            day = 31;
+        }
+    }
+
+    /// Returns the first matching time at or after `hour:min:sec` in a day.
+    fn next_time(&self, mut hour: u32, mut min: u32, mut sec: u32) -> Option<NaiveTime> {
+        loop {
+            let h = try_opt!(next_in(self.hours, hour));
+            if h != hour { hour = h; min = 0; sec = 0; }
+            match next_in(self.minutes, min) {
+                Some(m) if m != min => { min = m; sec = 0; }
+                Some(_) => {}
+                None => { hour += 1; min = 0; sec = 0; continue; }
+            }
+            match next_in(self.seconds, sec) {
+                Some(s) => return NaiveTime::from_hms_opt(hour, min, s),
+                None => { min += 1; sec = 0; }
+            }
+        }
+    }
+
+    /// Returns the last matching time at or before `hour:min:sec` in a day.
+    fn prev_time(&self, mut hour: u32, mut min: u32, mut sec: u32) -> Option<NaiveTime> {
+        loop {
+            let h = try_opt!(prev_in(self.hours, hour));
+            if h != hour { hour = h; min = 59; sec = 59; }
+            match prev_in(self.minutes, min) {
+                Some(m) if m != min => { min = m; sec = 59; }
+                Some(_) => {}
+                None if hour == 0 => return None,
+                None => { hour -= 1; min = 59; sec = 59; continue; }
+            }
+            match prev_in(self.seconds, sec) {
+                Some(s) => return NaiveTime::from_hms_opt(hour, min, s),
+                None if min == 0 && hour == 0 => return None,
+                None if min == 0 => { hour -= 1; min = 59; sec = 59; }
+                None => { min -= 1; sec = 59; }
+            }
+        }
+    }
+
+    /// Returns the first matching local date and time at or after `local`.
+    fn next_local(&self, local: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = local.date();
+        let mut time = (local.hour(), local.minute(), local.second());
+        loop {
+            let next = try_opt!(self.next_date(&date));
+            if next != date { time = (0, 0, 0); }
+            match self.next_time(time.0, time.1, time.2) {
+                Some(time) => return Some(next.and_time(time)),
+                None => {}
+            }
+            date = try_opt!(next.succ_opt());
+            time = (0, 0, 0);
+        }
+    }
+
+    /// Returns the last matching local date and time at or before `local`.
+    fn prev_local(&self, local: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut date = local.date();
+        let mut time = (local.hour(), local.minute(), local.second());
+        loop {
+            let prev = try_opt!(self.prev_date(&date));
+            if prev != date { time = (23, 59, 59); }
+            match self.prev_time(time.0, time.1, time.2) {
+                Some(time) => return Some(prev.and_time(time)),
+                None => {}
+            }
+            date = try_opt!(prev.pred_opt());
+            time = (23, 59, 59);
+        }
+    }
+
+    /// Reads a field and updates the schedule.
+    fn parse_field(&mut self, field: Field, s: &str, base: usize) -> ParseResult<()> {
+        let mut sc = Scanner { s: s.as_bytes(), pos: 0, base: base };
+        let (lo, hi, _) = field.values();
+        let any = s == "*" || (s == "?" && (field == Field::Day || field == Field::Weekday));
+        let mut set = 0;
+        loop {
+            let start = sc.pos;
+            let range = if field == Field::Day && sc.eat(b'L') {
+                if sc.eat(b'W') {
+                    self.last_weekday = true;
+                } else {
+                    let n = if sc.eat(b'-') { try!(sc.number(0, 30)) } else { 0 };
+                    self.days_before_last.push(n);
+                }
+                None
+            } else if any {
+                sc.pos = s.len();
+                Some((lo, hi, 1))
+            } else {
+                let star = sc.eat(b'*');
+                let first = if star { lo } else { try!(sc.value(field)) };
+                let last = if !star && sc.eat(b'-') { Some(try!(sc.value(field))) } else { None };
+                let single = !star && last.is_none();
+                if single && field == Field::Day && sc.eat(b'W') {
+                    self.nearest_weekdays.push(first);
+                    None
+                } else if single && field == Field::Weekday && sc.eat(b'#') {
+                    self.nth_weekdays.push((first % 7, try!(sc.number(1, 5))));
+                    None
+                } else if single && field == Field::Weekday && sc.eat(b'L') {
+                    self.last_weekdays |= 1 << (first % 7) as usize;
+                    None
+                } else {
+                    // `*` and `n/step` continue to the last value (Saturday for days of week)
+                    let top = if field == Field::Weekday { 6 } else { hi };
+                    let last = last.unwrap_or(if star || sc.peek() == Some(b'/') { top }
+                                              else { first });
+                    let step = if sc.eat(b'/') { try!(sc.number(1, hi)) } else { 1 };
+                    Some((first, last, step))
+                }
+            };
+            match range {
+                Some((first, last, _)) if first > last => {
+                    return sc.error(ParseErrorKind::OutOfRange, start);
+                }
+                Some((first, last, step)) if field == Field::Year => {
+                    let mut y = first;
+                    while y <= last { self.years.push(y as i32); y += step; }
+                }
+                Some((first, last, step)) => set |= bits(first, last, step),
+                None => {}
+            }
+            if !sc.eat(b',') { break; }
+        }
+        if sc.pos < s.len() { return sc.unexpected(); }
+
+        match field {
+            Field::Second => self.seconds = set,
+            Field::Minute => self.minutes = set,
+            Field::Hour => self.hours = set,
+            Field::Day => { self.days = set; self.any_day = any; }
+            Field::Month => self.months = set,
+            Field::Weekday => {
+                // Sunday can be either 0 or 7
+                self.weekdays = (set | set >> 7) & 0x7f;
+                self.any_weekday = any;
+            }
+            Field::Year => {
+                if any { self.years.clear(); }
+                self.years.sort();
+                self.years.dedup();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the cron expression. See the module documentation for the syntax.
+impl FromStr for Schedule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<Schedule> {
+        let base = s.len() - s.trim_left().len();
+        let source = s.trim();
+        let expr = if source.starts_with("@") {
+            match source.to_ascii_lowercase().as_slice() {
+                "@yearly" | "@annually" => "0 0 0 1 1 *",
+                "@monthly" => "0 0 0 1 * *",
+                "@weekly" => "0 0 0 * * 0",
+                "@daily" | "@midnight" => "0 0 0 * * *",
+                "@hourly" => "0 0 * * * *",
+                _ => return Err(ParseError { kind: ParseErrorKind::Invalid, pos: base }),
+            }
+        } else {
+            source
+        };
+
+        // splits the fields, remembering their positions
+        let mut fields = Vec::new();
+        let mut start = None;
+        for (i, c) in expr.char_indices().chain(Some((expr.len(), ' ')).into_iter()) {
+            match (c.is_whitespace(), start) {
+                (true, Some(first)) => {
+                    fields.push((base + first, &expr[first..i]));
+                    start = None;
+                }
+                (false, None) => start = Some(i),
+                _ => {}
+            }
+        }
+        match fields.len() {
+            0...4 => return Err(ParseError { kind: ParseErrorKind::TooShort, pos: s.len() }),
+            5 => fields.insert(0, (base, "0")), // every minute at 0 second
+            6 | 7 => {}
+            _ => return Err(ParseError { kind: ParseErrorKind::TooLong, pos: fields[7].0 }),
+        }
+
+        let mut schedule = Schedule {
+            source: source.to_string(),
+            seconds: 0, minutes: 0, hours: 0,
+            days: 0, any_day: false, days_before_last: Vec::new(), nearest_weekdays: Vec::new(),
+            last_weekday: false, months: 0,
+            weekdays: 0, any_weekday: false, last_weekdays: 0, nth_weekdays: Vec::new(),
+            years: Vec::new(),
+        };
+        static KINDS: [Field; 7] = [Field::Second, Field::Minute, Field::Hour, Field::Day,
+                                    Field::Month, Field::Weekday, Field::Year];
+        for (&(pos, field), &kind) in fields.iter().zip(KINDS.iter()) {
+            try!(schedule.parse_field(kind, field, pos));
+        }
+        Ok(schedule)
+    }
+}
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Schedule({:?})", self.source)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// An iterator over the dates and times matching `Schedule`,
+/// made by `Schedule::iter_after` or `Schedule::iter_before`.
+pub struct ScheduleIter<'a, Off> {
+    schedule: &'a Schedule,
+    current: Option<DateTime<Off>>,
+    forward: bool,
+}
+
+impl<'a, Off: Offset> Iterator for ScheduleIter<'a, Off> {
+    type Item = DateTime<Off>;
+
+    fn next(&mut self) -> Option<DateTime<Off>> {
+        let next = match self.current {
+            Some(ref current) if self.forward => self.schedule.next_after(current),
+            Some(ref current) => self.schedule.prev_before(current),
+            None => None,
+        };
+        self.current = next.clone();
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Schedule;
+    use offset::{Offset, UTC};
+    use naive::date::NaiveDate;
+    use datetime::DateTime;
+    use parse::ParseErrorKind;
+    use tz::posix::PosixTz;
+
+    fn after<Off: Offset>(expr: &str, after: DateTime<Off>, limit: usize) -> Vec<String> {
+        let schedule: Schedule = expr.parse().unwrap();
+        schedule.iter_after(&after).take(limit).map(|dt| format!("{:?}", dt)).collect()
+    }
+
+    fn before<Off: Offset>(expr: &str, before: DateTime<Off>, limit: usize) -> Vec<String> {
+        let schedule: Schedule = expr.parse().unwrap();
+        schedule.iter_before(&before).take(limit).map(|dt| format!("{:?}", dt)).collect()
+    }
+
+    fn dates(expr: &str, after: DateTime<UTC>) -> Vec<String> {
+        let schedule: Schedule = expr.parse().unwrap();
+        schedule.iter_after(&after).take(3).map(|dt| dt.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_schedule_parse() {
+        let schedule: Schedule = "  */15 9-17 * * MON-FRI\n".parse().unwrap();
+        assert_eq!(schedule.to_string(), "*/15 9-17 * * MON-FRI");
+        assert_eq!(format!("{:?}", schedule), "Schedule(\"*/15 9-17 * * MON-FRI\")");
+        assert!("@Weekly".parse::<Schedule>().is_ok());
+        assert!("0 0 0 ? JAN,jul 1-5,7L,SUN#2 2015/2".parse::<Schedule>().is_ok());
+
+        let err = |&: s: &str| { let e = s.parse::<Schedule>().unwrap_err(); (e.kind, e.pos) };
+        assert_eq!(err(""), (ParseErrorKind::TooShort, 0));
+        assert_eq!(err("* * * *"), (ParseErrorKind::TooShort, 7));
+        assert_eq!(err("* * * * * * * *"), (ParseErrorKind::TooLong, 14));
+        assert_eq!(err("60 * * * *"), (ParseErrorKind::OutOfRange, 0));
+        assert_eq!(err("* * * 13 *"), (ParseErrorKind::OutOfRange, 6));
+        assert_eq!(err("* * * * MON-SUN"), (ParseErrorKind::OutOfRange, 8));
+        assert_eq!(err("*/0 * * * *"), (ParseErrorKind::OutOfRange, 2));
+        assert_eq!(err("? * * * *"), (ParseErrorKind::Invalid, 0));
+        assert_eq!(err("* * * JANUARY *"), (ParseErrorKind::Invalid, 9));
+        assert_eq!(err("* * * * FRI#6"), (ParseErrorKind::OutOfRange, 12));
+        assert_eq!(err("* * 1W-3 * *"), (ParseErrorKind::Invalid, 6));
+        assert_eq!(err(" * * L-31 * *"), (ParseErrorKind::OutOfRange, 7));
+        assert_eq!(err("0 0 0 1 1 ? 1969"), (ParseErrorKind::OutOfRange, 12));
+        assert_eq!(err("@reboot"), (ParseErrorKind::Invalid, 0));
+    }
+
+    #[test]
+    fn test_schedule_includes() {
+        // either the day of month or the day of week matches
+        let schedule: Schedule = "0 9 13 * FRI".parse().unwrap();
+        assert!(schedule.includes(&NaiveDate::from_ymd(2015, 1, 13).and_hms(9, 0, 0)));
+        assert!(schedule.includes(&NaiveDate::from_ymd(2015, 1, 16).and_hms(9, 0, 0)));
+        assert!(!schedule.includes(&NaiveDate::from_ymd(2015, 1, 14).and_hms(9, 0, 0)));
+        assert!(!schedule.includes(&NaiveDate::from_ymd(2015, 1, 16).and_hms(9, 0, 1)));
+        assert!(!schedule.includes(&NaiveDate::from_ymd(2015, 1, 16).and_hms(10, 0, 0)));
+    }
+
+    #[test]
+    fn test_schedule_next_prev() {
+        assert_eq!(after("*/15 9-17 * * MON-FRI", UTC.ymd(2015, 1, 16).and_hms(17, 40, 0), 3),
+                   ["2015-01-16T17:45:00Z", "2015-01-19T09:00:00Z", "2015-01-19T09:15:00Z"]);
+        assert_eq!(before("*/15 9-17 * * MON-FRI", UTC.ymd(2015, 1, 19).and_hms(9, 0, 0), 3),
+                   ["2015-01-16T17:45:00Z", "2015-01-16T17:30:00Z", "2015-01-16T17:15:00Z"]);
+        assert_eq!(after("30 */20 * * * *", UTC.ymd(2015, 1, 20).and_hms_milli(17, 35, 20, 1), 3),
+                   ["2015-01-20T17:40:30Z", "2015-01-20T18:00:30Z", "2015-01-20T18:20:30Z"]);
+        assert_eq!(after("0 0 0 29 FEB ? 2015-2030", UTC.ymd(2015, 1, 1).and_hms(0, 0, 0), 5),
+                   ["2016-02-29T00:00:00Z", "2020-02-29T00:00:00Z", "2024-02-29T00:00:00Z",
+                    "2028-02-29T00:00:00Z"]);
+        assert_eq!(after("@weekly", UTC.ymd(2015, 1, 20).and_hms(0, 0, 0), 2),
+                   ["2015-01-25T00:00:00Z", "2015-02-01T00:00:00Z"]);
+        assert_eq!(before("0 0 L * ?", UTC.ymd(2015, 3, 1).and_hms(0, 0, 0), 3),
+                   ["2015-02-28T00:00:00Z", "2015-01-31T00:00:00Z", "2014-12-31T00:00:00Z"]);
+        assert!(after("0 0 30 2 *", UTC.ymd(2015, 1, 1).and_hms(0, 0, 0), 1).is_empty());
+
+        let jan1 = UTC.ymd(2015, 1, 1).and_hms(0, 0, 0);
+        assert_eq!(dates("0 0 L-2 2 *", jan1), ["2015-02-26", "2016-02-27", "2017-02-26"]);
+        assert_eq!(dates("0 0 LW * *", jan1), ["2015-01-30", "2015-02-27", "2015-03-31"]);
+        assert_eq!(dates("0 0 1W * *", jan1), ["2015-02-02", "2015-03-02", "2015-04-01"]);
+        assert_eq!(dates("0 0 1W 8 *", jan1), ["2015-08-03", "2016-08-01", "2017-08-01"]);
+        assert_eq!(dates("0 0 * * FRI#3", jan1), ["2015-01-16", "2015-02-20", "2015-03-20"]);
+        assert_eq!(dates("0 0 * * 5L", jan1), ["2015-01-30", "2015-02-27", "2015-03-27"]);
+        assert_eq!(dates("0 0 13 * FRI", jan1), ["2015-01-02", "2015-01-09", "2015-01-13"]);
+    }
+
+    #[test]
+    fn test_schedule_dst() {
+        let tz = PosixTz::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        // the UTC date and time in the offset
+        let at = |&: month: u32, day: u32, h: u32, m: u32| {
+            UTC.ymd(2015, month, day).and_hms(h, m, 0).with_offset(tz.clone())
+        };
+
+        // the local time in the gap matches at the end of the gap
+        assert_eq!(after("30 2 * * *", at(3, 7, 17, 0), 2),
+                   ["2015-03-08T03:00:00-04:00", "2015-03-09T02:30:00-04:00"]);
+        assert_eq!(after("*/30 * * * *", at(3, 8, 5, 50), 4),
+                   ["2015-03-08T01:00:00-05:00", "2015-03-08T01:30:00-05:00",
+                    "2015-03-08T03:00:00-04:00", "2015-03-08T03:30:00-04:00"]);
+
+        // the local time occurring twice matches only once
+        assert_eq!(after("*/30 * * * *", at(11, 1, 4, 50), 4),
+                   ["2015-11-01T01:00:00-04:00", "2015-11-01T01:30:00-04:00",
+                    "2015-11-01T02:00:00-05:00", "2015-11-01T02:30:00-05:00"]);
+        assert_eq!(before("*/30 * * * *", at(11, 1, 7, 0), 3),
+                   ["2015-11-01T01:30:00-04:00", "2015-11-01T01:00:00-04:00",
+                    "2015-11-01T00:30:00-04:00"]);
+        assert_eq!(after("*/30 * * * *", at(11, 1, 6, 10), 1), ["2015-11-01T02:00:00-05:00"]);
+        assert_eq!(before("*/30 * * * *", at(11, 1, 6, 10), 1), ["2015-11-01T01:30:00-04:00"]);
+    }
+}
@@ -12,12 +12,15 @@ use std::ops::{Add, Sub};
 
 use {Weekday, Datelike};
 use duration::Duration;
+use months::{Months, MonthPolicy};
 use offset::{Offset, UTC};
 use naive;
-use naive::date::NaiveDate;
+use naive::date::{NaiveDate, NaiveDateRange};
 use naive::time::NaiveTime;
 use datetime::DateTime;
-use format::DelayedFormat;
+use format::{Item, StrftimeItems, DelayedFormat};
+use locale::Locale;
+use resolve::resolve_date;
 
 /// ISO 8601 calendar date with timezone.
 #[derive(Clone)]
@@ -162,6 +165,42 @@ impl<Off:Offset> Date<Off> {
         self.date.pred_opt().map(|date| Date::from_utc(date, self.offset.clone()))
     }
 
+    /// Returns an iterator over the current and following dates, up to the last representable
+    /// date. See `NaiveDate::iter_days`.
+    #[inline]
+    pub fn iter_days(&self) -> DateRange<Off> {
+        DateRange { range: self.local().iter_days(), offset: self.offset.clone() }
+    }
+
+    /// Returns an iterator over the current date and the same day of following weeks,
+    /// up to the last representable date. See `NaiveDate::iter_weeks`.
+    #[inline]
+    pub fn iter_weeks(&self) -> DateRange<Off> {
+        DateRange { range: self.local().iter_weeks(), offset: self.offset.clone() }
+    }
+
+    /// Adds given number of months to the local date.
+    /// See `NaiveDate::checked_add_months` for the handling of the nonexistent day.
+    /// The local date is resolved in the offset as `DateTime::checked_add_months` does
+    /// for the start of the day.
+    ///
+    /// Returns `None` when the resulting date is out of range or does not exist.
+    pub fn checked_add_months(&self, months: Months, policy: MonthPolicy) -> Option<Date<Off>> {
+        self.local().checked_add_months(months, policy)
+            .and_then(|local| resolve_date(&self.offset, &local))
+    }
+
+    /// Subtracts given number of months from the local date.
+    /// See `NaiveDate::checked_add_months` for the handling of the nonexistent day.
+    /// The local date is resolved in the offset as `DateTime::checked_add_months` does
+    /// for the start of the day.
+    ///
+    /// Returns `None` when the resulting date is out of range or does not exist.
+    pub fn checked_sub_months(&self, months: Months, policy: MonthPolicy) -> Option<Date<Off>> {
+        self.local().checked_sub_months(months, policy)
+            .and_then(|local| resolve_date(&self.offset, &local))
+    }
+
     /// Retrieves an associated offset.
     #[inline]
     pub fn offset<'a>(&'a self) -> &'a Off {
@@ -182,11 +221,26 @@ impl<Off:Offset> Date<Off> {
 }
 
 impl<Off: Offset + fmt::Display> Date<Off> {
+    /// Formats the date with the specified formatting items.
+    #[inline]
+    pub fn format_with_items<'a, I>(&self, items: I) -> DelayedFormat<'a, I>
+            where I: Iterator<Item=Item<'a>> + Clone {
+        DelayedFormat::new_with_offset(Some(self.local()), None, &self.offset, items)
+    }
+
     /// Formats the date in the specified format string.
     /// See the `format` module on the supported escape sequences.
     #[inline]
-    pub fn format<'a>(&'a self, fmt: &'a str) -> DelayedFormat<'a> {
-        DelayedFormat::new_with_offset(Some(self.local()), None, &self.offset, fmt)
+    pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new(fmt))
+    }
+
+    /// Formats the date in the specified format string and locale.
+    /// The names, AM/PM markers and the layouts of `%c`, `%x`, `%X` and `%r` follow the locale.
+    #[inline]
+    pub fn format_localized<'a>(&self, fmt: &'a str,
+                                locale: &'a Locale<'a>) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new_with_locale(fmt, locale)).with_locale(locale)
     }
 }
 
@@ -286,6 +340,22 @@ impl<Off:Offset> Sub<Duration> for Date<Off> {
     fn sub(self, rhs: Duration) -> Date<Off> { self.add(-rhs) }
 }
 
+impl<Off:Offset> Add<Months> for Date<Off> {
+    type Output = Date<Off>;
+
+    fn add(self, rhs: Months) -> Date<Off> {
+        self.checked_add_months(rhs, MonthPolicy::Clamp).expect("`Date + Months` out of range")
+    }
+}
+
+impl<Off:Offset> Sub<Months> for Date<Off> {
+    type Output = Date<Off>;
+
+    fn sub(self, rhs: Months) -> Date<Off> {
+        self.checked_sub_months(rhs, MonthPolicy::Clamp).expect("`Date - Months` out of range")
+    }
+}
+
 impl<Off: Offset> fmt::Debug for Date<Off> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}{:?}", self.local(), self.offset)
@@ -298,6 +368,57 @@ impl<Off: Offset + fmt::Display> fmt::Display for Date<Off> {
     }
 }
 
+/// Returns an iterator over the local dates from `start` (inclusive) to `end` (exclusive),
+/// in the timezone of `start`. See `naive::date::range`.
+pub fn range<Off:Offset>(start: Date<Off>, end: Date<Off>) -> DateRange<Off> {
+    DateRange { range: naive::date::range(start.local(), end.local()), offset: start.offset }
+}
+
+/// An iterator over the local dates in the same timezone at the fixed interval of days.
+/// This is made by `range`, `Date::iter_days` or `Date::iter_weeks`;
+/// see `NaiveDateRange` for details.
+///
+/// Each date gets its own offset from the timezone (which may change, e.g. across DST),
+/// resolved as `Date::checked_add_months` does.
+#[derive(Clone)]
+pub struct DateRange<Off> {
+    range: NaiveDateRange,
+    offset: Off,
+}
+
+impl<Off:Offset> DateRange<Off> {
+    /// Changes the interval between dates to `step`.
+    /// See `NaiveDateRange::step_by`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `step` is not a positive whole number of days.
+    pub fn step_by(self, step: Duration) -> DateRange<Off> {
+        DateRange { range: self.range.step_by(step), offset: self.offset }
+    }
+}
+
+impl<Off:Offset> Iterator for DateRange<Off> {
+    type Item = Date<Off>;
+
+    fn next(&mut self) -> Option<Date<Off>> {
+        self.range.next().and_then(|date| resolve_date(&self.offset, &date))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<Off:Offset> DoubleEndedIterator for DateRange<Off> {
+    fn next_back(&mut self) -> Option<Date<Off>> {
+        self.range.next_back().and_then(|date| resolve_date(&self.offset, &date))
+    }
+}
+
+impl<Off:Offset> ExactSizeIterator for DateRange<Off> {}
+
 #[cfg(test)]
 mod tests {
     use std::fmt;
@@ -309,7 +430,8 @@ mod tests {
     use super::Date;
     use time::Time;
     use datetime::DateTime;
-    use offset::{Offset, LocalResult};
+    use offset::{Offset, LocalResult, UTC};
+    use tz::posix::PosixTz;
 
     #[derive(Copy, Clone, PartialEq, Eq)]
     struct UTC1y; // same to UTC but with an offset of 365 days
@@ -349,5 +471,36 @@ mod tests {
         assert_eq!(format!("{:?}", UTC1y.ymd(2012, 3, 4).and_hms(5, 6, 7)),
                    "2012-03-04T05:06:07+8760:00".to_string());
     }
+
+    #[test]
+    fn test_date_range() {
+        let ymd = |&: y,m,d| UTC.ymd(y, m, d);
+        assert_eq!(super::range(ymd(2012, 2, 27), ymd(2012, 3, 2)).collect::<Vec<_>>(),
+                   [ymd(2012, 2, 27), ymd(2012, 2, 28), ymd(2012, 2, 29), ymd(2012, 3, 1)]);
+        assert_eq!(super::range(ymd(2012, 2, 27), ymd(2012, 3, 2)).rev().next(),
+                   Some(ymd(2012, 3, 1)));
+        assert_eq!(ymd(2012, 2, 27).iter_weeks().take(2).collect::<Vec<_>>(),
+                   [ymd(2012, 2, 27), ymd(2012, 3, 5)]);
+        assert_eq!(ymd(2012, 2, 28).iter_days().nth(1), Some(ymd(2012, 2, 29)));
+        assert_eq!(super::range(ymd(2012, 1, 1), ymd(2013, 1, 1))
+                       .step_by(Duration::days(2)).len(), 183);
+
+        // the offset is made for each local date
+        assert_eq!(super::range(UTC1y.ymd(2012, 2, 28), UTC1y.ymd(2012, 3, 1)).last(),
+                   Some(UTC1y.ymd(2012, 2, 29)));
+        let tz = PosixTz::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        let dates = super::range(tz.ymd(2015, 3, 7), tz.ymd(2015, 3, 10)).rev()
+                        .map(|d| format!("{:?}", d)).collect::<Vec<_>>();
+        assert_eq!(dates, ["2015-03-09-04:00", "2015-03-08-04:00", "2015-03-07-05:00"]);
+        let dates = tz.ymd(2015, 10, 31).iter_days().take(2)
+                      .map(|d| format!("{:?}", d)).collect::<Vec<_>>();
+        assert_eq!(dates, ["2015-10-31-04:00", "2015-11-01-05:00"]);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_date_range_step_by_zero() {
+        UTC.ymd(2012, 2, 27).iter_days().step_by(Duration::zero());
+    }
 }
 
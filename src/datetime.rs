@@ -11,12 +11,16 @@ use std::cmp::Ordering;
 use std::ops::{Add, Sub};
 
 use {Weekday, Timelike, Datelike};
-use offset::Offset;
+use offset::{Offset, FixedOffset};
 use duration::Duration;
+use months::{Months, MonthPolicy};
 use naive::datetime::NaiveDateTime;
 use time::Time;
 use date::Date;
-use format::DelayedFormat;
+use format::{Item, StrftimeItems, DelayedFormat};
+use locale::Locale;
+use parse::{Parsed, ParseResult, parse_and_resolve};
+use resolve::resolve_datetime;
 
 /// ISO 8601 combined date and time with timezone.
 #[derive(Clone)]
@@ -28,8 +32,11 @@ pub struct DateTime<Off> {
 impl<Off:Offset> DateTime<Off> {
     /// Makes a new `DateTime` with given *UTC* datetime and offset.
     /// The local datetime should be constructed via the `Offset` trait.
+    ///
+    /// The offset is adjusted to the one in effect at given datetime (see `Offset::at_utc`).
     #[inline]
     pub fn from_utc(datetime: NaiveDateTime, offset: Off) -> DateTime<Off> {
+        let offset = offset.at_utc(&datetime);
         DateTime { datetime: datetime, offset: offset }
     }
 
@@ -51,6 +58,28 @@ impl<Off:Offset> DateTime<Off> {
         self.datetime.num_seconds_from_unix_epoch()
     }
 
+    /// Adds given number of months to the local date and time.
+    /// See `NaiveDate::checked_add_months` for the handling of the nonexistent day.
+    /// The local date and time occurring twice (e.g. when the daylight saving time ends)
+    /// is resolved to the earlier one, and the nonexistent one is resolved to the end of the gap.
+    ///
+    /// Returns `None` when the resulting date is out of range or does not exist.
+    pub fn checked_add_months(&self, months: Months, policy: MonthPolicy) -> Option<DateTime<Off>> {
+        self.local().checked_add_months(months, policy)
+            .and_then(|local| resolve_datetime(&self.offset, &local))
+    }
+
+    /// Subtracts given number of months from the local date and time.
+    /// See `NaiveDate::checked_add_months` for the handling of the nonexistent day.
+    /// The local date and time occurring twice (e.g. when the daylight saving time ends)
+    /// is resolved to the earlier one, and the nonexistent one is resolved to the end of the gap.
+    ///
+    /// Returns `None` when the resulting date is out of range or does not exist.
+    pub fn checked_sub_months(&self, months: Months, policy: MonthPolicy) -> Option<DateTime<Off>> {
+        self.local().checked_sub_months(months, policy)
+            .and_then(|local| resolve_datetime(&self.offset, &local))
+    }
+
     /// Retrieves an associated offset.
     #[inline]
     pub fn offset<'a>(&'a self) -> &'a Off {
@@ -71,12 +100,40 @@ impl<Off:Offset> DateTime<Off> {
 }
 
 impl<Off: Offset + fmt::Display> DateTime<Off> {
+    /// Formats the combined date and time with the specified formatting items.
+    #[inline]
+    pub fn format_with_items<'a, I>(&self, items: I) -> DelayedFormat<'a, I>
+            where I: Iterator<Item=Item<'a>> + Clone {
+        let local = self.local();
+        DelayedFormat::new_with_offset(Some(local.date()), Some(local.time()), &self.offset, items)
+    }
+
     /// Formats the combined date and time in the specified format string.
     /// See the `format` module on the supported escape sequences.
     #[inline]
-    pub fn format<'a>(&'a self, fmt: &'a str) -> DelayedFormat<'a> {
-        let local = self.local();
-        DelayedFormat::new_with_offset(Some(local.date()), Some(local.time()), &self.offset, fmt)
+    pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new(fmt))
+    }
+
+    /// Formats the combined date and time in the specified format string and locale.
+    /// The names, AM/PM markers and the layouts of `%c`, `%x`, `%X` and `%r` follow the locale.
+    #[inline]
+    pub fn format_localized<'a>(&self, fmt: &'a str,
+                                locale: &'a Locale<'a>) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new_with_locale(fmt, locale)).with_locale(locale)
+    }
+}
+
+impl DateTime<FixedOffset> {
+    /// Parses a string with the specified format string and makes a new `DateTime`
+    /// with the parsed offset. See the `format` module on the supported escape sequences.
+    /// The offset (`%z`) is required.
+    ///
+    /// Fails when the string does not match the format string,
+    /// or the parsed fields are insufficient, inconsistent or out of range.
+    pub fn parse_from_str(s: &str, fmt: &str) -> ParseResult<DateTime<FixedOffset>> {
+        parse_and_resolve(s, StrftimeItems::new(fmt),
+                          |&: parsed: &Parsed| parsed.to_datetime())
     }
 }
 
@@ -190,7 +247,7 @@ impl<Off:Offset> Add<Duration> for DateTime<Off> {
     type Output = DateTime<Off>;
 
     fn add(self, rhs: Duration) -> DateTime<Off> {
-        DateTime { datetime: self.datetime + rhs, offset: self.offset }
+        DateTime::from_utc(self.datetime + rhs, self.offset)
     }
 }
 
@@ -207,6 +264,22 @@ impl<Off:Offset> Sub<Duration> for DateTime<Off> {
     fn sub(self, rhs: Duration) -> DateTime<Off> { self.add(-rhs) }
 }
 
+impl<Off:Offset> Add<Months> for DateTime<Off> {
+    type Output = DateTime<Off>;
+
+    fn add(self, rhs: Months) -> DateTime<Off> {
+        self.checked_add_months(rhs, MonthPolicy::Clamp).expect("`DateTime + Months` out of range")
+    }
+}
+
+impl<Off:Offset> Sub<Months> for DateTime<Off> {
+    type Output = DateTime<Off>;
+
+    fn sub(self, rhs: Months) -> DateTime<Off> {
+        self.checked_sub_months(rhs, MonthPolicy::Clamp).expect("`DateTime - Months` out of range")
+    }
+}
+
 impl<Off: Offset> fmt::Debug for DateTime<Off> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}{:?}", self.local(), self.offset)
@@ -221,9 +294,12 @@ impl<Off: Offset + fmt::Display> fmt::Display for DateTime<Off> {
 
 #[cfg(test)]
 mod tests {
+    use super::DateTime;
     use {Datelike};
     use duration::Duration;
+    use months::{Months, MonthPolicy};
     use offset::{Offset, UTC, Local, FixedOffset};
+    use tz::posix::PosixTz;
 
     #[test]
     #[allow(non_snake_case)]
@@ -251,11 +327,90 @@ mod tests {
         assert!(*EDT.ymd(2014, 5, 6).and_hms(7, 8, 9).offset() != EST);
     }
 
+    #[test]
+    fn test_datetime_add_months() {
+        let kst = FixedOffset::east(9*3600);
+        let dt = kst.ymd(2014, 1, 31).and_hms(7, 8, 9);                  // 2014-01-30 in UTC
+        assert_eq!(dt + Months::new(1), kst.ymd(2014, 2, 28).and_hms(7, 8, 9));
+        assert_eq!(dt - Months::new(2), kst.ymd(2013, 11, 30).and_hms(7, 8, 9));
+        assert_eq!(dt.checked_add_months(Months::new(1), MonthPolicy::Fail), None);
+        assert_eq!(dt.checked_add_months(Months::new(1), MonthPolicy::Overflow),
+                   Some(kst.ymd(2014, 3, 3).and_hms(7, 8, 9)));
+        assert_eq!(dt.date() + Months::years(1), kst.ymd(2015, 1, 31));
+        assert_eq!(UTC.ymd(2014, 1, 30).checked_add_months(Months::new(1), MonthPolicy::Clamp),
+                   Some(UTC.ymd(2014, 2, 28)));
+    }
+
+    #[test]
+    fn test_datetime_add_months_dst() {
+        let tz = PosixTz::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        let at = |&: month: u32, day: u32, h: u32, m: u32| {
+            UTC.ymd(2015, month, day).and_hms(h, m, 0).with_offset(tz.clone())
+        };
+
+        // 2015-03-08 02:30 does not exist, resolved to the end of the gap
+        let dt = at(2, 8, 7, 30);
+        assert_eq!(format!("{:?}", dt), "2015-02-08T02:30:00-05:00");
+        assert_eq!(format!("{:?}", dt + Months::new(1)), "2015-03-08T03:00:00-04:00");
+        assert_eq!(format!("{:?}", at(4, 8, 6, 30) - Months::new(1)),
+                   "2015-03-08T03:00:00-04:00");
+
+        // 2015-11-01 01:30 occurs twice, resolved to the earlier one
+        let dt = at(10, 1, 5, 30);
+        assert_eq!(format!("{:?}", dt), "2015-10-01T01:30:00-04:00");
+        assert_eq!(format!("{:?}", dt + Months::new(1)), "2015-11-01T01:30:00-04:00");
+        assert_eq!(format!("{:?}", at(12, 1, 6, 30) - Months::new(1)),
+                   "2015-11-01T01:30:00-04:00");
+
+        assert_eq!(format!("{:?}", dt.date() + Months::new(1)), "2015-11-01-05:00"); // at noon
+        assert_eq!(format!("{:?}", dt.date() + Months::new(2)), "2015-12-01-05:00");
+    }
+
+    #[test]
+    fn test_datetime_format_offset() {
+        let dt = FixedOffset::east(9*3600 + 30*60).ymd(2015, 1, 20).and_hms(17, 35, 20);
+        assert_eq!(dt.format("%z,%:z,%::z,%#z").to_string(), "+0930,+09:30,+09:30:00,+0930");
+        let dt = FixedOffset::west(3*3600 + 25).ymd(2015, 1, 20).and_hms(17, 35, 20);
+        assert_eq!(dt.format("%z,%:z,%::z").to_string(), "-0300,-03:00,-03:00:25");
+        assert_eq!(dt.format("%s").to_string(), "1421786145");
+        assert_eq!(dt.format("%s").to_string(), dt.num_seconds_from_unix_epoch().to_string());
+        assert_eq!(UTC.ymd(1969, 12, 31).and_hms(23, 59, 0).format("%s").to_string(), "-60");
+    }
+
     #[test]
     fn test_datetime_fmt_with_local() {
         // if we are not around the year boundary, local and UTC date should have the same year
         let dt = Local::now().with_month(5).unwrap();
         assert_eq!(dt.format("%Y").to_string(), dt.with_offset(UTC).format("%Y").to_string());
     }
+
+    #[test]
+    fn test_datetime_parse_from_str() {
+        let ymdhms = |&: y,m,d,h,n,s,off| FixedOffset::east(off).ymd(y,m,d).and_hms(h,n,s);
+        let parse = |&: s, fmt| DateTime::<FixedOffset>::parse_from_str(s, fmt);
+
+        let dt = parse("Tue, 20 Jan 2015 17:35:20 -0800", "%a, %d %b %Y %H:%M:%S %z").unwrap();
+        assert_eq!(dt, ymdhms(2015, 1, 20, 17, 35, 20, -8*3600));
+        assert_eq!(format!("{:?}", dt), "2015-01-20T17:35:20-08:00");
+        assert_eq!(parse("2014-05-06T07:08:09+04:00", "%+"),
+                   Ok(ymdhms(2014, 5, 6, 7, 8, 9, 4*3600)));
+        assert_eq!(parse("2014-05-06 07:08:09 EDT +0400", "%F %T %Z %z"),
+                   Ok(ymdhms(2014, 5, 6, 7, 8, 9, 4*3600)));
+        assert!(parse("2014-05-06 07:08:09", "%F %T").is_err()); // no offset
+        assert!(parse("2014-05-06 07:08:09 +2400", "%F %T %z").is_err()); // invalid offset
+        assert!(parse("2014-05-06 07:08:09 +04:00", "%F %T %z").is_err()); // colon not allowed
+        assert!(parse("2014-05-06 07:08:09 +0400", "%F %T %:z").is_err()); // colon required
+        assert_eq!(parse("2014-05-06 07:08:09 +04:00", "%F %T %#z"),
+                   Ok(ymdhms(2014, 5, 6, 7, 8, 9, 4*3600)));
+        assert_eq!(parse("2014-05-06 07:08:09 +0400", "%F %T %#z"),
+                   Ok(ymdhms(2014, 5, 6, 7, 8, 9, 4*3600)));
+        assert_eq!(parse("2014-05-06 07:08:09 -04:00:30", "%F %T %::z"),
+                   Ok(ymdhms(2014, 5, 6, 7, 8, 9, -(4*3600 + 30))));
+        assert_eq!(parse("1399374489 +0400", "%s %z"),
+                   Ok(ymdhms(2014, 5, 6, 15, 8, 9, 4*3600)));
+        assert_eq!(parse("1399374489 +0400 15", "%s %z %H"),
+                   Ok(ymdhms(2014, 5, 6, 15, 8, 9, 4*3600)));
+        assert!(parse("1399374489 +0400 11", "%s %z %H").is_err()); // inconsistent hour
+    }
 }
 
@@ -4,213 +4,879 @@
 
 /*!
  * Formatting utilities for date and time.
+ *
+ * A format string is compiled into a sequence of formatting items (`Item`)
+ * by `StrftimeItems`, which can be reused for formatting and parsing.
+ * Formatting with an invalid format string fails with `fmt::Error`;
+ * `DelayedFormat::validate` tells which specifier is wrong and why.
+ * The GNU flags `-`, `_`, `0` and `^` change the padding or case of a field (e.g. `%-d`).
+ * The fractional seconds can be printed with a fixed (`%.3f`, `%6f`) or
+ * the shortest (`%.f`) precision in addition to the nanoseconds of `%f`.
+ * The offset is printed as `+hhmm` (`%z`), `+hh:mm` (`%:z`) or `+hh:mm:ss` (`%::z`).
+ * The names, AM/PM markers and the layouts of `%c`, `%x`, `%X` and `%r` are in English
+ * unless a `Locale` is given (e.g. `format_localized` methods).
+ * The same `%` specifiers are also accepted by the `parse_from_str` methods;
+ * see the `parse` module for details.
+ * `fmt_iso8601_duration` prints a `Duration` in the ISO 8601 form (e.g. `P3DT4H5M6.5S`).
  */
 
 use std::fmt;
+use std::error::Error;
 
 use {Datelike, Timelike};
 use duration::Duration;
 use offset::Offset;
 use naive::date::NaiveDate;
 use naive::time::NaiveTime;
+use locale::{self, Locale};
 
-/// The internal workhouse for `DelayedFormat`.
-fn format(w: &mut fmt::Formatter, date: Option<&NaiveDate>, time: Option<&NaiveTime>,
-          off: Option<&(String, Duration)>, fmt: &str) -> fmt::Result {
-    static SHORT_MONTHS: [&'static str; 12] =
-        ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
-    static LONG_MONTHS: [&'static str; 12] =
-        ["January", "February", "March", "April", "May", "June",
-         "July", "August", "September", "October", "November", "December"];
-    static SHORT_WEEKDAYS: [&'static str; 7] =
-        ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
-    static LONG_WEEKDAYS: [&'static str; 7] =
-        ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
-
-    let mut parts = fmt.split('%');
-    match parts.next() {
-        Some(first) => try!(write!(w, "{}", first)),
-        None => return Ok(()),
-    }
-
-    let mut last_was_percent = false;
-    for part in parts {
-        if last_was_percent { // `%%<part>`
-            last_was_percent = false;
-            try!(write!(w, "%{}", part));
-            continue;
+/// Abbreviated English month names (`%b`), starting from January.
+pub static SHORT_MONTHS: [&'static str; 12] =
+    ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+/// Full English month names (`%B`), starting from January.
+pub static LONG_MONTHS: [&'static str; 12] =
+    ["January", "February", "March", "April", "May", "June",
+     "July", "August", "September", "October", "November", "December"];
+/// Abbreviated English weekday names (`%a`), starting from Monday.
+pub static SHORT_WEEKDAYS: [&'static str; 7] =
+    ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+/// Full English weekday names (`%A`), starting from Monday.
+pub static LONG_WEEKDAYS: [&'static str; 7] =
+    ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// Padding characters for numeric items.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum Pad {
+    /// No padding.
+    None,
+    /// Zero (`0`) padding.
+    Zero,
+    /// Space padding.
+    Space,
+}
+
+/// Numeric item types.
+/// They have associated formatting width (the maximal width when parsing) and the value range.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum Numeric {
+    /// Full Gregorian year (`%Y`, width 4). Parsing accepts more digits with an explicit sign.
+    Year,
+    /// Gregorian year divided by 100 (`%C`, width 2, from 0 to 99).
+    YearDiv100,
+    /// Gregorian year modulo 100 (`%y`, width 2, from 0 to 99).
+    YearMod100,
+    /// Year in the ISO week date (`%G`, width 4).
+    IsoYear,
+    /// Year in the ISO week date modulo 100 (`%g`, width 2, from 0 to 99).
+    IsoYearMod100,
+    /// Month (`%m`, width 2, from 1 to 12).
+    Month,
+    /// Day of month (`%d` and `%e`, width 2, from 1 to 31).
+    Day,
+    /// Week number, where the week 1 starts at the first Sunday of January
+    /// (`%U`, width 2, from 0 to 53).
+    WeekFromSun,
+    /// Week number, where the week 1 starts at the first Monday of January
+    /// (`%W`, width 2, from 0 to 53).
+    WeekFromMon,
+    /// Week number in the ISO week date (`%V`, width 2, from 1 to 53).
+    IsoWeek,
+    /// Day of week, where Sunday = 0 and Saturday = 6 (`%w`, width 1).
+    NumDaysFromSun,
+    /// Day of week, where Monday = 1 and Sunday = 7 (`%u`, width 1).
+    WeekdayFromMon,
+    /// Day of year (`%j`, width 3, from 1 to 366).
+    Ordinal,
+    /// Hour number in the 24-hour clocks (`%H` and `%k`, width 2, from 0 to 23).
+    Hour,
+    /// Hour number in the 12-hour clocks (`%I` and `%l`, width 2, from 1 to 12).
+    Hour12,
+    /// The number of minutes since the last whole hour (`%M`, width 2, from 0 to 59).
+    Minute,
+    /// The number of seconds since the last whole minute
+    /// (`%S`, width 2, from 0 to 59; or 60 for the leap second).
+    Second,
+    /// The number of nanoseconds since the last whole second (`%f`, width 9).
+    /// The leap second is excluded. Parsing reads any number of digits as a fraction.
+    Nanosecond,
+    /// The number of non-leap seconds since January 1, 1970 0:00:00 UTC (`%s`).
+    /// The date and time without the offset are assumed to be in UTC.
+    Timestamp,
+}
+
+/// Fixed-format item types.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum Fixed {
+    /// Abbreviated month names (`%b` and `%h`).
+    /// Parsing accepts the full names as well, ignoring the case.
+    ShortMonthName,
+    /// Full month names (`%B`).
+    /// Parsing accepts the abbreviated names as well, ignoring the case.
+    LongMonthName,
+    /// Abbreviated day of the week names (`%a`).
+    /// Parsing accepts the full names as well, ignoring the case.
+    ShortWeekdayName,
+    /// Full day of the week names (`%A`).
+    /// Parsing accepts the abbreviated names as well, ignoring the case.
+    LongWeekdayName,
+    /// AM/PM in lower case (`%P`). Parsing ignores the case.
+    LowerAmPm,
+    /// AM/PM in upper case (`%p`). Parsing ignores the case.
+    UpperAmPm,
+    /// Timezone name (`%Z`). Parsing skips it, as it cannot be resolved into the offset.
+    TimezoneName,
+    /// Offset from the local time to UTC, `+hhmm` (`%z`).
+    TimezoneOffset,
+    /// Offset from the local time to UTC, `+hh:mm` (`%:z` and the offset in `%+`).
+    TimezoneOffsetColon,
+    /// Offset from the local time to UTC with seconds, `+hh:mm:ss` (`%::z`).
+    TimezoneOffsetDoubleColon,
+    /// Offset from the local time to UTC, `+hhmm` (`%#z`).
+    /// Parsing accepts both `+hhmm` and `+hh:mm`.
+    TimezoneOffsetPermissive,
+    /// Fractional seconds with the leading dot and as few digits as possible (3, 6 or 9),
+    /// or nothing when the fraction is zero (`%.f`). Parsing reads any number of digits.
+    Nanosecond,
+    /// Fractional seconds with the leading dot and 3 digits (`%.3f`).
+    /// Parsing reads any number of digits.
+    Nanosecond3,
+    /// Fractional seconds with the leading dot and 6 digits (`%.6f`).
+    /// Parsing reads any number of digits.
+    Nanosecond6,
+    /// Fractional seconds with the leading dot and 9 digits (`%.9f`).
+    /// Parsing reads any number of digits.
+    Nanosecond9,
+    /// Fractional seconds with 3 digits and without the dot (`%3f`).
+    Nanosecond3NoDot,
+    /// Fractional seconds with 6 digits and without the dot (`%6f`).
+    Nanosecond6NoDot,
+    /// Fractional seconds with 9 digits and without the dot (`%9f`).
+    Nanosecond9NoDot,
+}
+
+/// A single formatting item. A format string is compiled into a sequence of items.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum Item<'a> {
+    /// A literally printed and parsed text.
+    Literal(&'a str),
+    /// Whitespace. Printed literally; parsing skips any amount of whitespace.
+    Space(&'a str),
+    /// Numeric item. Parsing ignores the padding and allows the preceding whitespace.
+    Numeric(Numeric, Pad),
+    /// Fixed-format item.
+    Fixed(Fixed),
+    /// Fixed-format item printed in upper case (`^` flag). Parsing ignores the case.
+    Upper(Fixed),
+    /// An invalid specifier. Formatting and parsing always fail.
+    Error,
+}
+
+macro_rules! lit  { ($x:expr) => (Item::Literal($x)) }
+macro_rules! sp   { ($x:expr) => (Item::Space($x)) }
+macro_rules! num  { ($x:ident) => (Item::Numeric(Numeric::$x, Pad::None)) }
+macro_rules! num0 { ($x:ident) => (Item::Numeric(Numeric::$x, Pad::Zero)) }
+macro_rules! nums { ($x:ident) => (Item::Numeric(Numeric::$x, Pad::Space)) }
+macro_rules! fix  { ($x:ident) => (Item::Fixed(Fixed::$x)) }
+
+// composite specifiers, expanded into the individual items
+static D_ITEMS: &'static [Item<'static>] =                          // `%D`, `%x`
+    &[num0!(Month), lit!("/"), num0!(Day), lit!("/"), num0!(YearMod100)];
+static F_ITEMS: &'static [Item<'static>] =                          // `%F`
+    &[num0!(Year), lit!("-"), num0!(Month), lit!("-"), num0!(Day)];
+static V_ITEMS: &'static [Item<'static>] =                          // `%v`
+    &[nums!(Day), lit!("-"), fix!(ShortMonthName), lit!("-"), num0!(Year)];
+static R_ITEMS: &'static [Item<'static>] =                          // `%R`
+    &[num0!(Hour), lit!(":"), num0!(Minute)];
+static T_ITEMS: &'static [Item<'static>] =                          // `%T`, `%X`
+    &[num0!(Hour), lit!(":"), num0!(Minute), lit!(":"), num0!(Second)];
+static LOWER_R_ITEMS: &'static [Item<'static>] =                    // `%r`
+    &[num0!(Hour12), lit!(":"), num0!(Minute), lit!(":"), num0!(Second),
+      sp!(" "), fix!(UpperAmPm)];
+static C_ITEMS: &'static [Item<'static>] =                          // `%c`
+    &[fix!(ShortWeekdayName), sp!(" "), fix!(ShortMonthName), sp!(" "), nums!(Day), sp!(" "),
+      num0!(Hour), lit!(":"), num0!(Minute), lit!(":"), num0!(Second), sp!(" "), num0!(Year)];
+static PLUS_ITEMS: &'static [Item<'static>] =                       // `%+`
+    &[num!(Year), lit!("-"), num0!(Month), lit!("-"), num0!(Day), lit!("T"),
+      num0!(Hour), lit!(":"), num0!(Minute), lit!(":"), num0!(Second),
+      fix!(TimezoneOffsetColon)];
+
+/// The specifiers longer than a single character, excluding `%` and flags.
+static LONG_SPECS: [(&'static str, Fixed); 10] = [
+    (".f", Fixed::Nanosecond),
+    (".3f", Fixed::Nanosecond3),
+    (".6f", Fixed::Nanosecond6),
+    (".9f", Fixed::Nanosecond9),
+    ("3f", Fixed::Nanosecond3NoDot),
+    ("6f", Fixed::Nanosecond6NoDot),
+    ("9f", Fixed::Nanosecond9NoDot),
+    (":z", Fixed::TimezoneOffsetColon),
+    ("::z", Fixed::TimezoneOffsetDoubleColon),
+    ("#z", Fixed::TimezoneOffsetPermissive),
+];
+
+/// An iterator over the items of a format string with the `%` specifiers (like `strftime`).
+///
+/// The composite specifiers (e.g. `%F`) are expanded into the individual items,
+/// and an unknown specifier or a stray `%` is returned as `Item::Error`.
+/// Collecting the items once avoids scanning the format string on every use.
+///
+/// As in GNU `strftime`, flags can follow `%`: `-` suppresses the padding (`%-d`),
+/// `_` pads with spaces (`%_m`), `0` pads with zeroes (`%0e`), and `^` prints the names
+/// in upper case (`%^a`). The flags are ignored for the composite specifiers,
+/// and the specifiers longer than a single character (e.g. `%-.3f`) do not accept them.
+#[derive(Clone, Show)]
+pub struct StrftimeItems<'a> {
+    /// The remaining portion of the format string.
+    remainder: &'a str,
+    /// The remaining items of the composite specifier being expanded.
+    recons: &'static [Item<'static>],
+    /// The locale for the layouts of `%c`, `%x`, `%X` and `%r`, if any.
+    locale: Option<&'a Locale<'a>>,
+    /// The rest of the format string while a layout from the locale is being scanned.
+    resume: Option<&'a str>,
+}
+
+impl<'a> StrftimeItems<'a> {
+    /// Makes a new `StrftimeItems` from the format string.
+    pub fn new(fmt: &'a str) -> StrftimeItems<'a> {
+        StrftimeItems { remainder: fmt, recons: &[], locale: None, resume: None }
+    }
+
+    /// Makes a new `StrftimeItems` from the format string,
+    /// where `%c`, `%x`, `%X` and `%r` are expanded into the layouts of given locale.
+    /// The layouts themselves cannot refer to the locale; such specifiers in them
+    /// are expanded as `StrftimeItems::new` does.
+    pub fn new_with_locale(fmt: &'a str, locale: &'a Locale<'a>) -> StrftimeItems<'a> {
+        StrftimeItems { remainder: fmt, recons: &[], locale: Some(locale), resume: None }
+    }
+
+    /// Expands the composite specifier into the layout from the locale if any,
+    /// or into the default items otherwise.
+    fn expand<F>(&mut self, default: &'static [Item<'static>], layout: F) -> Option<Item<'a>>
+            where F: FnOnce(&'a Locale<'a>) -> &'a str {
+        match self.locale {
+            Some(locale) if self.resume.is_none() => {
+                self.resume = Some(self.remainder);
+                self.remainder = layout(locale);
+            }
+            _ => self.recons = default,
+        }
+        self.next()
+    }
+}
+
+impl<'a> Iterator for StrftimeItems<'a> {
+    type Item = Item<'a>;
+
+    fn next(&mut self) -> Option<Item<'a>> {
+        // the remaining items of the composite specifier come first
+        if !self.recons.is_empty() {
+            let item = self.recons[0];
+            self.recons = &self.recons[1..];
+            return Some(item);
+        }
+
+        // the rest of the format string comes after the layout from the locale
+        if self.remainder.is_empty() {
+            match self.resume.take() {
+                Some(resume) => self.remainder = resume,
+                None => return None,
+            }
         }
 
-        let (head, tail) = match part.slice_shift_char() {
-            Some((head, tail)) => (Some(head), tail),
-            None => (None, ""),
+        let (head, tail) = match self.remainder.slice_shift_char() {
+            Some(head_and_tail) => head_and_tail,
+            None => return None,
         };
-        match (head, date, time, off) {
-            // year
-            (Some('Y'), Some(d), _, _) => try!(write!(w, "{}", d.year())),
-            (Some('C'), Some(d), _, _) => try!(write!(w, "{:02}", d.year() / 100)),
-            (Some('y'), Some(d), _, _) => try!(write!(w, "{:02}", d.year() % 100)),
-            (Some('G'), Some(d), _, _) => try!(write!(w, "{:04}", d.isoweekdate().0)),
-            (Some('g'), Some(d), _, _) => try!(write!(w, "{:02}", d.isoweekdate().0 % 100)),
-
-            // month
-            (Some('m'), Some(d), _, _) => try!(write!(w, "{:02}", d.month())),
-            (Some('b'), Some(d), _, _) | (Some('h'), Some(d), _, _) =>
-                try!(write!(w, "{}", SHORT_MONTHS[d.month0() as usize])),
-            (Some('B'), Some(d), _, _) =>
-                try!(write!(w, "{}", LONG_MONTHS[d.month0() as usize])),
-
-            // day of month
-            (Some('d'), Some(d), _, _) => try!(write!(w, "{:02}", d.day())),
-            (Some('e'), Some(d), _, _) => try!(write!(w, "{:2}", d.day())),
-
-            // week
-            (Some('U'), Some(d), _, _) =>
-                try!(write!(w, "{:02}", (d.ordinal() - d.weekday().num_days_from_sunday()
-                                                     + 7) / 7)),
-            (Some('W'), Some(d), _, _) =>
-                try!(write!(w, "{:02}", (d.ordinal() - d.weekday().num_days_from_monday()
-                                                     + 7) / 7)),
-            (Some('V'), Some(d), _, _) => try!(write!(w, "{:02}", d.isoweekdate().1)),
-
-            // day of week
-            (Some('a'), Some(d), _, _) =>
-                try!(write!(w, "{}", SHORT_WEEKDAYS[d.weekday().num_days_from_monday() as usize])),
-            (Some('A'), Some(d), _, _) =>
-                try!(write!(w, "{}", LONG_WEEKDAYS[d.weekday().num_days_from_monday() as usize])),
-            (Some('w'), Some(d), _, _) => try!(write!(w, "{}", d.weekday().num_days_from_sunday())),
-            (Some('u'), Some(d), _, _) => try!(write!(w, "{}", d.weekday().number_from_monday())),
-
-            // day of year
-            (Some('j'), Some(d), _, _) => try!(write!(w, "{:03}", d.ordinal())),
-
-            // combined date
-            (Some('D'), Some(d), _, _) | (Some('x'), Some(d), _, _) => // `%m/%d/%y`
-                try!(write!(w, "{:02}/{:02}/{:02}", d.month(), d.day(), d.year() % 100)),
-            (Some('F'), Some(d), _, _) => // `%Y-%m-%d'
-                try!(write!(w, "{:04}-{:02}-{:02}", d.year(), d.month(), d.day())),
-            (Some('v'), Some(d), _, _) => // `%e-%b-%Y'
-                try!(write!(w, "{:2}-{}-{:04}", d.day(), SHORT_MONTHS[d.month0() as usize],
-                                                d.year())),
-
-            // hour
-            (Some('H'), _, Some(t), _) => try!(write!(w, "{:02}", t.hour())),
-            (Some('k'), _, Some(t), _) => try!(write!(w, "{:2}", t.hour())),
-            (Some('I'), _, Some(t), _) => try!(write!(w, "{:02}", t.hour12().1)),
-            (Some('l'), _, Some(t), _) => try!(write!(w, "{:2}", t.hour12().1)),
-            (Some('P'), _, Some(t), _) =>
-                try!(write!(w, "{}", if t.hour12().0 {"pm"} else {"am"})),
-            (Some('p'), _, Some(t), _) =>
-                try!(write!(w, "{}", if t.hour12().0 {"PM"} else {"AM"})),
-
-            // minute
-            (Some('M'), _, Some(t), _) => try!(write!(w, "{:02}", t.minute())),
-
-            // second and below
-            (Some('S'), _, Some(t), _) => try!(write!(w, "{:02}", t.second())),
-            (Some('f'), _, Some(t), _) => try!(write!(w, "{:09}", t.nanosecond())),
-
-            // combined time
-            (Some('R'), _, Some(t), _) => // `%H:%M`
-                try!(write!(w, "{:02}:{:02}", t.hour(), t.minute())),
-            (Some('T'), _, Some(t), _) | (Some('X'), _, Some(t), _) => // `%H:%M:%S`
-                try!(write!(w, "{:02}:{:02}:{:02}", t.hour(), t.minute(), t.second())),
-            (Some('r'), _, Some(t), _) => { // `%I:%M:%S %p`
-                let (is_pm, hour12) = t.hour12();
-                try!(write!(w, "{:02}:{:02}:{:02} {}", hour12, t.minute(), t.second(),
-                               if is_pm {"PM"} else {"AM"}))
-            },
 
-            // timezone
-            (Some('Z'), _, _, Some(&(ref name, _))) => try!(write!(w, "{}", *name)),
-            (Some('z'), _, _, Some(&(_, ref local_minus_utc))) => {
-                let off = local_minus_utc.num_minutes();
-                let (sign, off) = if off < 0 {('-', -off)} else {('+', off)};
-                try!(write!(w, "{}{:02}{:02}", sign, off / 60, off % 60))
-            },
+        if head == '%' {
+            // flags: `-` (no padding), `_` (space padding), `0` (zero padding), `^` (upper case)
+            let is_flag = |&: c: char| c == '-' || c == '_' || c == '0' || c == '^';
+            let nflags = tail.find(|&: c: char| !is_flag(c)).unwrap_or(tail.len());
+            let (flags, tail) = (&tail[..nflags], &tail[nflags..]);
 
-            /*
-            // timestamp
-            (Some('s'), Some(d), Some(t), Some(o)) => { // XXX
-                let datetime = o.from_local_datetime(&d.and_time(t.clone())).unwrap();
-                try!(write!(w, "{}", datetime.num_seconds_from_unix_epoch()))
-            },
-            */
-
-            // combined date and time
-            (Some('c'), Some(d), Some(t), _) => // `%a %b %e %T %Y`
-                try!(write!(w, "{} {} {:2} {:02}:{:02}:{:02} {:04}",
-                               SHORT_WEEKDAYS[d.weekday().num_days_from_monday() as usize],
-                               SHORT_MONTHS[d.month0() as usize], d.day(),
-                               t.hour(), t.minute(), t.second(), d.year())),
-            (Some('+'), Some(d), Some(t),
-                        Some(&(_, ref local_minus_utc))) => { // `%Y-%m-%dT%H:%M:%S` plus tz
-                let off = local_minus_utc.num_minutes();
-                let (sign, off) = if off < 0 {('-', -off)} else {('+', off)};
-                try!(write!(w, "{}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
-                               d.year(), d.month(), d.day(), t.hour(), t.minute(), t.second(),
-                               sign, off / 60, off % 60))
-            },
+            for &(name, spec) in LONG_SPECS.iter() {
+                if tail.starts_with(name) {
+                    // these specifiers have no padding or case to change
+                    self.remainder = &tail[name.len()..];
+                    return Some(if flags.is_empty() { Item::Fixed(spec) } else { Item::Error });
+                }
+            }
+
+            let (spec, tail) = match tail.slice_shift_char() {
+                Some(spec_and_tail) => spec_and_tail,
+                None => { // a stray `%`
+                    self.remainder = tail;
+                    return Some(Item::Error);
+                }
+            };
+            self.remainder = tail;
+
+            let item = match spec {
+                // year
+                'Y' => num!(Year),
+                'C' => num0!(YearDiv100),
+                'y' => num0!(YearMod100),
+                'G' => num0!(IsoYear),
+                'g' => num0!(IsoYearMod100),
+
+                // month
+                'm' => num0!(Month),
+                'b' | 'h' => fix!(ShortMonthName),
+                'B' => fix!(LongMonthName),
+
+                // day of month
+                'd' => num0!(Day),
+                'e' => nums!(Day),
+
+                // week
+                'U' => num0!(WeekFromSun),
+                'W' => num0!(WeekFromMon),
+                'V' => num0!(IsoWeek),
+
+                // day of week
+                'a' => fix!(ShortWeekdayName),
+                'A' => fix!(LongWeekdayName),
+                'w' => num!(NumDaysFromSun),
+                'u' => num!(WeekdayFromMon),
+
+                // day of year
+                'j' => num0!(Ordinal),
+
+                // hour
+                'H' => num0!(Hour),
+                'k' => nums!(Hour),
+                'I' => num0!(Hour12),
+                'l' => nums!(Hour12),
+                'P' => fix!(LowerAmPm),
+                'p' => fix!(UpperAmPm),
+
+                // minute
+                'M' => num0!(Minute),
+
+                // second and below
+                'S' => num0!(Second),
+                'f' => num0!(Nanosecond),
+                's' => num!(Timestamp),
+
+                // timezone
+                'Z' => fix!(TimezoneName),
+                'z' => fix!(TimezoneOffset),
+
+                // special characters
+                't' => sp!("\t"),
+                'n' => sp!("\n"),
+                '%' => lit!("%"),
+
+                // composite specifiers
+                'D' => { self.recons = D_ITEMS; return self.next(); }
+                'F' => { self.recons = F_ITEMS; return self.next(); }
+                'v' => { self.recons = V_ITEMS; return self.next(); }
+                'R' => { self.recons = R_ITEMS; return self.next(); }
+                'T' => { self.recons = T_ITEMS; return self.next(); }
+                '+' => { self.recons = PLUS_ITEMS; return self.next(); }
+
+                // composite specifiers depending on the locale
+                'x' => return self.expand(D_ITEMS, |&: l| l.date_format),
+                'X' => return self.expand(T_ITEMS, |&: l| l.time_format),
+                'c' => return self.expand(C_ITEMS, |&: l| l.datetime_format),
+                'r' => return self.expand(LOWER_R_ITEMS, |&: l| l.time_ampm_format),
+
+                _ => Item::Error,
+            };
+
+            // the last padding flag overrides the default padding
+            let pad = flags.chars().rev().filter_map(|c| match c {
+                '-' => Some(Pad::None),
+                '_' => Some(Pad::Space),
+                '0' => Some(Pad::Zero),
+                _ => None,
+            }).next();
+            let upper = flags.chars().any(|c| c == '^');
+            let item = match item {
+                Item::Numeric(spec, default) => Item::Numeric(spec, pad.unwrap_or(default)),
+                Item::Fixed(spec) if upper => Item::Upper(spec),
+                item => item,
+            };
+            return Some(item);
+        }
+
+        // a run of whitespace or other literal characters, up to the next `%`
+        let is_space = head.is_whitespace();
+        let len = self.remainder.find(|&: c: char| c == '%' || c.is_whitespace() != is_space)
+                                .unwrap_or(self.remainder.len());
+        let (text, rest) = (&self.remainder[..len], &self.remainder[len..]);
+        self.remainder = rest;
+        Some(if is_space { Item::Space(text) } else { Item::Literal(text) })
+    }
+}
+
+/// The category of `FormatError`.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum FormatErrorKind {
+    /// An unknown specifier, e.g. `%Q`.
+    UnknownSpecifier,
+    /// A stray `%` at the end of the format string.
+    StrayPercent,
+    /// The specifier requires the date, which is not available (e.g. `%Y` for `NaiveTime`).
+    NoDate,
+    /// The specifier requires the time, which is not available (e.g. `%H` for `NaiveDate`).
+    NoTime,
+    /// The specifier requires the offset, which is not available (e.g. `%z` for `NaiveDate`).
+    NoOffset,
+}
 
-            // special characters
-            (Some('t'), _, _, _) => try!(write!(w, "\t")),
-            (Some('n'), _, _, _) => try!(write!(w, "\n")),
+/// An error in the format string, with the offending specifier and its position.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct FormatError {
+    /// The category of the error.
+    pub kind: FormatErrorKind,
+    /// The byte offset of the specifier in the format string.
+    pub pos: usize,
+    /// The offending specifier, e.g. `%Q` or `%c` (rather than the part of it not available).
+    pub spec: String,
+}
 
-            // TODO issue a detailed error if possible
-            (Some(_), _, _, _) => return Err(fmt::Error),
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            FormatErrorKind::StrayPercent =>
+                write!(f, "{} at position {}", self.description(), self.pos),
+            _ => write!(f, "{} `{}` at position {}", self.description(), self.spec, self.pos),
+        }
+    }
+}
+
+impl Error for FormatError {
+    fn description(&self) -> &str {
+        match self.kind {
+            FormatErrorKind::UnknownSpecifier => "unknown specifier",
+            FormatErrorKind::StrayPercent => "stray `%`",
+            FormatErrorKind::NoDate => "date is not available for",
+            FormatErrorKind::NoTime => "time is not available for",
+            FormatErrorKind::NoOffset => "offset is not available for",
+        }
+    }
+}
 
-            (None, _, _, _) => {
-                // if there is the next part, a single `%` and that part should be printed
-                // in verbatim. otherwise it is an error (the stray `%`).
-                last_was_percent = true;
+/// Returns whether the item requires the date, the time and the offset respectively.
+fn item_requires(item: &Item) -> (bool, bool, bool) {
+    match *item {
+        Item::Numeric(spec, _) => {
+            use self::Numeric::*;
+            match spec {
+                Hour | Hour12 | Minute | Second | Nanosecond => (false, true, false),
+                Timestamp => (true, true, false),
+                _ => (true, false, false),
+            }
+        }
+        Item::Fixed(spec) | Item::Upper(spec) => {
+            use self::Fixed::*;
+            match spec {
+                ShortMonthName | LongMonthName |
+                ShortWeekdayName | LongWeekdayName => (true, false, false),
+                LowerAmPm | UpperAmPm |
+                Nanosecond | Nanosecond3 | Nanosecond6 | Nanosecond9 |
+                Nanosecond3NoDot | Nanosecond6NoDot | Nanosecond9NoDot => (false, true, false),
+                TimezoneName | TimezoneOffset | TimezoneOffsetColon |
+                TimezoneOffsetDoubleColon | TimezoneOffsetPermissive => (false, false, true),
             }
         }
+        _ => (false, false, false),
+    }
+}
+
+impl<'a> StrftimeItems<'a> {
+    /// Checks if the remaining format string can be used to format a value
+    /// with the date, the time and/or the offset, as given by the arguments.
+    /// Returns the first offending specifier and its position otherwise.
+    pub fn validate(&self, date: bool, time: bool, offset: bool) -> Result<(), FormatError> {
+        // the locale and the pending items are kept, so that the same items are checked
+        let fmt = self.resume.unwrap_or(self.remainder);
+        let mut items = self.clone();
+        let pos = |&: items: &StrftimeItems|
+            fmt.len() - items.resume.unwrap_or(items.remainder).len();
+        let mut start = 0;
+        let mut end = 0;
+        loop {
+            // the items expanded from a composite specifier or the layout from the locale
+            // share its position
+            let expanding = !items.recons.is_empty() ||
+                            (items.resume.is_some() && !items.remainder.is_empty());
+            if !expanding { start = pos(&items); }
+            let item = match items.next() { Some(item) => item, None => return Ok(()) };
+            if !expanding { end = pos(&items); }
 
-        try!(write!(w, "{}", tail));
+            let (needs_date, needs_time, needs_offset) = item_requires(&item);
+            let kind = if item == Item::Error {
+                if end == fmt.len() && fmt[start+1..end].chars().all(|c| "-_0^".contains_char(c)) {
+                    FormatErrorKind::StrayPercent                   // `%` and flags only
+                } else {
+                    FormatErrorKind::UnknownSpecifier
+                }
+            } else if needs_date && !date {
+                FormatErrorKind::NoDate
+            } else if needs_time && !time {
+                FormatErrorKind::NoTime
+            } else if needs_offset && !offset {
+                FormatErrorKind::NoOffset
+            } else {
+                continue;
+            };
+            return Err(FormatError { kind: kind, pos: start, spec: fmt[start..end].to_string() });
+        }
     }
+}
+
+/// The internal workhouse for `DelayedFormat`.
+fn format<'a, I>(w: &mut fmt::Formatter, date: Option<&NaiveDate>, time: Option<&NaiveTime>,
+                 off: Option<&(String, Duration)>, locale: &Locale, items: I) -> fmt::Result
+        where I: Iterator<Item=Item<'a>> {
+    for item in items {
+        match item {
+            Item::Literal(s) | Item::Space(s) => try!(write!(w, "{}", s)),
+
+            Item::Numeric(spec, pad) => {
+                use self::Numeric::*;
+
+                let week_from_sun = |&: d: &NaiveDate|
+                    (d.ordinal() - d.weekday().num_days_from_sunday() + 7) / 7;
+                let week_from_mon = |&: d: &NaiveDate|
+                    (d.ordinal() - d.weekday().num_days_from_monday() + 7) / 7;
+
+                let (width, v) = match (spec, date, time) {
+                    (Year,           Some(d), _) => (4, d.year() as i64),
+                    (YearDiv100,     Some(d), _) => (2, d.year() as i64 / 100),
+                    (YearMod100,     Some(d), _) => (2, d.year() as i64 % 100),
+                    (IsoYear,        Some(d), _) => (4, d.isoweekdate().0 as i64),
+                    (IsoYearMod100,  Some(d), _) => (2, d.isoweekdate().0 as i64 % 100),
+                    (Month,          Some(d), _) => (2, d.month() as i64),
+                    (Day,            Some(d), _) => (2, d.day() as i64),
+                    (WeekFromSun,    Some(d), _) => (2, week_from_sun(d) as i64),
+                    (WeekFromMon,    Some(d), _) => (2, week_from_mon(d) as i64),
+                    (IsoWeek,        Some(d), _) => (2, d.isoweekdate().1 as i64),
+                    (NumDaysFromSun, Some(d), _) => (1, d.weekday().num_days_from_sunday() as i64),
+                    (WeekdayFromMon, Some(d), _) => (1, d.weekday().number_from_monday() as i64),
+                    (Ordinal,        Some(d), _) => (3, d.ordinal() as i64),
+                    (Hour,           _, Some(t)) => (2, t.hour() as i64),
+                    (Hour12,         _, Some(t)) => (2, t.hour12().1 as i64),
+                    (Minute,         _, Some(t)) => (2, t.minute() as i64),
+                    (Second,         _, Some(t)) =>
+                        (2, (t.second() + t.nanosecond() / 1_000_000_000) as i64),
+                    (Nanosecond,     _, Some(t)) => (9, (t.nanosecond() % 1_000_000_000) as i64),
+                    (Timestamp,      Some(d), Some(t)) => {
+                        let offset = off.map_or(0, |&(_, ref local_minus_utc)| {
+                            local_minus_utc.num_seconds()
+                        });
+                        (1, d.and_time(*t).num_seconds_from_unix_epoch() - offset)
+                    },
+                    _ => return Err(fmt::Error), // see `DelayedFormat::validate` for details
+                };
+
+                try!(match pad {
+                    Pad::None => write!(w, "{}", v),
+                    Pad::Zero => write!(w, "{:01$}", v, width),
+                    Pad::Space => write!(w, "{:1$}", v, width),
+                });
+            },
+
+            Item::Fixed(spec) | Item::Upper(spec) => {
+                use self::Fixed::*;
+
+                let weekday = |&: d: &NaiveDate| d.weekday().num_days_from_monday() as usize;
+                let text = match (spec, date, time, off) {
+                    (ShortMonthName, Some(d), _, _) =>
+                        Some(locale.short_months[d.month0() as usize]),
+                    (LongMonthName, Some(d), _, _) =>
+                        Some(locale.long_months[d.month0() as usize]),
+                    (ShortWeekdayName, Some(d), _, _) => Some(locale.short_weekdays[weekday(d)]),
+                    (LongWeekdayName, Some(d), _, _) => Some(locale.long_weekdays[weekday(d)]),
+                    (LowerAmPm, _, Some(t), _) |
+                    (UpperAmPm, _, Some(t), _) => Some(locale.am_pm[t.hour12().0 as usize]),
+                    (TimezoneName, _, _, Some(&(ref name, _))) => Some(name.as_slice()),
+                    _ => None,
+                };
+
+                // the leap second is printed as the second 60, not in the fraction
+                let nano = time.map(|t| t.nanosecond() % 1_000_000_000);
+
+                match (spec, text, nano, off) {
+                    (_, Some(text), _, _) => match (item, spec) {
+                        (Item::Upper(_), _) => {
+                            let text: String = text.chars().map(|c| c.to_uppercase()).collect();
+                            try!(write!(w, "{}", text))
+                        },
+                        (_, LowerAmPm) => {
+                            let text: String = text.chars().map(|c| c.to_lowercase()).collect();
+                            try!(write!(w, "{}", text))
+                        },
+                        _ => try!(write!(w, "{}", text)),
+                    },
+                    (Nanosecond, _, Some(nano), _) => {
+                        if nano == 0 {
+                            // no fraction at all
+                        } else if nano % 1_000_000 == 0 {
+                            try!(write!(w, ".{:03}", nano / 1_000_000))
+                        } else if nano % 1_000 == 0 {
+                            try!(write!(w, ".{:06}", nano / 1_000))
+                        } else {
+                            try!(write!(w, ".{:09}", nano))
+                        }
+                    },
+                    (Nanosecond3, _, Some(nano), _) =>
+                        try!(write!(w, ".{:03}", nano / 1_000_000)),
+                    (Nanosecond6, _, Some(nano), _) =>
+                        try!(write!(w, ".{:06}", nano / 1_000)),
+                    (Nanosecond9, _, Some(nano), _) =>
+                        try!(write!(w, ".{:09}", nano)),
+                    (Nanosecond3NoDot, _, Some(nano), _) =>
+                        try!(write!(w, "{:03}", nano / 1_000_000)),
+                    (Nanosecond6NoDot, _, Some(nano), _) =>
+                        try!(write!(w, "{:06}", nano / 1_000)),
+                    (Nanosecond9NoDot, _, Some(nano), _) =>
+                        try!(write!(w, "{:09}", nano)),
+                    (TimezoneOffset, _, _, Some(&(_, ref local_minus_utc))) |
+                    (TimezoneOffsetColon, _, _, Some(&(_, ref local_minus_utc))) |
+                    (TimezoneOffsetDoubleColon, _, _, Some(&(_, ref local_minus_utc))) |
+                    (TimezoneOffsetPermissive, _, _, Some(&(_, ref local_minus_utc))) => {
+                        let off = local_minus_utc.num_seconds();
+                        let (sign, off) = if off < 0 {('-', -off)} else {('+', off)};
+                        let (hh, mm, ss) = (off / 3600, off / 60 % 60, off % 60);
+                        try!(match spec {
+                            TimezoneOffsetColon =>
+                                write!(w, "{}{:02}:{:02}", sign, hh, mm),
+                            TimezoneOffsetDoubleColon =>
+                                write!(w, "{}{:02}:{:02}:{:02}", sign, hh, mm, ss),
+                            _ => write!(w, "{}{:02}{:02}", sign, hh, mm),
+                        })
+                    },
+                    _ => return Err(fmt::Error), // see `DelayedFormat::validate` for details
+                }
+            },
 
-    if last_was_percent { // a stray `%`
-        // TODO issue a detailed error if possible
-        Err(fmt::Error)
-    } else {
-        Ok(())
+            Item::Error => return Err(fmt::Error), // see `DelayedFormat::validate` for details
+        }
     }
+
+    Ok(())
 }
 
 /// A *temporary* object which can be used as an argument to `format!` or others.
 /// This is normally constructed via `format` methods of each date and time type.
 #[derive(Show)]
-pub struct DelayedFormat<'a> {
+pub struct DelayedFormat<'a, I> {
     /// The date view, if any.
     date: Option<NaiveDate>,
     /// The time view, if any.
     time: Option<NaiveTime>,
     /// The name and local-to-UTC difference for the offset (timezone), if any.
     off: Option<(String, Duration)>,
-    /// The format string.
-    fmt: &'a str,
+    /// The locale for the names and AM/PM markers.
+    locale: &'a Locale<'a>,
+    /// An iterator returning formatting items.
+    items: I,
 }
 
-impl<'a> DelayedFormat<'a> {
+impl<'a, I: Iterator<Item=Item<'a>> + Clone> DelayedFormat<'a, I> {
     /// Makes a new `DelayedFormat` value out of local date and time.
-    pub fn new(date: Option<NaiveDate>, time: Option<NaiveTime>,
-               fmt: &'a str) -> DelayedFormat<'a> {
-        DelayedFormat { date: date, time: time, off: None, fmt: fmt }
+    pub fn new(date: Option<NaiveDate>, time: Option<NaiveTime>, items: I) -> DelayedFormat<'a, I> {
+        DelayedFormat { date: date, time: time, off: None, locale: &locale::EN_US, items: items }
     }
 
     /// Makes a new `DelayedFormat` value out of local date and time and UTC offset.
     pub fn new_with_offset<Off>(date: Option<NaiveDate>, time: Option<NaiveTime>,
-                                offset: &Off, fmt: &'a str) -> DelayedFormat<'a>
+                                offset: &Off, items: I) -> DelayedFormat<'a, I>
             where Off: Offset + fmt::Display {
         let name_and_diff = (offset.to_string(), offset.local_minus_utc());
-        DelayedFormat { date: date, time: time, off: Some(name_and_diff), fmt: fmt }
+        DelayedFormat { date: date, time: time, off: Some(name_and_diff),
+                        locale: &locale::EN_US, items: items }
+    }
+
+    /// Makes the names and AM/PM markers printed in given locale (`en_US` by default).
+    /// The layouts of `%c`, `%x`, `%X` and `%r` are determined by the items instead;
+    /// see `StrftimeItems::new_with_locale`.
+    pub fn with_locale(self, locale: &'a Locale<'a>) -> DelayedFormat<'a, I> {
+        DelayedFormat { locale: locale, ..self }
+    }
+}
+
+impl<'a> DelayedFormat<'a, StrftimeItems<'a>> {
+    /// Checks if the format string can be used for the value being formatted.
+    ///
+    /// Formatting with an invalid format string only fails with `fmt::Error`
+    /// (so `to_string` panics); this returns the offending specifier and its position,
+    /// and tells an unknown specifier from the one not available for the value
+    /// (e.g. `%H` for `NaiveDate`).
+    pub fn validate(&self) -> Result<(), FormatError> {
+        self.items.validate(self.date.is_some(), self.time.is_some(), self.off.is_some())
     }
 }
 
-impl<'a> fmt::Display for DelayedFormat<'a> {
+impl<'a, I: Iterator<Item=Item<'a>> + Clone> fmt::Display for DelayedFormat<'a, I> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let ret = format(f, self.date.as_ref(), self.time.as_ref(), self.off.as_ref(), self.fmt);
+        let ret = format(f, self.date.as_ref(), self.time.as_ref(), self.off.as_ref(),
+                         self.locale, self.items.clone());
         ret.map_err(|_| fmt::Error) // we don't have any good means to pass detailed errors...
     }
 }
 
+/// Formats a `Duration` as an ISO 8601 duration, e.g. `P3DT4H5M6.5S` or `-PT30M`.
+/// A day is always 24 hours, and the zero components are omitted (`PT0S` if all).
+/// The fractional seconds are printed without the trailing zeroes.
+/// This is the inverse operation of `parse::iso8601_to_duration`.
+pub fn fmt_iso8601_duration(d: Duration) -> String {
+    let mut s = String::new();
+    let d = if d < Duration::zero() { s.push('-'); -d } else { d };
+    s.push('P');
+
+    let secs = d.num_seconds();
+    let nano = (d - Duration::seconds(secs)).num_nanoseconds().unwrap();
+    let (days, hours, mins, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days != 0 {
+        s.push_str(format!("{}D", days).as_slice());
+        if hours == 0 && mins == 0 && secs == 0 && nano == 0 { return s; }
+    }
+    s.push('T');
+    if hours != 0 { s.push_str(format!("{}H", hours).as_slice()); }
+    if mins != 0 { s.push_str(format!("{}M", mins).as_slice()); }
+    if secs != 0 || nano != 0 || (hours == 0 && mins == 0) {
+        s.push_str(format!("{}", secs).as_slice());
+        if nano != 0 {
+            let frac = format!("{:09}", nano);
+            s.push('.');
+            s.push_str(frac.trim_right_matches('0'));
+        }
+        s.push('S');
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Item, Pad, Numeric, Fixed, StrftimeItems, FormatError, FormatErrorKind};
+    use super::{fmt_iso8601_duration, SHORT_MONTHS, LONG_MONTHS, SHORT_WEEKDAYS, LONG_WEEKDAYS};
+    use duration::Duration;
+    use locale::{self, Locale};
+    use offset::{Offset, UTC};
+    use naive::date::NaiveDate;
+    use naive::time::NaiveTime;
+
+    #[test]
+    fn test_strftime_items() {
+        fn parse_and_collect<'a>(s: &'a str) -> Vec<Item<'a>> {
+            StrftimeItems::new(s).collect()
+        }
+
+        assert_eq!(parse_and_collect(""), vec![]);
+        assert_eq!(parse_and_collect(" \t\n"), [Item::Space(" \t\n")]);
+        assert_eq!(parse_and_collect("hello, world"),
+                   [Item::Literal("hello,"), Item::Space(" "), Item::Literal("world")]);
+        assert_eq!(parse_and_collect("%Y-%m"),
+                   [Item::Numeric(Numeric::Year, Pad::None), Item::Literal("-"),
+                    Item::Numeric(Numeric::Month, Pad::Zero)]);
+        assert_eq!(parse_and_collect("%e%k%%"),
+                   [Item::Numeric(Numeric::Day, Pad::Space),
+                    Item::Numeric(Numeric::Hour, Pad::Space), Item::Literal("%")]);
+        assert_eq!(parse_and_collect("%R %z"),
+                   [Item::Numeric(Numeric::Hour, Pad::Zero), Item::Literal(":"),
+                    Item::Numeric(Numeric::Minute, Pad::Zero), Item::Space(" "),
+                    Item::Fixed(Fixed::TimezoneOffset)]);
+        assert_eq!(parse_and_collect("%t%n"), [Item::Space("\t"), Item::Space("\n")]);
+        assert_eq!(parse_and_collect("%Q%"), [Item::Error, Item::Error]);   // unknown, stray `%`
+        assert_eq!(parse_and_collect("%-d%_m%0e%^a%^-H"),
+                   [Item::Numeric(Numeric::Day, Pad::None),
+                    Item::Numeric(Numeric::Month, Pad::Space),
+                    Item::Numeric(Numeric::Day, Pad::Zero),
+                    Item::Upper(Fixed::ShortWeekdayName),
+                    Item::Numeric(Numeric::Hour, Pad::None)]);
+        assert_eq!(parse_and_collect("%-_Y%^%"),                            // the last one wins
+                   [Item::Numeric(Numeric::Year, Pad::Space), Item::Literal("%")]);
+        assert_eq!(parse_and_collect("%-Q%-"), [Item::Error, Item::Error]);
+        assert_eq!(parse_and_collect("%S%.f,%.3f%6f"),
+                   [Item::Numeric(Numeric::Second, Pad::Zero),
+                    Item::Fixed(Fixed::Nanosecond), Item::Literal(","),
+                    Item::Fixed(Fixed::Nanosecond3), Item::Fixed(Fixed::Nanosecond6NoDot)]);
+        assert_eq!(parse_and_collect("%.4f%3"), [Item::Error, Item::Literal("4f"), Item::Error]);
+        assert_eq!(parse_and_collect("%z%:z%::z%#z%s"),
+                   [Item::Fixed(Fixed::TimezoneOffset), Item::Fixed(Fixed::TimezoneOffsetColon),
+                    Item::Fixed(Fixed::TimezoneOffsetDoubleColon),
+                    Item::Fixed(Fixed::TimezoneOffsetPermissive),
+                    Item::Numeric(Numeric::Timestamp, Pad::None)]);
+        assert_eq!(parse_and_collect("%:::z"), [Item::Error, Item::Literal("::z")]);
+    }
+
+    #[test]
+    fn test_strftime_items_with_locale() {
+        let items: Vec<Item> = StrftimeItems::new_with_locale("%x|%Y", &locale::DE_DE).collect();
+        assert_eq!(items, [Item::Numeric(Numeric::Day, Pad::Zero), Item::Literal("."),
+                           Item::Numeric(Numeric::Month, Pad::Zero), Item::Literal("."),
+                           Item::Numeric(Numeric::Year, Pad::None), Item::Literal("|"),
+                           Item::Numeric(Numeric::Year, Pad::None)]);
+        let items: Vec<Item> = StrftimeItems::new_with_locale("%X", &locale::EN_US).collect();
+        assert_eq!(items, StrftimeItems::new("%X").collect::<Vec<_>>());
+        let items: Vec<Item> = StrftimeItems::new_with_locale("%r", &locale::KO_KR).collect();
+        assert_eq!(items[0], Item::Fixed(Fixed::UpperAmPm));
+    }
+
+    #[test]
+    fn test_validate() {
+        let err = |&: kind, pos, spec: &str| Err(FormatError { kind: kind, pos: pos,
+                                                                spec: spec.to_string() });
+        let d = NaiveDate::from_ymd(2015, 1, 20);
+        let t = NaiveTime::from_hms(17, 35, 20);
+        let dt = UTC.ymd(2015, 1, 20).and_hms(17, 35, 20);
+
+        assert_eq!(d.format("%Y-%m-%d").validate(), Ok(()));
+        assert_eq!(d.format("%Y-%m-%d %H").validate(), err(FormatErrorKind::NoTime, 9, "%H"));
+        assert_eq!(t.format("%T %p").validate(), Ok(()));
+        assert_eq!(t.format("%c").validate(), err(FormatErrorKind::NoDate, 0, "%c"));
+        assert_eq!(d.and_time(t).format("%F %z").validate(),
+                   err(FormatErrorKind::NoOffset, 3, "%z"));
+        assert_eq!(dt.format("%+ %Z").validate(), Ok(()));
+        assert_eq!(dt.format("%Y %Q").validate(), err(FormatErrorKind::UnknownSpecifier, 3, "%Q"));
+        assert_eq!(dt.format("%F %").validate(), err(FormatErrorKind::StrayPercent, 3, "%"));
+        assert_eq!(dt.format("%F %-").validate(), err(FormatErrorKind::StrayPercent, 3, "%-"));
+        assert_eq!(dt.format("%-Q").validate(), err(FormatErrorKind::UnknownSpecifier, 0, "%-Q"));
+        assert_eq!(StrftimeItems::new("%%%k").validate(true, false, false),
+                   err(FormatErrorKind::NoTime, 2, "%k"));
+        assert_eq!(t.format("%S%-.3f").validate(),
+                   err(FormatErrorKind::UnknownSpecifier, 2, "%-.3f"));
+        assert_eq!(dt.format("%_:z").validate(),
+                   err(FormatErrorKind::UnknownSpecifier, 0, "%_:z"));
+
+        // the layouts from the locale are checked at the position of the composite specifier
+        static DATE_AND_HOUR: Locale<'static> = Locale { name: "xx_XX", short_months: &SHORT_MONTHS,
+                                                long_months: &LONG_MONTHS,
+                                                short_weekdays: &SHORT_WEEKDAYS,
+                                                long_weekdays: &LONG_WEEKDAYS,
+                                                am_pm: &["AM", "PM"],
+                                                date_format: "%d.%m.%Y %Hh",
+                                                time_format: "%H:%M:%S",
+                                                datetime_format: "%x %X",
+                                                time_ampm_format: "%I:%M:%S %p" };
+        assert_eq!(d.format_localized("%Y|%x|%d", &DATE_AND_HOUR).validate(),
+                   err(FormatErrorKind::NoTime, 3, "%x"));
+        assert_eq!(d.and_time(t).format_localized("%x|%Q", &DATE_AND_HOUR).validate(),
+                   err(FormatErrorKind::UnknownSpecifier, 3, "%Q"));
+        assert_eq!(d.and_time(t).format_localized("%c %x", &DATE_AND_HOUR).validate(), Ok(()));
+
+        assert_eq!(d.format("%Y-%m-%d %H").validate().unwrap_err().to_string(),
+                   "time is not available for `%H` at position 9");
+        assert_eq!(dt.format("%F %").validate().unwrap_err().to_string(),
+                   "stray `%` at position 3");
+    }
+
+    #[test]
+    fn test_fmt_iso8601_duration() {
+        let hms = |&: h, m, s| Duration::hours(h) + Duration::minutes(m) + Duration::seconds(s);
+        assert_eq!(fmt_iso8601_duration(Duration::zero()), "PT0S");
+        assert_eq!(fmt_iso8601_duration(Duration::days(3) + hms(4, 5, 6) +
+                                        Duration::milliseconds(500)), "P3DT4H5M6.5S");
+        assert_eq!(fmt_iso8601_duration(Duration::weeks(2)), "P14D");
+        assert_eq!(fmt_iso8601_duration(Duration::days(1) + hms(0, 0, 1)), "P1DT1S");
+        assert_eq!(fmt_iso8601_duration(hms(0, 90, 0)), "PT1H30M");
+        assert_eq!(fmt_iso8601_duration(-hms(0, 30, 0)), "-PT30M");
+        assert_eq!(fmt_iso8601_duration(Duration::microseconds(-1)), "-PT0.000001S");
+        assert_eq!(fmt_iso8601_duration(Duration::nanoseconds(123456789)), "PT0.123456789S");
+    }
+}
@@ -133,6 +133,47 @@ assert_eq!(dt.to_string(), "2014-11-28 12:00:09 UTC");
 assert_eq!(format!("{:?}", dt), "2014-11-28T12:00:09Z");
 ~~~~
 
+The format string can be compiled into the formatting items (`format::Item`) in advance
+with `format::StrftimeItems`, and given to the `format_with_items` method.
+This avoids scanning the format string every time the value is formatted.
+
+~~~~ {.rust}
+# #![allow(unstable)]
+use chrono::{UTC, Offset};
+use chrono::format::{Item, StrftimeItems};
+
+let items: Vec<Item> = StrftimeItems::new("%Y-%m-%d %H:%M:%S").collect();
+let dt = UTC.ymd(2014, 11, 28).and_hms(12, 0, 9);
+assert_eq!(dt.format_with_items(items.iter().cloned()).to_string(), "2014-11-28 12:00:09");
+~~~~
+
+Parsing is done via the `parse_from_str` method with the same format string,
+which is equivalent to the familiar `strptime` function.
+The timezone-aware `DateTime` can be parsed only with the explicit offset (`%z`).
+
+~~~~ {.rust}
+# #![allow(unstable)]
+use chrono::{DateTime, FixedOffset, NaiveDate, Offset};
+
+let dt = DateTime::<FixedOffset>::parse_from_str("Fri Nov 28 12:00:09 2014 +0900", "%c %z");
+assert_eq!(dt, Ok(FixedOffset::east(9*3600).ymd(2014, 11, 28).and_hms(12, 0, 9)));
+assert_eq!(NaiveDate::parse_from_str("28/11/2014", "%d/%m/%Y"),
+           Ok(NaiveDate::from_ymd(2014, 11, 28)));
+assert!(NaiveDate::parse_from_str("31/11/2014", "%d/%m/%Y").is_err());
+~~~~
+
+The `Debug` output of every date and time type can be read back via `parse` (`FromStr`):
+
+~~~~ {.rust}
+# #![allow(unstable)]
+use chrono::{UTC, DateTime, NaiveDate, Offset};
+
+let dt = UTC.ymd(2014, 11, 28).and_hms(12, 0, 9);
+assert_eq!("2014-11-28T12:00:09Z".parse::<DateTime<UTC>>(), Ok(dt));
+assert_eq!("2014-11-28".parse::<NaiveDate>(), Ok(NaiveDate::from_ymd(2014, 11, 28)));
+assert!("2014-11-31".parse::<NaiveDate>().is_err());
+~~~~
+
 ### Individual date and time
 
 Chrono also provides an individual date type (`Date`) and time type (`Time`).
@@ -156,6 +197,10 @@ assert_eq!(UTC.hms_milli(7, 8, 9, 10).format("%H%M%S").to_string(), "070809");
 `DateTime` has two methods, `date` and `time`,
 which return narrow views to its date and time components respectively.
 
+The consecutive dates can be iterated with `iter_days` and `iter_weeks`,
+or between two dates with `date::range` (and `naive::date::range` for `NaiveDate`),
+optionally at a different interval with `step_by`.
+
 ### Naive date and time
 
 Chrono provides naive counterparts to `Date`, `Time` and `DateTime`
@@ -182,8 +227,29 @@ Chrono inherently does not support an inaccurate or partial date and time repres
 Any operation that can be ambiguous will return `None` in such cases.
 For example, "a month later" of 2014-01-30 is not well-defined
 and consequently `UTC.ymd(2014, 1, 30).with_month(2)` returns `None`.
-
-Advanced offset handling and date/time parsing is not yet supported (but is planned).
+The month arithmetic has to choose a `MonthPolicy` for such cases:
+`UTC.ymd(2014, 1, 30).checked_add_months(Months::new(1), MonthPolicy::Clamp)` is 2014-02-28,
+which is also what `UTC.ymd(2014, 1, 30) + Months::new(1)` returns.
+`Period` (e.g. `P1Y2M3D`) combines the years, months and days in the same way,
+and `NaiveDate::period_since` gives the period between two dates.
+
+The IANA time zone database is supported via `Tz`, which reads the compiled TZif files
+(e.g. `Tz::from_zoneinfo("Europe/Berlin")`), and the POSIX `TZ` strings via `PosixTz`
+(e.g. `PosixTz::parse("EST5EDT,M3.2.0,M11.1.0")`).
+
+The iCalendar recurrence rules (RFC 5545) are supported via `RRule`
+(e.g. `"FREQ=MONTHLY;BYDAY=2TU".parse::<RRule>()`), and `Recurrence` combines them
+with the additional and excluded dates.
+The cron expressions are supported via `Schedule`
+(e.g. `"0,30 9-17 * * MON-FRI".parse::<Schedule>()`), which gives the next
+or previous matching `DateTime` in any offset with `next_after` and `prev_before`.
+
+The business days are counted against a `HolidayCalendar`, such as `RuleCalendar` made of
+the weekends and `HolidayRule`s (e.g. `HolidayRule::NthWeekday(11, 4, Weekday::Thu)`):
+`NaiveDate::add_business_days` and `NaiveDate::business_days_between` skip the weekends
+and holidays, and the calendars of several markets combine with `Union` and `Intersection`.
+The movable feasts are available as `NaiveDate::easter`, `NaiveDate::orthodox_easter`,
+`NaiveDate::good_friday` and so on.
 
 */
 
@@ -191,26 +257,29 @@ Advanced offset handling and date/time parsing is not yet supported (but is plan
 
 #![allow(unstable)]
 #![deny(missing_docs)]
-//  This is needed to allow compile-time regular expressions in this crate.
-#![feature(plugin)]
-#[plugin] #[no_link]
-extern crate regex_macros;
-extern crate regex;
-
 
 extern crate "time" as stdtime;
 
 pub use duration::Duration;
+pub use months::{Months, MonthPolicy};
+pub use period::Period;
 pub use offset::{Offset, LocalResult};
 pub use offset::{UTC, FixedOffset, Local};
+pub use tz::tzfile::Tz;
+pub use tz::posix::PosixTz;
 pub use naive::date::NaiveDate;
 pub use naive::time::NaiveTime;
 pub use naive::datetime::NaiveDateTime;
 pub use date::Date;
 pub use time::Time;
 pub use datetime::DateTime;
+pub use rrule::{RRule, Frequency, Recurrence};
+pub use cron::Schedule;
+pub use calendar::{HolidayCalendar, HolidayRule, Observance, RuleCalendar};
+pub use calendar::{Union, Intersection};
 
 mod div;
+mod resolve;
 pub mod duration {
     //! ISO 8601 duration.
     //!
@@ -218,7 +287,17 @@ pub mod duration {
     //! but has been subsequently merged into Rust's standard library.
     pub use std::time::duration::{MIN, MAX, Duration};
 }
+pub mod months;
+pub mod period;
 pub mod offset;
+pub mod tz {
+    //! Time zones with the daylight saving time and other historical changes.
+    //!
+    //! They implement `Offset` and thus can be used in place of `UTC` or `FixedOffset`,
+    //! but the actual difference from UTC depends on the date and time in question.
+    pub mod tzfile;
+    pub mod posix;
+}
 pub mod naive {
     //! Date and time types which do not concern about the timezones.
     //!
@@ -232,11 +311,22 @@ pub mod date;
 pub mod time;
 pub mod datetime;
 pub mod format;
+pub mod rrule;
+pub mod cron;
+pub mod calendar;
+pub mod locale;
 
 /// Parsing functions for date/time strings.
 ///
 /// Parsing functions are provided for RFC 2822 ("Tue, 20 Jan 2015 17:35:20 -0800")
-/// and RFC3339/ISO8601 ("2015-01-20T17:35:20.001-0800") date/time strings.
+/// and RFC3339/ISO8601 ("2015-01-20T17:35:20.001-08:00") date/time strings,
+/// for the full ISO 8601 including ordinal and week dates and the basic format
+/// ("2015-W04-2", "20150120T173520Z") and the ISO 8601 durations ("P3DT4H5M6.5S"),
+/// for HTTP dates ("Sun, 06 Nov 1994 08:49:37 GMT" and the obsolete forms),
+/// for RFC 5545 recurrence rules ("FREQ=MONTHLY;BYDAY=2TU" via `FromStr` for `RRule`),
+/// as well as for arbitrary format strings (`parse` and `Parsed`).
+/// The month and weekday names can be read in other locales (`parse_localized`).
+/// They return `ParseError` with the position of the failure on the invalid input.
 pub mod parse;
 
 /// The day of week (DOW).
@@ -0,0 +1,141 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2014-2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * Locales for formatting the date and time.
+ *
+ * A `Locale` supplies the month and weekday names, AM/PM markers and
+ * the layouts of the `%c`, `%x`, `%X` and `%r` composite specifiers.
+ * Some locales are built in (`EN_US`, `DE_DE`, `FR_FR`, `JA_JP` and `KO_KR`),
+ * and others can be made by filling the fields with own tables, which can be
+ * borrowed from the data loaded at runtime.
+ * The built-in names and layouts follow the GNU C library.
+ */
+
+use format::{SHORT_MONTHS, LONG_MONTHS, SHORT_WEEKDAYS, LONG_WEEKDAYS};
+
+/// The locale-specific names and layouts for formatting, borrowed for the lifetime `'a`.
+/// The tables should have as many names as the fields say; formatting fails otherwise.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub struct Locale<'a> {
+    /// The name of the locale, e.g. `en_US`.
+    pub name: &'a str,
+    /// 12 abbreviated month names (`%b`), starting from January.
+    pub short_months: &'a [&'a str],
+    /// 12 full month names (`%B`), starting from January.
+    pub long_months: &'a [&'a str],
+    /// 7 abbreviated weekday names (`%a`), starting from Monday.
+    pub short_weekdays: &'a [&'a str],
+    /// 7 full weekday names (`%A`), starting from Monday.
+    pub long_weekdays: &'a [&'a str],
+    /// AM and PM markers (`%p`), in this order, which `%P` prints in lower case.
+    /// Both are empty strings if the 12-hour clock is unused.
+    pub am_pm: &'a [&'a str],
+    /// The layout of the date (`%x`).
+    pub date_format: &'a str,
+    /// The layout of the time (`%X`).
+    pub time_format: &'a str,
+    /// The layout of the date and time (`%c`).
+    pub datetime_format: &'a str,
+    /// The layout of the time in the 12-hour clock (`%r`).
+    pub time_ampm_format: &'a str,
+}
+
+/// English as used in the United States. This is the default locale.
+pub static EN_US: Locale<'static> = Locale {
+    name: "en_US",
+    short_months: &SHORT_MONTHS,
+    long_months: &LONG_MONTHS,
+    short_weekdays: &SHORT_WEEKDAYS,
+    long_weekdays: &LONG_WEEKDAYS,
+    am_pm: &["AM", "PM"],
+    date_format: "%m/%d/%y",
+    time_format: "%H:%M:%S",
+    datetime_format: "%a %b %e %H:%M:%S %Y",
+    time_ampm_format: "%I:%M:%S %p",
+};
+
+/// German as used in Germany.
+pub static DE_DE: Locale<'static> = Locale {
+    name: "de_DE",
+    short_months: &["Jan", "Feb", "Mär", "Apr", "Mai", "Jun",
+                    "Jul", "Aug", "Sep", "Okt", "Nov", "Dez"],
+    long_months: &["Januar", "Februar", "März", "April", "Mai", "Juni",
+                   "Juli", "August", "September", "Oktober", "November", "Dezember"],
+    short_weekdays: &["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    long_weekdays: &["Montag", "Dienstag", "Mittwoch", "Donnerstag",
+                     "Freitag", "Samstag", "Sonntag"],
+    am_pm: &["", ""],
+    date_format: "%d.%m.%Y",
+    time_format: "%H:%M:%S",
+    datetime_format: "%a %d %b %Y %H:%M:%S",
+    time_ampm_format: "%H:%M:%S",
+};
+
+/// French as used in France.
+pub static FR_FR: Locale<'static> = Locale {
+    name: "fr_FR",
+    short_months: &["janv.", "févr.", "mars", "avril", "mai", "juin",
+                    "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+    long_months: &["janvier", "février", "mars", "avril", "mai", "juin",
+                   "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+    short_weekdays: &["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+    long_weekdays: &["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+    am_pm: &["", ""],
+    date_format: "%d/%m/%Y",
+    time_format: "%H:%M:%S",
+    datetime_format: "%a %d %b %Y %H:%M:%S",
+    time_ampm_format: "%H:%M:%S",
+};
+
+/// Japanese as used in Japan.
+pub static JA_JP: Locale<'static> = Locale {
+    name: "ja_JP",
+    short_months: &[" 1月", " 2月", " 3月", " 4月", " 5月", " 6月",
+                    " 7月", " 8月", " 9月", "10月", "11月", "12月"],
+    long_months: &["1月", "2月", "3月", "4月", "5月", "6月",
+                   "7月", "8月", "9月", "10月", "11月", "12月"],
+    short_weekdays: &["月", "火", "水", "木", "金", "土", "日"],
+    long_weekdays: &["月曜日", "火曜日", "水曜日", "木曜日", "金曜日", "土曜日", "日曜日"],
+    am_pm: &["午前", "午後"],
+    date_format: "%Y年%m月%d日",
+    time_format: "%H時%M分%S秒",
+    datetime_format: "%Y年%m月%d日 %H時%M分%S秒",
+    time_ampm_format: "%p%I時%M分%S秒",
+};
+
+/// Korean as used in South Korea.
+pub static KO_KR: Locale<'static> = Locale {
+    name: "ko_KR",
+    short_months: &[" 1월", " 2월", " 3월", " 4월", " 5월", " 6월",
+                    " 7월", " 8월", " 9월", "10월", "11월", "12월"],
+    long_months: &["1월", "2월", "3월", "4월", "5월", "6월",
+                   "7월", "8월", "9월", "10월", "11월", "12월"],
+    short_weekdays: &["월", "화", "수", "목", "금", "토", "일"],
+    long_weekdays: &["월요일", "화요일", "수요일", "목요일", "금요일", "토요일", "일요일"],
+    am_pm: &["오전", "오후"],
+    date_format: "%Y년 %m월 %d일",
+    time_format: "%H시 %M분 %S초",
+    datetime_format: "%Y년 %m월 %d일 (%a) %p %I시 %M분 %S초",
+    time_ampm_format: "%p %I시 %M분 %S초",
+};
+
+/// Returns the built-in locale with given name (e.g. `de_DE`), if any.
+pub fn find(name: &str) -> Option<&'static Locale<'static>> {
+    static LOCALES: [&'static Locale<'static>; 5] = [&EN_US, &DE_DE, &FR_FR, &JA_JP, &KO_KR];
+    LOCALES.iter().map(|&locale| locale).find(|locale| locale.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find, EN_US, KO_KR};
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find("en_US"), Some(&EN_US));
+        assert_eq!(find("ko_KR").map(|l| l.long_weekdays[0]), Some("월요일"));
+        assert_eq!(find("ko_KR"), Some(&KO_KR));
+        assert_eq!(find("en_GB"), None);
+    }
+}
@@ -0,0 +1,62 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2014-2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * Calendar months for the month and year arithmetic.
+ *
+ * Unlike `Duration`, a month does not have the fixed length,
+ * so adding months can land on the day which does not exist (e.g. February 30).
+ * `MonthPolicy` decides what happens then; the `+` and `-` operators clamp the day.
+ */
+
+use std::num::Int;
+
+use Datelike;
+use naive::date::NaiveDate;
+
+/// The number of calendar months, to be added to or subtracted from dates.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Show)]
+pub struct Months(u32);
+
+impl Months {
+    /// Makes a new `Months` from the number of months.
+    #[inline]
+    pub fn new(months: u32) -> Months {
+        Months(months)
+    }
+
+    /// Makes a new `Months` from the number of years, i.e. 12 months each.
+    ///
+    /// Fails when the number of months overflows.
+    #[inline]
+    pub fn years(years: u32) -> Months {
+        Months(years.checked_mul(12).expect("`Months::years` overflowed"))
+    }
+
+    /// Returns the number of months.
+    #[inline]
+    pub fn num_months(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Returns the number of days in given month of given year.
+/// The month out of range or the year after the last representable date counts as 31 days.
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    let next = if month == 12 { NaiveDate::from_ymd_opt(year + 1, 1, 1) }
+               else { NaiveDate::from_ymd_opt(year, month + 1, 1) };
+    next.map_or(31, |next| next.pred().day())
+}
+
+/// What to do when the day of month does not exist after adding or subtracting months.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum MonthPolicy {
+    /// Uses the last day of the month instead (2014-01-31 plus a month is 2014-02-28).
+    /// This is the policy of the `+` and `-` operators.
+    Clamp,
+    /// Carries the excess days into the next month (2014-01-31 plus a month is 2014-03-03).
+    Overflow,
+    /// Returns `None` (2014-01-31 plus a month is invalid).
+    Fail,
+}
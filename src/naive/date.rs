@@ -6,16 +6,21 @@
  * ISO 8601 calendar date without timezone.
  */
 
-use std::{fmt, hash};
+use std::{fmt, hash, iter};
 use std::num::{Int, ToPrimitive};
 use std::ops::{Add, Sub};
 
 use {Weekday, Datelike};
-use div::div_mod_floor;
+use div::{mod_floor, div_mod_floor};
 use duration::Duration;
+use months::{Months, MonthPolicy};
+use period::Period;
+use calendar::HolidayCalendar;
 use naive::time::NaiveTime;
 use naive::datetime::NaiveDateTime;
-use format::DelayedFormat;
+use format::{Item, StrftimeItems, DelayedFormat};
+use locale::Locale;
+use parse::{Parsed, ParseResult, parse_and_resolve};
 
 use self::internals::{DateImpl, Of, Mdf, YearFlags};
 
@@ -158,6 +163,103 @@ impl NaiveDate {
                            Of::new(ordinal, flags))
     }
 
+    /// Makes a new `NaiveDate` for the Western Easter Sunday in given year,
+    /// by the Gregorian computus.
+    ///
+    /// Fails on the out-of-range year.
+    pub fn easter(year: i32) -> NaiveDate {
+        NaiveDate::easter_opt(year).expect("out-of-range year")
+    }
+
+    /// Makes a new `NaiveDate` for the Western Easter Sunday in given year,
+    /// by the Gregorian computus.
+    ///
+    /// Returns `None` on the out-of-range year.
+    pub fn easter_opt(year: i32) -> Option<NaiveDate> {
+        // the anonymous Gregorian algorithm, extended to the negative years
+        let a = mod_floor(year, 19);
+        let (b, c) = div_mod_floor(year, 100);
+        let (d, e) = div_mod_floor(b, 4);
+        let f = div_mod_floor(b + 8, 25).0;
+        let g = div_mod_floor(b - f + 1, 3).0;
+        let h = mod_floor(19 * a + b - d - g + 15, 30);
+        let (i, k) = (c / 4, c % 4);
+        let l = mod_floor(32 + 2 * e + 2 * i - h - k, 7);
+        let m = (a + 11 * h + 22 * l) / 451;
+        let n = h + l - 7 * m + 114; // March 22 to April 25
+        NaiveDate::from_ymd_opt(year, (n / 31) as u32, (n % 31 + 1) as u32)
+    }
+
+    /// Makes a new `NaiveDate` for the Orthodox Easter Sunday in given year,
+    /// by the Julian computus. The resulting date is in the Gregorian calendar,
+    /// so it drifts later and may be in the following years for the distant years.
+    ///
+    /// Fails on the out-of-range date.
+    pub fn orthodox_easter(year: i32) -> NaiveDate {
+        NaiveDate::orthodox_easter_opt(year).expect("out-of-range date")
+    }
+
+    /// Makes a new `NaiveDate` for the Orthodox Easter Sunday in given year,
+    /// by the Julian computus. The resulting date is in the Gregorian calendar,
+    /// so it drifts later and may be in the following years for the distant years.
+    ///
+    /// Returns `None` on the out-of-range date,
+    /// which happens for a few years near the both ends of the supported range.
+    pub fn orthodox_easter_opt(year: i32) -> Option<NaiveDate> {
+        // Meeus's Julian algorithm, giving March 22 to April 25 in the Julian calendar
+        let a = mod_floor(year, 4);
+        let b = mod_floor(year, 7);
+        let c = mod_floor(year, 19);
+        let d = (19 * c + 15) % 30;
+        let e = (2 * a + 4 * b - d + 34) % 7;
+        let n = d + e + 114;
+        let julian = match NaiveDate::from_ymd_opt(year, (n / 31) as u32, (n % 31 + 1) as u32) {
+            Some(julian) => julian,
+            None => return None,
+        };
+        // the Julian calendar lags behind by the century years not leap in the Gregorian one
+        // (valid from March 1 of the year to the end of February of the next year)
+        let lag = div_mod_floor(year, 100).0 - div_mod_floor(year, 400).0 - 2;
+        julian.num_days_from_ce().checked_add(lag)
+              .and_then(|days| NaiveDate::from_num_days_from_ce_opt(days))
+    }
+
+    /// Makes a new `NaiveDate` for Ash Wednesday in given year,
+    /// 46 days before the Western Easter Sunday.
+    ///
+    /// Fails on the out-of-range year.
+    #[inline]
+    pub fn ash_wednesday(year: i32) -> NaiveDate {
+        NaiveDate::easter(year) - Duration::days(46)
+    }
+
+    /// Makes a new `NaiveDate` for Good Friday in given year,
+    /// 2 days before the Western Easter Sunday.
+    ///
+    /// Fails on the out-of-range year.
+    #[inline]
+    pub fn good_friday(year: i32) -> NaiveDate {
+        NaiveDate::easter(year) - Duration::days(2)
+    }
+
+    /// Makes a new `NaiveDate` for the Feast of the Ascension in given year,
+    /// 39 days after the Western Easter Sunday.
+    ///
+    /// Fails on the out-of-range year.
+    #[inline]
+    pub fn ascension(year: i32) -> NaiveDate {
+        NaiveDate::easter(year) + Duration::days(39)
+    }
+
+    /// Makes a new `NaiveDate` for Pentecost in given year,
+    /// 49 days after the Western Easter Sunday.
+    ///
+    /// Fails on the out-of-range year.
+    #[inline]
+    pub fn pentecost(year: i32) -> NaiveDate {
+        NaiveDate::easter(year) + Duration::days(49)
+    }
+
     /// Makes a new `NaiveDateTime` from the current date and given `NaiveTime`.
     #[inline]
     pub fn and_time(&self, time: NaiveTime) -> NaiveDateTime {
@@ -302,11 +404,178 @@ impl NaiveDate {
         self.with_of(self.of().pred()).or_else(|| NaiveDate::from_ymd_opt(self.year() - 1, 12, 31))
     }
 
+    /// Returns an iterator over the current and following dates, up to `MAX`.
+    /// `.rev()` gives the dates from `MAX` back to the current date.
+    #[inline]
+    pub fn iter_days(&self) -> NaiveDateRange {
+        NaiveDateRange::new(self.num_days_from_ce(), MAX.num_days_from_ce(), 1)
+    }
+
+    /// Returns an iterator over the current date and the same day of following weeks,
+    /// up to `MAX`.
+    #[inline]
+    pub fn iter_weeks(&self) -> NaiveDateRange {
+        NaiveDateRange::new(self.num_days_from_ce(), MAX.num_days_from_ce(), 7)
+    }
+
+    /// Adds given number of months to the current date.
+    /// The nonexistent day of month (e.g. February 30) is handled by `policy`.
+    ///
+    /// Returns `None` when the resulting date is out of range,
+    /// or when the day does not exist under `MonthPolicy::Fail`.
+    #[inline]
+    pub fn checked_add_months(&self, months: Months, policy: MonthPolicy) -> Option<NaiveDate> {
+        self.add_months(months.num_months() as i64, policy)
+    }
+
+    /// Subtracts given number of months from the current date.
+    /// The nonexistent day of month (e.g. February 30) is handled by `policy`.
+    ///
+    /// Returns `None` when the resulting date is out of range,
+    /// or when the day does not exist under `MonthPolicy::Fail`.
+    #[inline]
+    pub fn checked_sub_months(&self, months: Months, policy: MonthPolicy) -> Option<NaiveDate> {
+        self.add_months(-(months.num_months() as i64), policy)
+    }
+
+    /// Adds given (possibly negative) number of months. See `checked_add_months`.
+    fn add_months(&self, months: i64, policy: MonthPolicy) -> Option<NaiveDate> {
+        let month0 = self.year() as i64 * 12 + self.month0() as i64 + months;
+        let (year, month0) = div_mod_floor(month0, 12);
+        if year < MIN_YEAR as i64 || year > MAX_YEAR as i64 { return None; }
+        let (year, month, day) = (year as i32, month0 as u32 + 1, self.day());
+
+        match NaiveDate::from_ymd_opt(year, month, day) {
+            Some(date) => Some(date),
+            // every month has the days from 1 to 28, and December (no carry) has 31 days
+            None => match policy {
+                MonthPolicy::Clamp =>
+                    iter::range(28, day).rev()
+                        .filter_map(|d| NaiveDate::from_ymd_opt(year, month, d)).next(),
+                MonthPolicy::Overflow =>
+                    NaiveDate::from_ymd_opt(year, month, 28)
+                        .and_then(|d| d.with_ordinal(d.ordinal() + day - 28)),
+                MonthPolicy::Fail => None,
+            },
+        }
+    }
+
+    /// Adds given period to the current date: the years and months first,
+    /// using the last day of the month for the nonexistent day, and then the days.
+    ///
+    /// Returns `None` when the resulting date is out of range.
+    pub fn checked_add_period(&self, period: Period) -> Option<NaiveDate> {
+        self.add_months(period.num_total_months(), MonthPolicy::Clamp)
+            .and_then(|date| date.num_days_from_ce().checked_add(period.num_days()))
+            .and_then(|days| NaiveDate::from_num_days_from_ce_opt(days))
+    }
+
+    /// Subtracts given period from the current date, i.e. adds the negated period.
+    ///
+    /// Returns `None` when the resulting date is out of range.
+    #[inline]
+    pub fn checked_sub_period(&self, period: Period) -> Option<NaiveDate> {
+        self.checked_add_period(-period)
+    }
+
+    /// Returns the calendar period from `other` to the current date,
+    /// so that `other.checked_add_period(period)` is the current date.
+    /// The components have the same sign, e.g. `P1Y2M3D` or `-P1Y2M3D`;
+    /// the days are counted after adding the years and months.
+    pub fn period_since(&self, other: &NaiveDate) -> Period {
+        let mut months = (self.year() as i64 - other.year() as i64) * 12 +
+                         (self.month() as i64 - other.month() as i64);
+        let mut days = self.day() as i32 - other.day() as i32;
+        let carry = (months > 0 && days < 0) || (months < 0 && days > 0);
+        if carry {
+            // the days go back to the last month, whose length depends on the base date
+            months += if months > 0 {-1} else {1};
+        }
+        if carry || months < 0 {
+            // going backward can also clamp the day of `other` to the end of a shorter month
+            let base = other.add_months(months, MonthPolicy::Clamp).unwrap();
+            days = (*self - base).num_days() as i32;
+        }
+        Period::new((months / 12) as i32, (months % 12) as i32, days)
+    }
+
+    /// Returns true if the current date is a business day in given calendar.
+    #[inline]
+    pub fn is_business_day<C: HolidayCalendar>(&self, calendar: &C) -> bool {
+        calendar.is_business_day(self)
+    }
+
+    /// Adds given number of business days in given calendar to the current date,
+    /// moving backward for the negative number. The current date itself does not count,
+    /// so e.g. `date.add_business_days(1, &calendar)` on Saturday is the next Monday
+    /// (unless it is a holiday) and `date.add_business_days(0, &calendar)` is `date` itself.
+    ///
+    /// Returns `None` when the resulting date is out of range,
+    /// or the calendar has no business days in the given direction.
+    pub fn add_business_days<C: HolidayCalendar>(&self, n: i64,
+                                                 calendar: &C) -> Option<NaiveDate> {
+        // every day would be skipped if there are no weekdays at all
+        let mut weekdays = iter::iterate(Weekday::Mon, |w| w.succ()).take(7);
+        if n != 0 && weekdays.all(|w| calendar.is_weekend(w)) { return None; }
+        let mut date = *self;
+        let mut remaining = n.abs();
+        while remaining > 0 {
+            date = match if n > 0 { date.succ_opt() } else { date.pred_opt() } {
+                Some(date) => date,
+                None => return None,
+            };
+            if calendar.is_business_day(&date) { remaining -= 1; }
+        }
+        Some(date)
+    }
+
+    /// Returns the number of business days in given calendar from the current date to `other`,
+    /// so that `self.add_business_days(n, &calendar)` is `other` when `other` is a business day.
+    /// It counts the business days after the current date up to and including `other`,
+    /// or the negated number of business days from `other` up to the current date exclusive.
+    pub fn business_days_between<C: HolidayCalendar>(&self, other: &NaiveDate,
+                                                     calendar: &C) -> i64 {
+        let forward = *self <= *other;
+        let (mut date, end) = if forward { (*self, *other) } else { (*other, *self) };
+        let mut count = 0;
+        while date < end {
+            let next = date.succ();
+            if calendar.is_business_day(if forward { &next } else { &date }) { count += 1; }
+            date = next;
+        }
+        if forward { count } else { -count }
+    }
+
+    /// Formats the date with the specified formatting items.
+    #[inline]
+    pub fn format_with_items<'a, I>(&self, items: I) -> DelayedFormat<'a, I>
+            where I: Iterator<Item=Item<'a>> + Clone {
+        DelayedFormat::new(Some(self.clone()), None, items)
+    }
+
     /// Formats the date in the specified format string.
     /// See the `format` module on the supported escape sequences.
     #[inline]
-    pub fn format<'a>(&'a self, fmt: &'a str) -> DelayedFormat<'a> {
-        DelayedFormat::new(Some(self.clone()), None, fmt)
+    pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new(fmt))
+    }
+
+    /// Formats the date in the specified format string and locale.
+    /// The names, AM/PM markers and the layouts of `%c`, `%x`, `%X` and `%r` follow the locale.
+    #[inline]
+    pub fn format_localized<'a>(&self, fmt: &'a str,
+                                locale: &'a Locale<'a>) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new_with_locale(fmt, locale)).with_locale(locale)
+    }
+
+    /// Parses a string with the specified format string and makes a new `NaiveDate`.
+    /// See the `format` module on the supported escape sequences.
+    ///
+    /// Fails when the string does not match the format string,
+    /// or the parsed fields are insufficient, inconsistent or out of range.
+    pub fn parse_from_str(s: &str, fmt: &str) -> ParseResult<NaiveDate> {
+        parse_and_resolve(s, StrftimeItems::new(fmt),
+                          |&: parsed: &Parsed| parsed.to_naive_date())
     }
 }
 
@@ -425,6 +694,38 @@ impl Sub<Duration> for NaiveDate {
     fn sub(self, rhs: Duration) -> NaiveDate { self.add(-rhs) }
 }
 
+impl Add<Months> for NaiveDate {
+    type Output = NaiveDate;
+
+    fn add(self, rhs: Months) -> NaiveDate {
+        self.checked_add_months(rhs, MonthPolicy::Clamp).expect("`NaiveDate + Months` out of range")
+    }
+}
+
+impl Sub<Months> for NaiveDate {
+    type Output = NaiveDate;
+
+    fn sub(self, rhs: Months) -> NaiveDate {
+        self.checked_sub_months(rhs, MonthPolicy::Clamp).expect("`NaiveDate - Months` out of range")
+    }
+}
+
+impl Add<Period> for NaiveDate {
+    type Output = NaiveDate;
+
+    fn add(self, rhs: Period) -> NaiveDate {
+        self.checked_add_period(rhs).expect("`NaiveDate + Period` out of range")
+    }
+}
+
+impl Sub<Period> for NaiveDate {
+    type Output = NaiveDate;
+
+    fn sub(self, rhs: Period) -> NaiveDate {
+        self.checked_sub_period(rhs).expect("`NaiveDate - Period` out of range")
+    }
+}
+
 impl fmt::Debug for NaiveDate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let year = self.year();
@@ -450,11 +751,87 @@ impl fmt::Display for NaiveDate {
     }
 }
 
+/// Returns an iterator over the dates from `start` (inclusive) to `end` (exclusive).
+/// It is empty when `end` is not after `start`. `step_by` changes the interval.
+pub fn range(start: NaiveDate, end: NaiveDate) -> NaiveDateRange {
+    NaiveDateRange::new(start.num_days_from_ce(), end.num_days_from_ce() - 1, 1)
+}
+
+/// An iterator over the dates at the fixed interval of days.
+/// This is made by `range`, `NaiveDate::iter_days` or `NaiveDate::iter_weeks`.
+///
+/// It can be iterated from both ends and knows its exact length.
+/// The dates are never out of range, so the iteration never fails.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub struct NaiveDateRange {
+    /// The next date from the front, in days from CE.
+    first: i32,
+    /// The number of remaining dates.
+    len: usize,
+    /// The days between two dates, always positive.
+    step: i64,
+}
+
+impl NaiveDateRange {
+    /// Makes a new range from `first` to `last` (both inclusive and in days from CE)
+    /// in steps of `step` days.
+    fn new(first: i32, last: i32, step: i64) -> NaiveDateRange {
+        let len = if last < first { 0 } else { (last as i64 - first as i64) / step + 1 };
+        NaiveDateRange { first: first, len: len as usize, step: step }
+    }
+
+    /// Changes the interval between dates to `step`.
+    /// The first date is kept, and the dates do not go beyond the current last date.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `step` is not a positive whole number of days.
+    pub fn step_by(self, step: Duration) -> NaiveDateRange {
+        let days = step.num_days();
+        assert!(days > 0 && Duration::days(days) == step,
+                "`NaiveDateRange::step_by` requires a positive whole number of days");
+        let last = self.first as i64 + (self.len as i64 - 1) * self.step;
+        NaiveDateRange::new(self.first, last as i32, days)
+    }
+}
+
+impl Iterator for NaiveDateRange {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<NaiveDate> {
+        if self.len == 0 { return None; }
+        let date = NaiveDate::from_num_days_from_ce(self.first);
+        self.len -= 1;
+        if self.len > 0 { self.first = (self.first as i64 + self.step) as i32; }
+        Some(date)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl DoubleEndedIterator for NaiveDateRange {
+    fn next_back(&mut self) -> Option<NaiveDate> {
+        if self.len == 0 { return None; }
+        self.len -= 1;
+        let days = self.first as i64 + self.len as i64 * self.step;
+        Some(NaiveDate::from_num_days_from_ce(days as i32))
+    }
+}
+
+impl ExactSizeIterator for NaiveDateRange {}
+
 #[cfg(test)]
 mod tests {
-    use super::{NaiveDate, MIN, MAX};
+    use super::{NaiveDate, NaiveDateRange, MIN, MAX};
     use {Datelike, Weekday};
     use duration::Duration;
+    use months::{Months, MonthPolicy};
+    use period::Period;
+    use calendar::{HolidayRule, RuleCalendar};
+    use locale;
     use std::{i32, u32};
     use std::iter::{range_inclusive, range_step_inclusive};
 
@@ -714,6 +1091,52 @@ mod tests {
         assert_eq!(ymd(MIN.year(), 1, 1).pred_opt(), None);
     }
 
+    #[test]
+    fn test_date_range() {
+        let ymd = |&: y,m,d| NaiveDate::from_ymd(y, m, d);
+        let collect = |&: it: NaiveDateRange| it.map(|d| d.day()).collect::<Vec<_>>();
+
+        assert_eq!(collect(super::range(ymd(2014, 2, 26), ymd(2014, 3, 3))), [26, 27, 28, 1, 2]);
+        assert_eq!(collect(super::range(ymd(2014, 2, 26), ymd(2014, 3, 3)).rev()),
+                   [2, 1, 28, 27, 26]);
+        assert_eq!(collect(super::range(ymd(2014, 3, 3), ymd(2014, 3, 3))), vec![]);
+        assert_eq!(collect(super::range(ymd(2014, 3, 3), ymd(2014, 3, 1))), vec![]);
+        assert_eq!(collect(super::range(ymd(2014, 3, 1), ymd(2014, 3, 11))
+                               .step_by(Duration::days(3))), [1, 4, 7, 10]);
+        assert_eq!(collect(super::range(ymd(2014, 3, 1), ymd(2014, 3, 11))
+                               .step_by(Duration::days(3)).rev()), [10, 7, 4, 1]);
+        assert_eq!(collect(super::range(ymd(2014, 3, 1), ymd(2014, 3, 10))
+                               .step_by(Duration::days(3)).step_by(Duration::days(2))),
+                   [1, 3, 5, 7]);
+        assert_eq!(collect(super::range(ymd(2014, 3, 1), ymd(2014, 3, 2))
+                               .step_by(Duration::weeks(1000000))), [1]);
+        assert_eq!(super::range(ymd(2014, 1, 1), ymd(2015, 1, 1)).len(), 365);
+        assert_eq!(super::range(ymd(2014, 1, 1), ymd(2015, 1, 1))
+                       .step_by(Duration::weeks(1)).len(), 53);
+
+        let mut it = super::range(ymd(2014, 3, 1), ymd(2014, 3, 5));
+        assert_eq!(it.next(), Some(ymd(2014, 3, 1)));
+        assert_eq!(it.next_back(), Some(ymd(2014, 3, 4)));
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(it.next_back(), Some(ymd(2014, 3, 3)));
+        assert_eq!(it.next(), Some(ymd(2014, 3, 2)));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next_back(), None);
+
+        assert_eq!(collect(ymd(2014, 2, 27).iter_days().take(3)), [27, 28, 1]);
+        assert_eq!(collect(ymd(2014, 2, 27).iter_weeks().take(3)), [27, 6, 13]);
+        assert_eq!(MAX.pred().iter_days().collect::<Vec<_>>(), [MAX.pred(), MAX]);
+        assert_eq!(MAX.iter_weeks().collect::<Vec<_>>(), [MAX]);
+        assert_eq!(MIN.iter_days().next_back(), Some(MAX));
+        assert_eq!(MIN.iter_days().len() as i64, (MAX - MIN).num_days() + 1);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_date_range_step_by_partial_day() {
+        NaiveDate::from_ymd(2014, 3, 1).iter_days().step_by(Duration::hours(36));
+    }
+
     #[test]
     fn test_date_add() {
         fn check((y1,m1,d1): (i32, u32, u32), rhs: Duration, (y,m,d): (i32, u32, u32)) {
@@ -752,6 +1175,134 @@ mod tests {
         check((2414, 1, 1), (2014, 1, 1), Duration::days(365*400 + 97));
     }
 
+    #[test]
+    fn test_date_add_months() {
+        let ymd = |&: y,m,d| NaiveDate::from_ymd(y,m,d);
+        let add = |&: (y,m,d): (i32, u32, u32), n, policy|
+            ymd(y,m,d).checked_add_months(Months::new(n), policy);
+        let sub = |&: (y,m,d): (i32, u32, u32), n, policy|
+            ymd(y,m,d).checked_sub_months(Months::new(n), policy);
+
+        assert_eq!(add((2014, 1, 15), 1, MonthPolicy::Fail), Some(ymd(2014, 2, 15)));
+        assert_eq!(add((2014, 1, 15), 0, MonthPolicy::Fail), Some(ymd(2014, 1, 15)));
+        assert_eq!(add((2014, 11, 15), 3, MonthPolicy::Fail), Some(ymd(2015, 2, 15)));
+        assert_eq!(sub((2014, 1, 15), 13, MonthPolicy::Fail), Some(ymd(2012, 12, 15)));
+        assert_eq!(add((2014, 1, 30), 1, MonthPolicy::Clamp), Some(ymd(2014, 2, 28)));
+        assert_eq!(add((2014, 1, 30), 1, MonthPolicy::Overflow), Some(ymd(2014, 3, 2)));
+        assert_eq!(add((2014, 1, 30), 1, MonthPolicy::Fail), None);
+        assert_eq!(add((2016, 1, 31), 1, MonthPolicy::Clamp), Some(ymd(2016, 2, 29)));
+        assert_eq!(add((2016, 1, 31), 1, MonthPolicy::Overflow), Some(ymd(2016, 3, 2)));
+        assert_eq!(add((2014, 5, 31), 1, MonthPolicy::Clamp), Some(ymd(2014, 6, 30)));
+        assert_eq!(add((2014, 5, 31), 1, MonthPolicy::Overflow), Some(ymd(2014, 7, 1)));
+        assert_eq!(sub((2014, 3, 31), 1, MonthPolicy::Clamp), Some(ymd(2014, 2, 28)));
+        assert_eq!(add((2012, 2, 29), 12, MonthPolicy::Clamp), Some(ymd(2013, 2, 28)));
+        assert_eq!(add((2012, 2, 29), 48, MonthPolicy::Fail), Some(ymd(2016, 2, 29)));
+        assert_eq!(MAX.checked_add_months(Months::new(1), MonthPolicy::Clamp), None);
+        assert_eq!(MIN.checked_sub_months(Months::new(1), MonthPolicy::Clamp), None);
+
+        assert_eq!(ymd(2014, 1, 31) + Months::new(1), ymd(2014, 2, 28));
+        assert_eq!(ymd(2014, 1, 31) - Months::new(2), ymd(2013, 11, 30));
+        assert_eq!(ymd(2012, 2, 29) + Months::years(1), ymd(2013, 2, 28));
+    }
+
+    #[test]
+    fn test_date_period() {
+        let ymd = |&: y,m,d| NaiveDate::from_ymd(y,m,d);
+        let check = |&: (y1,m1,d1): (i32, u32, u32), (y2,m2,d2): (i32, u32, u32), period| {
+            let (lhs, rhs) = (ymd(y1,m1,d1), ymd(y2,m2,d2));
+            assert_eq!(lhs.period_since(&rhs), period);
+            assert_eq!(rhs.checked_add_period(period), Some(lhs));
+        };
+
+        check((2014, 1, 1), (2014, 1, 1), Period::zero());
+        check((2015, 3, 4), (2014, 1, 1), Period::new(1, 2, 3));
+        check((2014, 1, 1), (2015, 3, 4), Period::new(-1, -2, -3));
+        check((2014, 3, 1), (2014, 1, 31), Period::new(0, 1, 1));       // via 2014-02-28
+        check((2014, 3, 1), (2014, 2, 28), Period::days(1));
+        check((2014, 1, 25), (2014, 3, 20), Period::new(0, -1, -26));    // via 2014-02-20
+        check((2016, 2, 29), (1980, 2, 29), Period::years(36));          // age
+        check((2015, 2, 28), (1980, 2, 29), Period::new(34, 11, 30));
+        check((-1, 12, 31), (1, 1, 1), Period::new(-1, 0, -1));
+        check((2014, 2, 28), (2014, 3, 31), Period::months(-1));        // clamped from 03-31
+        check((2014, 2, 27), (2014, 3, 31), Period::new(0, -1, -1));
+        check((2014, 1, 31), (2014, 3, 30), Period::new(0, -1, -28));   // via 2014-02-28
+
+        // the round trip around the ends of months, in both directions
+        let dates: Vec<NaiveDate> = ymd(2015, 11, 25).iter_days().take(200)
+                                                     .filter(|d| d.day() >= 27 || d.day() <= 2)
+                                                     .collect();
+        for &lhs in dates.iter() {
+            for &rhs in dates.iter() {
+                let period = lhs.period_since(&rhs);
+                assert_eq!(rhs.checked_add_period(period), Some(lhs));
+                let signs = [period.num_years(), period.num_months(), period.num_days()];
+                assert!(signs.iter().all(|&v| v >= 0) || signs.iter().all(|&v| v <= 0));
+            }
+        }
+
+        assert_eq!(ymd(2014, 1, 31) + Period::new(0, 1, 1), ymd(2014, 3, 1));
+        assert_eq!(ymd(2014, 3, 1) - Period::new(0, 1, 1), ymd(2014, 1, 31));
+        assert_eq!(MAX.checked_add_period(Period::days(1)), None);
+    }
+
+    #[test]
+    fn test_date_easter() {
+        let ymd = |&: y,m,d| NaiveDate::from_ymd(y,m,d);
+        assert_eq!(NaiveDate::easter(2015), ymd(2015, 4, 5));
+        assert_eq!(NaiveDate::easter(2016), ymd(2016, 3, 27));
+        assert_eq!(NaiveDate::easter(1818), ymd(1818, 3, 22)); // the earliest possible
+        assert_eq!(NaiveDate::easter(1943), ymd(1943, 4, 25)); // the latest possible
+        assert_eq!(NaiveDate::easter(0), ymd(0, 4, 9));
+        assert_eq!(NaiveDate::easter_opt(MIN.year()), Some(ymd(MIN.year(), 4, 6)));
+        assert_eq!(NaiveDate::easter_opt(MAX.year()), Some(ymd(MAX.year(), 3, 24)));
+        assert_eq!(NaiveDate::easter_opt(MAX.year() + 1), None);
+
+        assert_eq!(NaiveDate::orthodox_easter(2015), ymd(2015, 4, 12));
+        assert_eq!(NaiveDate::orthodox_easter(2016), ymd(2016, 5, 1));
+        assert_eq!(NaiveDate::orthodox_easter(2038), ymd(2038, 4, 25)); // same as Western
+        assert_eq!(NaiveDate::orthodox_easter(1), ymd(1, 3, 25)); // March 27 in Julian
+        assert_eq!(NaiveDate::orthodox_easter_opt(MIN.year() + 6), Some(ymd(MIN.year(), 11, 23)));
+        assert_eq!(NaiveDate::orthodox_easter_opt(MIN.year() + 5), None);
+        assert_eq!(NaiveDate::orthodox_easter_opt(MAX.year() - 5), Some(ymd(MAX.year(), 8, 18)));
+        assert_eq!(NaiveDate::orthodox_easter_opt(MAX.year() - 4), None);
+
+        assert_eq!(NaiveDate::ash_wednesday(2015), ymd(2015, 2, 18));
+        assert_eq!(NaiveDate::good_friday(2015), ymd(2015, 4, 3));
+        assert_eq!(NaiveDate::ascension(2015), ymd(2015, 5, 14));
+        assert_eq!(NaiveDate::pentecost(2015), ymd(2015, 5, 24));
+    }
+
+    #[test]
+    fn test_date_business_days() {
+        let ymd = |&: y,m,d| NaiveDate::from_ymd(y,m,d);
+        let mut calendar = RuleCalendar::new();
+        calendar.add_rule(HolidayRule::Fixed(7, 3)); // observed Independence Day in 2015
+
+        let thu = ymd(2015, 7, 2);
+        assert!(thu.is_business_day(&calendar));
+        assert!(!ymd(2015, 7, 3).is_business_day(&calendar));
+        assert_eq!(thu.add_business_days(0, &calendar), Some(thu));
+        assert_eq!(thu.add_business_days(2, &calendar), Some(ymd(2015, 7, 7)));
+        assert_eq!(ymd(2015, 7, 7).add_business_days(-2, &calendar), Some(thu));
+        assert_eq!(thu.business_days_between(&ymd(2015, 7, 7), &calendar), 2);
+        assert_eq!(ymd(2015, 7, 7).business_days_between(&thu, &calendar), -2);
+        assert_eq!(thu.business_days_between(&thu, &calendar), 0);
+
+        // from the weekend
+        let sat = ymd(2015, 7, 11);
+        assert_eq!(sat.add_business_days(1, &calendar), Some(ymd(2015, 7, 13)));
+        assert_eq!(sat.add_business_days(-1, &calendar), Some(ymd(2015, 7, 10)));
+        assert_eq!(sat.business_days_between(&ymd(2015, 7, 13), &calendar), 1);
+        assert_eq!(ymd(2015, 7, 13).business_days_between(&ymd(2015, 7, 10), &calendar), -1);
+
+        let every = RuleCalendar::with_weekend(&[Weekday::Mon, Weekday::Tue, Weekday::Wed,
+                                                 Weekday::Thu, Weekday::Fri, Weekday::Sat,
+                                                 Weekday::Sun]);
+        assert_eq!(thu.add_business_days(1, &every), None);
+        assert_eq!(thu.add_business_days(0, &every), Some(thu));
+        assert_eq!(MAX.add_business_days(1, &calendar), None);
+    }
+
     #[test]
     fn test_date_fmt() {
         assert_eq!(format!("{:?}", NaiveDate::from_ymd(2012,  3, 4)),   "2012-03-04");
@@ -782,6 +1333,8 @@ mod tests {
         assert_eq!(d.format("%F").to_string(), "2012-03-04");
         assert_eq!(d.format("%v").to_string(), " 4-Mar-2012");
         assert_eq!(d.format("%t%n%%%n%t").to_string(), "\t\n%\n\t");
+        assert_eq!(d.format("%-d,%_m,%0e,%-j").to_string(), "4, 3,04,64");
+        assert_eq!(d.format("%^a,%^B,%^-d").to_string(), "SUN,MARCH,4");
 
         // corner cases
         assert_eq!(NaiveDate::from_ymd(2007, 12, 31).format("%G,%g,%U,%W,%V").to_string(),
@@ -789,6 +1342,64 @@ mod tests {
         assert_eq!(NaiveDate::from_ymd(2010, 1, 3).format("%G,%g,%U,%W,%V").to_string(),
                    "2009,09,01,00,53");
     }
+
+    #[test]
+    fn test_date_format_localized() {
+        let d = NaiveDate::from_ymd(2012, 3, 4);
+        assert_eq!(d.format_localized("%a %b %x", &locale::EN_US).to_string(),
+                   d.format("%a %b %x").to_string());
+        assert_eq!(d.format_localized("%A, %e. %B %Y", &locale::DE_DE).to_string(),
+                   "Sonntag,  4. März 2012");
+        assert_eq!(d.format_localized("%x|%^b", &locale::DE_DE).to_string(), "04.03.2012|MÄR");
+        assert_eq!(d.format_localized("%a %d %b %Y", &locale::FR_FR).to_string(),
+                   "dim. 04 mars 2012");
+        assert_eq!(d.format_localized("%x (%a)", &locale::JA_JP).to_string(),
+                   "2012年03月04日 (日)");
+        assert_eq!(d.format_localized("%B %-d일 %A", &locale::KO_KR).to_string(),
+                   "3월 4일 일요일");
+
+        // the tables can be loaded at runtime
+        let months: Vec<String> = range(1, 13).map(|m| format!("M{:02}", m)).collect();
+        let weekdays: Vec<String> = range(1, 8).map(|w| format!("W{}", w)).collect();
+        let am_pm = vec!["a.m.".to_string(), "p.m.".to_string()];
+        let layout = "%Y/%m/%d".to_string();
+        fn as_strs(v: &Vec<String>) -> Vec<&str> { v.iter().map(|s| s.as_slice()).collect() }
+        let (months, weekdays, am_pm) = (as_strs(&months), as_strs(&weekdays), as_strs(&am_pm));
+        let loaded = locale::Locale { name: "xx_XX", short_months: &months[..],
+                                      long_months: &months[..], short_weekdays: &weekdays[..],
+                                      long_weekdays: &weekdays[..], am_pm: &am_pm[..],
+                                      date_format: &layout[..], time_format: "%H:%M:%S",
+                                      datetime_format: "%x %X", time_ampm_format: "%I:%M %p" };
+        assert_eq!(d.format_localized("%x %b %a", &loaded).to_string(), "2012/03/04 M03 W7");
+        assert_eq!(d.and_hms(15, 0, 0).format_localized("%r", &loaded).to_string(),
+                   "03:00 p.m.");
+    }
+
+    #[test]
+    fn test_date_parse_from_str() {
+        let ymd = |&: y,m,d| NaiveDate::from_ymd(y,m,d);
+        assert_eq!(NaiveDate::parse_from_str("2012-03-04", "%Y-%m-%d"), Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str(" 4-MAR-2012", "%v"), Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("Sunday, March 4, 2012", "%A, %B %e, %Y"),
+                   Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("03/04/12", "%D"), Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("SUNDAY, 4 MARCH 2012", "%^A, %-d %^B %Y"),
+                   Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("03/04/69", "%D"), Ok(ymd(1969, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("20 12-064", "%C%y-%j"), Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("2012,10,Sun", "%Y,%U,%a"), Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("2012,09,7", "%Y,%W,%u"), Ok(ymd(2012, 3, 4)));
+        assert_eq!(NaiveDate::parse_from_str("2010,00,0", "%Y,%W,%w"), Ok(ymd(2010, 1, 3)));
+        assert_eq!(NaiveDate::parse_from_str("2009-W53-7", "%G-W%V-%u"), Ok(ymd(2010, 1, 3)));
+        assert_eq!(NaiveDate::parse_from_str("-0307-03-04", "%Y-%m-%d"), Ok(ymd(-307, 3, 4)));
+
+        assert!(NaiveDate::parse_from_str("2012-02-30", "%Y-%m-%d").is_err()); // invalid
+        assert!(NaiveDate::parse_from_str("Mon, 2012-03-04", "%a, %F").is_err()); // inconsistent
+        assert!(NaiveDate::parse_from_str("2012-03-04 2013", "%F %Y").is_err()); // inconsistent
+        assert!(NaiveDate::parse_from_str("2012-03", "%Y-%m").is_err()); // insufficient
+        assert!(NaiveDate::parse_from_str("2012-03-04x", "%F").is_err()); // trailing input
+        assert!(NaiveDate::parse_from_str("2012-03-04", "%F %").is_err()); // stray `%`
+    }
 }
 
 /**
@@ -13,9 +13,12 @@ use std::ops::{Add, Sub};
 use {Weekday, Timelike, Datelike};
 use div::div_mod_floor;
 use duration::Duration;
+use months::{Months, MonthPolicy};
 use naive::time::NaiveTime;
 use naive::date::NaiveDate;
-use format::DelayedFormat;
+use format::{Item, StrftimeItems, DelayedFormat};
+use locale::Locale;
+use parse::{Parsed, ParseResult, parse_and_resolve};
 
 /// ISO 8601 combined date and time without timezone.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
@@ -81,11 +84,52 @@ impl NaiveDateTime {
         (ndays - 719163) * 86400 + nseconds
     }
 
+    /// Adds given number of months to the current date, keeping the time.
+    /// See `NaiveDate::checked_add_months` for the handling of the nonexistent day.
+    #[inline]
+    pub fn checked_add_months(&self, months: Months,
+                              policy: MonthPolicy) -> Option<NaiveDateTime> {
+        self.date.checked_add_months(months, policy).map(|date| date.and_time(self.time))
+    }
+
+    /// Subtracts given number of months from the current date, keeping the time.
+    /// See `NaiveDate::checked_add_months` for the handling of the nonexistent day.
+    #[inline]
+    pub fn checked_sub_months(&self, months: Months,
+                              policy: MonthPolicy) -> Option<NaiveDateTime> {
+        self.date.checked_sub_months(months, policy).map(|date| date.and_time(self.time))
+    }
+
+    /// Formats the combined date and time with the specified formatting items.
+    #[inline]
+    pub fn format_with_items<'a, I>(&self, items: I) -> DelayedFormat<'a, I>
+            where I: Iterator<Item=Item<'a>> + Clone {
+        DelayedFormat::new(Some(self.date.clone()), Some(self.time.clone()), items)
+    }
+
     /// Formats the combined date and time in the specified format string.
     /// See the `format` module on the supported escape sequences.
     #[inline]
-    pub fn format<'a>(&'a self, fmt: &'a str) -> DelayedFormat<'a> {
-        DelayedFormat::new(Some(self.date.clone()), Some(self.time.clone()), fmt)
+    pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new(fmt))
+    }
+
+    /// Formats the combined date and time in the specified format string and locale.
+    /// The names, AM/PM markers and the layouts of `%c`, `%x`, `%X` and `%r` follow the locale.
+    #[inline]
+    pub fn format_localized<'a>(&self, fmt: &'a str,
+                                locale: &'a Locale<'a>) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new_with_locale(fmt, locale)).with_locale(locale)
+    }
+
+    /// Parses a string with the specified format string and makes a new `NaiveDateTime`.
+    /// See the `format` module on the supported escape sequences.
+    ///
+    /// Fails when the string does not match the format string,
+    /// or the parsed fields are insufficient, inconsistent or out of range.
+    pub fn parse_from_str(s: &str, fmt: &str) -> ParseResult<NaiveDateTime> {
+        parse_and_resolve(s, StrftimeItems::new(fmt),
+                          |&: parsed: &Parsed| parsed.to_naive_datetime())
     }
 }
 
@@ -205,6 +249,24 @@ impl Sub<Duration> for NaiveDateTime {
     fn sub(self, rhs: Duration) -> NaiveDateTime { self.add(-rhs) }
 }
 
+impl Add<Months> for NaiveDateTime {
+    type Output = NaiveDateTime;
+
+    fn add(self, rhs: Months) -> NaiveDateTime {
+        self.checked_add_months(rhs, MonthPolicy::Clamp)
+            .expect("`NaiveDateTime + Months` out of range")
+    }
+}
+
+impl Sub<Months> for NaiveDateTime {
+    type Output = NaiveDateTime;
+
+    fn sub(self, rhs: Months) -> NaiveDateTime {
+        self.checked_sub_months(rhs, MonthPolicy::Clamp)
+            .expect("`NaiveDateTime - Months` out of range")
+    }
+}
+
 impl fmt::Debug for NaiveDateTime {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}T{:?}", self.date, self.time)
@@ -221,7 +283,9 @@ impl fmt::Display for NaiveDateTime {
 mod tests {
     use super::NaiveDateTime;
     use duration::Duration;
+    use months::{Months, MonthPolicy};
     use naive::date::NaiveDate;
+    use locale;
     use std::i64;
 
     #[test]
@@ -252,6 +316,19 @@ mod tests {
                    ymdhms(2014, 4, 26, 7, 8, 9));
     }
 
+    #[test]
+    fn test_datetime_add_months() {
+        let ymdhms = |&: y,m,d,h,n,s| NaiveDate::from_ymd(y,m,d).and_hms(h,n,s);
+        assert_eq!(ymdhms(2014, 1, 31, 7, 8, 9) + Months::new(1), ymdhms(2014, 2, 28, 7, 8, 9));
+        assert_eq!(ymdhms(2014, 1, 31, 7, 8, 9) - Months::years(2), ymdhms(2012, 1, 31, 7, 8, 9));
+        assert_eq!(ymdhms(2014, 1, 31, 7, 8, 9).checked_add_months(Months::new(1),
+                                                                   MonthPolicy::Overflow),
+                   Some(ymdhms(2014, 3, 3, 7, 8, 9)));
+        assert_eq!(ymdhms(2014, 3, 31, 7, 8, 9).checked_sub_months(Months::new(1),
+                                                                   MonthPolicy::Fail),
+                   None);
+    }
+
     #[test]
     fn test_datetime_sub() {
         let ymdhms = |&: y,m,d,h,n,s| NaiveDate::from_ymd(y,m,d).and_hms(h,n,s);
@@ -282,6 +359,28 @@ mod tests {
         let dt = NaiveDate::from_ymd(2010, 9, 8).and_hms_milli(7, 6, 54, 321);
         assert_eq!(dt.format("%c").to_string(), "Wed Sep  8 07:06:54 2010");
         assert_eq!(dt.format("%t%n%%%n%t").to_string(), "\t\n%\n\t");
+        assert_eq!(dt.format_localized("%c", &locale::DE_DE).to_string(),
+                   "Mi 08 Sep 2010 07:06:54");
+        assert_eq!(dt.format_localized("[%c] %Y", &locale::KO_KR).to_string(),
+                   "[2010년 09월 08일 (수) 오전 07시 06분 54초] 2010");
+    }
+
+    #[test]
+    fn test_datetime_parse_from_str() {
+        let ymdhms = |&: y,m,d,h,n,s| NaiveDate::from_ymd(y,m,d).and_hms(h,n,s);
+        assert_eq!(NaiveDateTime::parse_from_str("2014-5-7T12:34:56", "%Y-%m-%dT%H:%M:%S"),
+                   Ok(ymdhms(2014, 5, 7, 12, 34, 56)));
+        assert_eq!(NaiveDateTime::parse_from_str("Wed Sep  8 07:06:54 2010", "%c"),
+                   Ok(ymdhms(2010, 9, 8, 7, 6, 54)));
+        assert_eq!(NaiveDateTime::parse_from_str("[07:06:54 08/Sep/2010]", "[%T %d/%b/%Y]"),
+                   Ok(ymdhms(2010, 9, 8, 7, 6, 54)));
+        assert!(NaiveDateTime::parse_from_str("2010-09-08", "%F").is_err()); // no time
+        assert!(NaiveDateTime::parse_from_str("07:06:54", "%T").is_err()); // no date
+
+        // round trip
+        let dt = NaiveDate::from_ymd(2010, 9, 8).and_hms_nano(7, 6, 54, 321);
+        let fmt = "%a %B %e %Y, week %V of %G, %I:%M:%S.%f %p";
+        assert_eq!(NaiveDateTime::parse_from_str(&dt.format(fmt).to_string()[..], fmt), Ok(dt));
     }
 }
 
@@ -14,7 +14,9 @@ use Timelike;
 use div::div_mod_floor;
 use offset::Offset;
 use duration::Duration;
-use format::DelayedFormat;
+use format::{Item, StrftimeItems, DelayedFormat};
+use locale::Locale;
+use parse::{Parsed, ParseResult, parse_and_resolve};
 
 /// ISO 8601 time without timezone.
 /// Allows for the nanosecond precision and optional leap second representation.
@@ -118,11 +120,37 @@ impl NaiveTime {
         Some(NaiveTime { secs: secs, frac: nano })
     }
 
+    /// Formats the time with the specified formatting items.
+    #[inline]
+    pub fn format_with_items<'a, I>(&self, items: I) -> DelayedFormat<'a, I>
+            where I: Iterator<Item=Item<'a>> + Clone {
+        DelayedFormat::new(None, Some(self.clone()), items)
+    }
+
     /// Formats the time in the specified format string.
     /// See the `format` module on the supported escape sequences.
     #[inline]
-    pub fn format<'a>(&'a self, fmt: &'a str) -> DelayedFormat<'a> {
-        DelayedFormat::new(None, Some(self.clone()), fmt)
+    pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new(fmt))
+    }
+
+    /// Formats the time in the specified format string and locale.
+    /// The names, AM/PM markers and the layouts of `%c`, `%x`, `%X` and `%r` follow the locale.
+    #[inline]
+    pub fn format_localized<'a>(&self, fmt: &'a str,
+                                locale: &'a Locale<'a>) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new_with_locale(fmt, locale)).with_locale(locale)
+    }
+
+    /// Parses a string with the specified format string and makes a new `NaiveTime`.
+    /// See the `format` module on the supported escape sequences.
+    /// The hour and minute are required, and the second and nanosecond default to zero.
+    ///
+    /// Fails when the string does not match the format string,
+    /// or the parsed fields are insufficient, inconsistent or out of range.
+    pub fn parse_from_str(s: &str, fmt: &str) -> ParseResult<NaiveTime> {
+        parse_and_resolve(s, StrftimeItems::new(fmt),
+                          |&: parsed: &Parsed| parsed.to_naive_time())
     }
 
     /// Returns a triple of the hour, minute and second numbers.
@@ -254,6 +282,7 @@ mod tests {
     use super::NaiveTime;
     use Timelike;
     use duration::Duration;
+    use locale;
     use std::u32;
 
     #[test]
@@ -374,13 +403,62 @@ mod tests {
         assert_eq!(t.format("%H,%k,%I,%l,%P,%p").to_string(), "03, 3,03, 3,am,AM");
         assert_eq!(t.format("%M").to_string(), "05");
         assert_eq!(t.format("%S,%f").to_string(), "07,098765432");
+        assert_eq!(t.format("%.3f,%.6f,%.9f,%.f").to_string(),
+                   ".098,.098765,.098765432,.098765432");
+        assert_eq!(t.format("%3f,%6f,%9f").to_string(), "098,098765,098765432");
         assert_eq!(t.format("%R").to_string(), "03:05");
         assert_eq!(t.format("%T,%X").to_string(), "03:05:07,03:05:07");
         assert_eq!(t.format("%r").to_string(), "03:05:07 AM");
         assert_eq!(t.format("%t%n%%%n%t").to_string(), "\t\n%\n\t");
+        assert_eq!(t.format("%-H,%_M,%0k,%^P").to_string(), "3, 5,03,AM");
 
         // corner cases
         assert_eq!(NaiveTime::from_hms(13, 57, 9).format("%r").to_string(), "01:57:09 PM");
+        assert_eq!(NaiveTime::from_hms(3, 5, 7).format("%T%.f,%.3f").to_string(),
+                   "03:05:07,.000");
+        assert_eq!(NaiveTime::from_hms_micro(3, 5, 7, 250_000).format("%.f").to_string(),
+                   ".250");
+        assert_eq!(NaiveTime::from_hms_micro(3, 5, 7, 250_001).format("%.f").to_string(),
+                   ".250001");
+        assert_eq!(NaiveTime::from_hms_milli(23, 59, 59, 1_500).format("%T%.f,%f").to_string(),
+                   "23:59:60.500,500000000");
+    }
+
+    #[test]
+    fn test_time_format_localized() {
+        let t = NaiveTime::from_hms(15, 5, 7);
+        assert_eq!(t.format_localized("%r,%P", &locale::EN_US).to_string(), "03:05:07 PM,pm");
+        assert_eq!(t.format_localized("%X|%r|%p", &locale::DE_DE).to_string(),
+                   "15:05:07|15:05:07|");
+        assert_eq!(t.format_localized("%X|%r", &locale::JA_JP).to_string(),
+                   "15時05分07秒|午後03時05分07秒");
+        assert_eq!(t.format_localized("%p %l시", &locale::KO_KR).to_string(), "오후  3시");
+    }
+
+    #[test]
+    fn test_time_parse_from_str() {
+        let hms = |&: h,m,s| NaiveTime::from_hms(h,m,s);
+        assert_eq!(NaiveTime::parse_from_str("03:05:07", "%H:%M:%S"), Ok(hms(3, 5, 7)));
+        assert_eq!(NaiveTime::parse_from_str(" 3:05", "%k:%M"), Ok(hms(3, 5, 0)));
+        assert_eq!(NaiveTime::parse_from_str("01:57:09 PM", "%r"), Ok(hms(13, 57, 9)));
+        assert_eq!(NaiveTime::parse_from_str("12:00 am", "%I:%M %P"), Ok(hms(0, 0, 0)));
+        assert_eq!(NaiveTime::parse_from_str("03:05:07,098765432", "%T,%f"),
+                   Ok(NaiveTime::from_hms_nano(3, 5, 7, 98765432)));
+        assert_eq!(NaiveTime::parse_from_str("03:05:07.25", "%T.%f"),
+                   Ok(NaiveTime::from_hms_milli(3, 5, 7, 250)));
+        assert_eq!(NaiveTime::parse_from_str("03:05:07.25", "%T%.f"),
+                   Ok(NaiveTime::from_hms_milli(3, 5, 7, 250)));
+        assert_eq!(NaiveTime::parse_from_str("03:05:07", "%T%.f"), Ok(hms(3, 5, 7)));
+        assert_eq!(NaiveTime::parse_from_str("03:05:07250", "%T%3f"),
+                   Ok(NaiveTime::from_hms_milli(3, 5, 7, 250)));
+        assert_eq!(NaiveTime::parse_from_str("23:59:60", "%T"),
+                   Ok(NaiveTime::from_hms_milli(23, 59, 59, 1_000)));
+
+        assert!(NaiveTime::parse_from_str("24:00:00", "%T").is_err()); // out of range
+        assert!(NaiveTime::parse_from_str("13:00 PM", "%I:%M %p").is_err()); // out of range
+        assert!(NaiveTime::parse_from_str("13:00 AM", "%H:%M %p").is_err()); // inconsistent
+        assert!(NaiveTime::parse_from_str("01:00", "%I:%M").is_err()); // insufficient
+        assert!(NaiveTime::parse_from_str("03:05:07 ", "%T").is_err()); // trailing input
     }
 }
 
@@ -9,7 +9,7 @@
 use std::fmt;
 use stdtime;
 
-use {Weekday, Datelike, Timelike};
+use Weekday;
 use div::div_mod_floor;
 use duration::Duration;
 use naive::date::NaiveDate;
@@ -314,6 +314,16 @@ pub trait Offset: Clone + fmt::Debug {
     /// Converts the UTC `NaiveDateTime` to the local time.
     /// The UTC is continuous and thus this cannot fail (but can give the duplicate local time).
     fn to_local_datetime(&self, utc: &NaiveDateTime) -> NaiveDateTime;
+
+    /// Returns the offset which is in effect at given UTC `NaiveDateTime`.
+    ///
+    /// Offsets with a constant difference from UTC can simply return itself (the default).
+    /// Offsets with a varying difference should return a copy with the cached difference
+    /// updated, so that `local_minus_utc` and the string representation are correct
+    /// for every `DateTime` made out of that UTC datetime.
+    fn at_utc(&self, _utc: &NaiveDateTime) -> Self {
+        self.clone()
+    }
 }
 
 /// The UTC timescale. This is the most efficient offset when you don't need the local time.
@@ -475,23 +485,29 @@ impl Local {
         DateTime::from_utc(date.and_time(time) + Duration::seconds(-tm.tm_utcoff as i64), offset)
     }
 
-    /// Converts a local `NaiveDateTime` to the `time::Timespec`.
-    fn datetime_to_timespec(d: &NaiveDateTime) -> stdtime::Timespec {
-        let tm = stdtime::Tm {
-            tm_sec: d.second() as i32,
-            tm_min: d.minute() as i32,
-            tm_hour: d.hour() as i32,
-            tm_mday: d.day() as i32,
-            tm_mon: d.month0() as i32, // yes, C is that strange...
-            tm_year: d.year() - 1900, // this doesn't underflow, we know that d is `NaiveDateTime`.
-            tm_wday: 0, // to_local ignores this
-            tm_yday: 0, // and this
-            tm_isdst: -1,
-            tm_utcoff: 1, // this is arbitrary but should be nonzero
-                          // in order to make `to_timespec` use `rust_mktime` internally.
-            tm_nsec: d.nanosecond() as i32,
-        };
-        tm.to_timespec()
+    /// Returns the offset from UTC in seconds, which is in effect at given number of
+    /// non-leap seconds since January 1, 1970 0:00:00 UTC.
+    fn utcoff_at(secs: i64) -> i32 {
+        stdtime::at(stdtime::Timespec::new(secs, 0)).tm_utcoff
+    }
+
+    /// Returns the UTC datetimes which map to given local datetime in the ascending order,
+    /// where `utcoff_at` gives the offset from UTC in seconds as `Local::utcoff_at` does.
+    fn local_to_utcs<F>(local: &NaiveDateTime, utcoff_at: F) -> Vec<NaiveDateTime>
+            where F: Fn(i64) -> i32 {
+        // every offset is less than a day, and we assume that the local timezone changes
+        // its offset at most once within two days. the only possible offsets are then
+        // those in effect a day before and a day after the local datetime (taken as UTC).
+        let secs = local.num_seconds_from_unix_epoch();
+        let mut utcs: Vec<NaiveDateTime> = Vec::with_capacity(2);
+        for &utcoff in [utcoff_at(secs - 86400), utcoff_at(secs + 86400)].iter() {
+            if utcoff_at(secs - utcoff as i64) == utcoff {
+                let utc = *local - Duration::seconds(utcoff as i64);
+                if !utcs.contains(&utc) { utcs.push(utc); }
+            }
+        }
+        utcs.sort();
+        utcs
     }
 
     /// Returns a `Date` which corresponds to the current date.
@@ -526,8 +542,14 @@ impl Offset for Local {
     }
 
     fn from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<DateTime<Local>> {
-        let timespec = Local::datetime_to_timespec(local);
-        LocalResult::Single(Local::tm_to_datetime(stdtime::at(timespec)))
+        let utcs = Local::local_to_utcs(local, Local::utcoff_at);
+        let offset = self.clone();
+        match utcs.len() {
+            0 => LocalResult::None,
+            1 => LocalResult::Single(DateTime::from_utc(utcs[0], offset)),
+            _ => LocalResult::Ambiguous(DateTime::from_utc(utcs[0], offset),
+                                        DateTime::from_utc(utcs[1], offset)),
+        }
     }
 
     fn to_local_date(&self, utc: &NaiveDate) -> NaiveDate { self.cached.to_local_date(utc) }
@@ -535,6 +557,11 @@ impl Offset for Local {
     fn to_local_datetime(&self, utc: &NaiveDateTime) -> NaiveDateTime {
         self.cached.to_local_datetime(utc)
     }
+
+    fn at_utc(&self, utc: &NaiveDateTime) -> Local {
+        let utcoff = Local::utcoff_at(utc.num_seconds_from_unix_epoch());
+        Local { cached: FixedOffset::east(utcoff) }
+    }
 }
 
 impl fmt::Debug for Local {
@@ -546,3 +573,35 @@ impl fmt::Display for Local {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { self.cached.fmt(f) }
 }
 
+#[cfg(test)]
+mod tests {
+    use naive::date::NaiveDate;
+    use super::Local;
+
+    #[test]
+    fn test_local_to_utcs() {
+        // EST5EDT in 2015, without depending on the timezone of the process:
+        // the daylight saving time is from 2015-03-08 07:00 to 2015-11-01 06:00 UTC
+        fn utcoff_at(secs: i64) -> i32 {
+            if 1425798000 <= secs && secs < 1446357600 { -4 * 3600 } else { -5 * 3600 }
+        }
+        let ymdhm = |&: m: u32, d: u32, h: u32, n: u32| {
+            NaiveDate::from_ymd(2015, m, d).and_hms(h, n, 0)
+        };
+        let utcs = |&: m: u32, d: u32, h: u32, n: u32| {
+            Local::local_to_utcs(&ymdhm(m, d, h, n), utcoff_at)
+        };
+
+        assert_eq!(utcs(7, 1, 12, 0), [ymdhm(7, 1, 16, 0)]);
+
+        // spring forward: 2:00 EST becomes 3:00 EDT
+        assert_eq!(utcs(3, 8, 1, 59), [ymdhm(3, 8, 6, 59)]);
+        assert_eq!(utcs(3, 8, 2, 30), vec![]);
+        assert_eq!(utcs(3, 8, 3, 0), [ymdhm(3, 8, 7, 0)]);
+
+        // fall back: 2:00 EDT becomes 1:00 EST
+        assert_eq!(utcs(11, 1, 0, 59), [ymdhm(11, 1, 4, 59)]);
+        assert_eq!(utcs(11, 1, 1, 30), [ymdhm(11, 1, 5, 30), ymdhm(11, 1, 6, 30)]);
+        assert_eq!(utcs(11, 1, 2, 0), [ymdhm(11, 1, 7, 0)]);
+    }
+}
@@ -1,13 +1,308 @@
-use std::num::Int;
-use std::cmp;
-use ::{Offset};
-
 //
 //  parse.rs  --  parsing for various standardized date and time string formats
 //
 //  John Nagle
 //  January, 2015
 //
+
+use std::{fmt, num};
+use std::num::{Int, ToPrimitive};
+use std::ascii::AsciiExt;
+use std::error::Error;
+use std::str::FromStr;
+use ::{Offset, Weekday, Datelike, Timelike};
+use div::{div_mod_floor, mod_floor};
+use duration::{self, Duration};
+use offset::{FixedOffset, UTC};
+use naive::date::NaiveDate;
+use naive::time::NaiveTime;
+use naive::datetime::NaiveDateTime;
+use period::Period;
+use datetime::DateTime;
+use rrule::{self, RRule};
+use format::{SHORT_MONTHS, LONG_MONTHS, SHORT_WEEKDAYS, LONG_WEEKDAYS};
+use format::{Item, StrftimeItems};
+use locale::{self, Locale};
+
+//
+//  Parse errors
+//
+//  Every parser returns `ParseResult`, so that the caller can tell which part
+//  of the input was wrong. The position is the byte offset in the input.
+//
+
+/// The category of `ParseError`.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub enum ParseErrorKind {
+    /// A field does not have the expected form,
+    /// e.g. an unknown month name or a letter in place of digits.
+    Invalid,
+    /// A field is out of its permitted range, e.g. the 25th hour or the 13th month.
+    OutOfRange,
+    /// The fields are individually valid but cannot be combined,
+    /// e.g. February 30 or a day of week disagreeing with the date.
+    Impossible,
+    /// The fields are not enough to determine the result, e.g. a missing offset.
+    NotEnough,
+    /// There is trailing input after the date and time.
+    TooLong,
+    /// The input ends prematurely.
+    TooShort,
+    /// The format string is invalid, e.g. an unknown specifier or a stray `%`.
+    BadFormat,
+}
+
+/// An error from parsing the date and time string, with the position of the failure.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+pub struct ParseError {
+    /// The category of the error.
+    pub kind: ParseErrorKind,
+    /// The byte offset in the input where the error was found.
+    pub pos: usize,
+}
+
+/// The result of parsing functions.
+pub type ParseResult<T> = Result<T, ParseError>;
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.description(), self.pos)
+    }
+}
+
+impl Error for ParseError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ParseErrorKind::Invalid => "invalid input",
+            ParseErrorKind::OutOfRange => "input is out of range",
+            ParseErrorKind::Impossible => "no possible date and time matching input",
+            ParseErrorKind::NotEnough => "input is not enough for unique date and time",
+            ParseErrorKind::TooLong => "trailing input",
+            ParseErrorKind::TooShort => "premature end of input",
+            ParseErrorKind::BadFormat => "bad or unsupported format string",
+        }
+    }
+}
+
+//
+//  Scanner
+//
+//  A cursor over the input shared by all parsers. It keeps the current position
+//  for error reports, and the start of the last scanned field (`last`) so that
+//  a field found to be out of range or inconsistent can be reported at its start.
+//
+
+/// The cursor over the input string.
+struct Scanner<'a> {
+    s: &'a str,
+    pos: usize,
+    last: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(s: &'a str) -> Scanner<'a> {
+        Scanner { s: s, pos: 0, last: 0 }
+    }
+
+    /// Returns the remaining input.
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn is_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    /// Makes an error at the current position.
+    fn error<T>(&self, kind: ParseErrorKind) -> ParseResult<T> {
+        Err(ParseError { kind: kind, pos: self.pos })
+    }
+
+    /// Makes an error at the start of the last scanned field.
+    fn error_last<T>(&self, kind: ParseErrorKind) -> ParseResult<T> {
+        Err(ParseError { kind: kind, pos: self.last })
+    }
+
+    /// Makes an error for the unexpected input at the current position:
+    /// `TooShort` at the end of input, `Invalid` otherwise.
+    fn unexpected<T>(&self) -> ParseResult<T> {
+        self.error(if self.is_end() { ParseErrorKind::TooShort } else { ParseErrorKind::Invalid })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_digit(&self) -> bool {
+        self.peek().map_or(false, |c| c.is_digit(10))
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        match c { Some(c) => self.pos += c.len_utf8(), None => {} }
+        c
+    }
+
+    /// Consumes given character if it is the next one.
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) { self.pos += c.len_utf8(); true } else { false }
+    }
+
+    /// Consumes given ASCII character if it is the next one, ignoring the case.
+    fn eat_ci(&mut self, c: char) -> bool {
+        match self.peek() {
+            Some(c2) if c2.to_ascii_lowercase() == c.to_ascii_lowercase() => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consumes given character or fails.
+    fn expect(&mut self, c: char) -> ParseResult<()> {
+        if self.eat(c) { Ok(()) } else { self.unexpected() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_left().len();
+    }
+
+    /// Skips one or more whitespace.
+    fn expect_whitespace(&mut self) -> ParseResult<()> {
+        if !self.peek().map_or(false, |c| c.is_whitespace()) { return self.unexpected(); }
+        self.skip_whitespace();
+        Ok(())
+    }
+
+    /// Fails unless the input has been fully consumed.
+    fn expect_end(&self) -> ParseResult<()> {
+        if self.is_end() { Ok(()) } else { self.error(ParseErrorKind::TooLong) }
+    }
+
+    /// Reads a decimal number of `min` to `max` digits.
+    fn digits(&mut self, min: usize, max: usize) -> ParseResult<i64> {
+        self.last = self.pos;
+        let bytes = self.rest().as_bytes();
+        let mut n = 0i64;
+        let mut i = 0;
+        while i < max && i < bytes.len() && b'0' <= bytes[i] && bytes[i] <= b'9' {
+            n = n * 10 + (bytes[i] - b'0') as i64;
+            i += 1;
+        }
+        self.pos += i;
+        if i < min { return self.unexpected(); }
+        Ok(n)
+    }
+
+    /// Reads a decimal number of `min` to `max` digits in the range `lo` to `hi`.
+    fn digits_in(&mut self, min: usize, max: usize, lo: i64, hi: i64) -> ParseResult<i64> {
+        let v = try!(self.digits(min, max));
+        if v < lo || v > hi { return self.error_last(ParseErrorKind::OutOfRange); }
+        Ok(v)
+    }
+
+    /// Reads a decimal number as `digits_in`, possibly preceded by whitespace.
+    fn number(&mut self, min: usize, max: usize, lo: i64, hi: i64) -> ParseResult<i64> {
+        self.skip_whitespace();
+        self.digits_in(min, max, lo, hi)
+    }
+
+    /// Reads a year: up to 4 digits, or more digits with an explicit sign.
+    fn year(&mut self) -> ParseResult<i32> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let (sign, max) = if self.eat('+') { (1, 9) } else if self.eat('-') { (-1, 9) }
+                          else { (1, 4) };
+        let v = try!(self.digits(1, max));
+        self.last = start;
+        Ok(sign * v as i32)
+    }
+
+    /// Reads digits after the decimal point as nanoseconds.
+    /// The digits beyond the nanosecond precision are ignored.
+    fn nanosecond(&mut self) -> ParseResult<u32> {
+        let start = self.pos;
+        let v = try!(self.digits(1, 9));
+        let ndigits = self.pos - start;
+        while self.peek_digit() { self.pos += 1; }
+        self.last = start;
+        Ok(v as u32 * Int::pow(10, 9 - ndigits))
+    }
+
+    /// Reads the longest of `long_names` or `short_names`, ignoring the case,
+    /// and returns the index to the name. The abbreviations (e.g. `janv.`) can omit
+    /// the trailing dot; the surrounding whitespace and empty names are ignored.
+    fn name(&mut self, long_names: &[&str], short_names: &[&str]) -> ParseResult<usize> {
+        self.skip_whitespace();
+        self.last = self.pos;
+        let rest = self.rest();
+        let mut longest = None; // the length and index of the longest match so far
+        for names in [long_names, short_names].iter() {
+            for (i, name) in names.iter().enumerate() {
+                let name = name.trim();
+                for &name in [name, name.trim_right_matches('.')].iter() {
+                    if name.is_empty() { continue; }
+                    match (prefix_len_ci(rest, name), longest) {
+                        (Some(len), Some((max, _))) if len <= max => {}
+                        (Some(len), _) => longest = Some((len, i)),
+                        (None, _) => {}
+                    }
+                }
+            }
+        }
+        match longest {
+            Some((len, i)) => { self.pos += len; Ok(i) }
+            None => self.unexpected(),
+        }
+    }
+
+    /// Reads the offset `+hhmm` or `+hh:mm` (or `-`) in seconds.
+    /// The colon is required when `colon` is `Some(true)`, rejected when `Some(false)`.
+    fn offset(&mut self, colon: Option<bool>) -> ParseResult<i32> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let sign = if self.eat('+') { 1 } else if self.eat('-') { -1 }
+                   else { return self.unexpected(); };
+        let hh = try!(self.digits_in(2, 2, 0, 23));
+        match (colon, self.eat(':')) {
+            (Some(true), false) | (Some(false), true) => return self.unexpected(),
+            _ => {}
+        }
+        let mm = try!(self.digits_in(2, 2, 0, 59));
+        self.last = start;
+        Ok(sign * (hh * 3600 + mm * 60) as i32)
+    }
+
+    /// Reads the offset `+hh:mm:ss` (or `-`) in seconds.
+    fn offset_hms(&mut self) -> ParseResult<i32> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let sign = if self.eat('+') { 1 } else if self.eat('-') { -1 }
+                   else { return self.unexpected(); };
+        let hh = try!(self.digits_in(2, 2, 0, 23));
+        try!(self.expect(':'));
+        let mm = try!(self.digits_in(2, 2, 0, 59));
+        try!(self.expect(':'));
+        let ss = try!(self.digits_in(2, 2, 0, 59));
+        self.last = start;
+        Ok(sign * (hh * 3600 + mm * 60 + ss) as i32)
+    }
+}
+
+/// Returns the length of the prefix of `s` matching given `prefix`, ignoring the case.
+fn prefix_len_ci(s: &str, prefix: &str) -> Option<usize> {
+    let mut chars = s.char_indices();
+    for p in prefix.chars() {
+        match chars.next() {
+            Some((_, c)) if c.to_lowercase() == p.to_lowercase() => {}
+            _ => return None,
+        }
+    }
+    Some(chars.next().map_or(s.len(), |(i, _)| i))
+}
+
 //
 //  RFC2822 time/date stamp parsing
 //
@@ -78,96 +373,190 @@ use ::{Offset};
 //                         %d107-122               ; upper and lower case
 //
 //
-// Per RFC2882, all the obsolete one-letter military time zones are interpreted as 
-//  +0000.
+// Comments and folding whitespace, from RFC2822 section 3.2.3:
 //
-// The only feature not supported is that an offset of "-0000" should return a
-// naive date/time, not a time zone aware one.  This returns a time zone aware
-// date/time object in all cases.
-// 
+// FWS             =       ([*WSP CRLF] 1*WSP) /   ; Folding white space
+//                         obs-FWS
 //
-/// Time zone offset in minutes, from string.
-/// Allowed input per RFC2822 above - numeric offset or named time zone
-fn offsetmins(s: &str) -> Option<i32> {
-    let offsetre = regex!(r"^([+-])(\d\d)(\d\d)$"); // +0800 as 8 hour offset
-    let offsetmatches = offsetre.captures(s);           // match time zone
-    match offsetmatches {
-        Some(caps) => {                                 // It looks like a numeric offset
-            let sign = caps.at(1).unwrap();             // + or -
-            let hh = caps.at(2).unwrap().parse::<i32>().unwrap(); // hours
-            let mm = caps.at(3).unwrap().parse::<i32>().unwrap(); // minutes
-            let signval = match sign {
-                "+" => 1,
-                "-" => -1,
-                _ => return None                        // unreachable
-                };
-            if hh < -12 || hh > 12 || mm < 0 || mm > 59 { return None }   // check offsets
-            return Some(signval*(hh*60 + mm))           // return offset in minute
+// ctext           =       NO-WS-CTL /     ; Non white space controls
+//                         %d33-39 /       ; The rest of the US-ASCII
+//                         %d42-91 /       ;  characters not including "(",
+//                         %d93-126        ;  ")", or "\"
+//
+// ccontent        =       ctext / quoted-pair / comment
+//
+// comment         =       "(" *([FWS] ccontent) [FWS] ")"
+//
+// CFWS            =       *([FWS] comment) (([FWS] comment) / FWS)
+//
+// Per RFC2822 section 4.3, a two-digit obs-year is 19xx from 50 to 99
+// and 20xx from 00 to 49, and a three-digit obs-year is added to 1900.
+//
+// Per RFC2822 section 3.3, an offset of "-0000" means that the local time
+// is given without the information about the offset, and the obsolete
+// one-letter military time zones should be considered equivalent to "-0000".
+// rfc2822_to_naive_datetime reports such times without an offset;
+// rfc2822_to_datetime takes them as UTC, the same as "+0000".
+//
+// The day of week, if present, must be the day implied by the date.
+//
+/// Skips the comments and folding whitespace (CFWS), and returns true if there were any.
+/// Comments can be nested and can contain quoted pairs.
+fn rfc2822_cfws(sc: &mut Scanner) -> ParseResult<bool> {
+    let start = sc.pos;
+    loop {
+        sc.skip_whitespace();                               // FWS, with or without CRLF
+        if !sc.eat('(') { break; }                          // no comment
+        let mut depth = 1us;                                // comments nest
+        while depth > 0 {
+            match sc.bump() {
+                Some('(') => depth += 1,
+                Some(')') => depth -= 1,
+                Some('\\') => { sc.bump(); }               // quoted pair
+                Some(_) => {}
+                None => return sc.error(ParseErrorKind::TooShort),  // unterminated comment
             }
-        None => {                                       // not numeric, try the named time zones
-             return match s {
-                "GMT"|"UT"|"Z"|"z" => Some(0),          // prime meridian
-                "EDT" => Some(-4*60),                   // obsolete forms
-                "EST"|"CDT" => Some(-5*60),             // rather US-centric in this old RFC.
+        }
+    }
+    Ok(sc.pos > start)
+}
+
+/// Skips the comments and folding whitespace, which are required.
+fn rfc2822_expect_cfws(sc: &mut Scanner) -> ParseResult<()> {
+    if try!(rfc2822_cfws(sc)) { Ok(()) } else { sc.unexpected() }
+}
+
+/// Reads the RFC 2822 zone, a numeric offset or a named time zone, in seconds.
+/// Returns `None` for the zones without the offset information (`-0000` and military zones).
+fn rfc2822_zone(sc: &mut Scanner) -> ParseResult<Option<i32>> {
+    match sc.peek() {
+        Some('+') | Some('-') => {                          // It looks like a numeric offset
+            let negative = sc.peek() == Some('-');
+            let offset = try!(sc.offset(Some(false)));     // +0800 as 8 hour offset
+            if negative && offset == 0 { return Ok(None); } // "-0000", no information
+            Ok(Some(offset))
+        }
+        _ => {                                              // not numeric, try the named time zones
+            let rest = sc.rest();
+            let len = rest.find(|&: c: char| !c.is_alphabetic()).unwrap_or(rest.len());
+            let mins = match &rest[..len] {
+                "GMT"|"UT" => Some(0),                      // prime meridian
+                "EDT" => Some(-4*60),                       // obsolete forms
+                "EST"|"CDT" => Some(-5*60),                 // rather US-centric in this old RFC.
                 "CST"|"MDT" => Some(-6*60),
                 "MST"|"PDT" => Some(-7*60),
                 "PST" => Some(-8*60),
-                _ => match s.len() { 1 => Some(0), _  => None } // obsolete single-letter miltary forms are treated as 0 per RFC2822
-            }
-        }       
-    };
+                _ if len == 1 => None,                      // obsolete military zones, same as -0000
+                _ => return sc.unexpected(),
+            };
+            sc.pos += len;
+            Ok(mins.map(|mins| mins * 60))
+        }
+    }
 }
 
-/// Makes a new `DateTime` with offset given a valid RFC2822 string.
+/// Makes the local date and time, and the offset unless it is unknown (`-0000`),
+/// given a valid RFC2822 string, including the obsolete forms.
 /// Example: "Tue, 20 Jan 2015 17:35:20 -0800"
-pub fn rfc2822_to_datetime(s: &str) -> Option<::DateTime<::FixedOffset>> {
-
-    //  Match the date format.  Case-insensitive, compile-time regex.
-    let datere = regex!(r"^(?i)(?:Mon,|Tue,|Wed,|Thu,|Fri,|Sat,|Sun,)??\s*(\d+)\s+(Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)\s+(\d\d\d\d)\s+(\d+):(\d+):(\d+)\s*([+-]\d\d\d\d|[A-Z]+)$");
-    let matches = datere.captures(s.trim());   // Pattern match the date
-    let captures = match matches {
-        Some(caps) => caps,             // succeed
-        None => return None             // fail
+pub fn rfc2822_to_naive_datetime(s: &str)
+        -> ParseResult<(NaiveDateTime, Option<FixedOffset>)> {
+    rfc2822_naive_datetime(s, None)
+}
+
+/// Parses RFC2822 with the English names, or the full and abbreviated names from the locale.
+fn rfc2822_naive_datetime(s: &str, locale: Option<&Locale>)
+        -> ParseResult<(NaiveDateTime, Option<FixedOffset>)> {
+    let (weekdays, months): ((&[&str], &[&str]), (&[&str], &[&str])) = match locale {
+        Some(l) => ((l.long_weekdays, l.short_weekdays), (l.long_months, l.short_months)),
+        None => ((&SHORT_WEEKDAYS, &[]), (&SHORT_MONTHS, &[])),
+    };
+    let mut sc = Scanner::new(s);
+    try!(rfc2822_cfws(&mut sc));
+    let weekday = if sc.peek().map_or(false, |c| c.is_alphabetic()) {  // optional day of week
+        let weekday_pos = sc.pos;
+        let weekday = try!(sc.name(weekdays.0, weekdays.1));
+        try!(rfc2822_cfws(&mut sc));
+        try!(sc.expect(','));
+        try!(rfc2822_cfws(&mut sc));
+        Some((weekday_pos, weekday))
+    } else {
+        None
     };
-    //  Unwrapping numeric fields is safe because we've matched the regular expression.
-    let dd = captures.at(1).unwrap().parse::<u32>().unwrap();   // day of month
+    let date_pos = sc.pos;
+    let dd = try!(sc.digits_in(1, 2, 1, 31));              // day of month
+    try!(rfc2822_expect_cfws(&mut sc));
     //  Month names are case-sensitive in RFC 2822, but we allow the obvious other forms.
-    let mo = match captures.at(2).unwrap() {                    // month decode
-        "Jan"|"JAN"|"jan" => 1,
-        "Feb"|"FEB"|"feb" => 2,
-        "Mar"|"MAR"|"mar" => 3,
-        "Apr"|"APR"|"apr" => 4,
-        "May"|"MAY"|"may" => 5,
-        "Jun"|"JUN"|"jun" => 6,
-        "Jul"|"JUL"|"jul" => 7,
-        "Aug"|"AUG"|"aug" => 8,
-        "Sep"|"SEP"|"sep" => 9,
-        "Oct"|"OCT"|"oct" => 10,
-        "Nov"|"NOV"|"nov" => 11,
-        "Dec"|"DEC"|"dec" => 12,
-        _ => return None
-        };    
-    let yyyy = captures.at(3).unwrap().parse::<i32>().unwrap(); // chrono wants a signed year
-    let hh = captures.at(4).unwrap().parse::<u32>().unwrap();
-    let mm = captures.at(5).unwrap().parse::<u32>().unwrap();   // minute
-    let ss = captures.at(6).unwrap().parse::<u32>().unwrap();
-    let offsetstr = captures.at(7).unwrap();                    // can be +0800 or a time zone name
-    let offsetmm = match offsetmins(offsetstr) {
-        Some(v) => v,
-        None => return None
-    };    
-    let tz = ::FixedOffset::east(offsetmm*60);                  // decode time zone offset
-    //  Pack numeric values into DateTime object, returning None if fail.
-    let date = tz.ymd_opt(yyyy, mo, dd);                        // date or none
-    match date {                                                // check for invalid date
-        ::LocalResult::Single(d) => d.and_hms_opt(hh, mm, ss),  // encode into DateTime
-        _ => return None                                        // date conversion failed
-    }     
+    let mo = try!(sc.name(months.0, months.1)) + 1;         // month decode
+    try!(rfc2822_expect_cfws(&mut sc));
+    let year_pos = sc.pos;
+    let yyyy = try!(sc.digits(2, 9));                       // 4 or more digits, or obs-year
+    let yyyy = match sc.pos - year_pos {
+        2 if yyyy < 50 => yyyy + 2000,                      // obs-year "15" is 2015
+        2 | 3 => yyyy + 1900,                               // obs-year "99" is 1999, "115" is 2015
+        _ => yyyy,
+    };
+    try!(rfc2822_expect_cfws(&mut sc));
+    let hh = try!(sc.digits_in(1, 2, 0, 23));
+    try!(rfc2822_cfws(&mut sc));
+    try!(sc.expect(':'));
+    try!(rfc2822_cfws(&mut sc));
+    let mm = try!(sc.digits_in(1, 2, 0, 59));               // minute
+    try!(rfc2822_cfws(&mut sc));
+    let ss = if sc.eat(':') {                               // seconds are optional
+        try!(rfc2822_cfws(&mut sc));
+        let ss = try!(sc.digits_in(1, 2, 0, 60));           // 60 is the leap second
+        try!(rfc2822_cfws(&mut sc));
+        ss
+    } else {
+        0
+    };
+    let offset = try!(rfc2822_zone(&mut sc));               // can be +0800 or a time zone name
+    try!(rfc2822_cfws(&mut sc));
+    try!(sc.expect_end());
+    //  Pack numeric values into NaiveDateTime object, failing on the invalid date.
+    let date = match NaiveDate::from_ymd_opt(yyyy as i32, mo as u32, dd as u32) {
+        Some(date) => date,
+        None => return Err(ParseError { kind: ParseErrorKind::Impossible, pos: date_pos }),
+    };
+    match weekday {                                         // day of week must match
+        Some((pos, weekday)) if date.weekday().num_days_from_monday() != weekday as u32 =>
+            return Err(ParseError { kind: ParseErrorKind::Impossible, pos: pos }),
+        _ => {}
+    }
+    let (ss, ns) = if ss == 60 { (59, 1_000_000_000) } else { (ss, 0) };
+    let time = NaiveTime::from_hms_nano(hh as u32, mm as u32, ss as u32, ns);
+    Ok((date.and_time(time), offset.map(|off| FixedOffset::east(off))))
+}
+
+/// Makes a new `DateTime` with offset given a valid RFC2822 string.
+/// The unknown offset (`-0000` and military zones) is treated as UTC.
+/// Example: "Tue, 20 Jan 2015 17:35:20 -0800"
+pub fn rfc2822_to_datetime(s: &str) -> ParseResult<DateTime<FixedOffset>> {
+    rfc2822_to_datetime_with(s, None)
+}
+
+/// Makes a new `DateTime` with offset given an RFC2822-like string, where the month and
+/// weekday names are in given locale (full or abbreviated, ignoring the case).
+/// Examples: "Di, 20 Jan 2015 17:35:20 +0100", "20 janv. 2015 17:35:20 +0100"
+pub fn rfc2822_to_datetime_localized(s: &str,
+                                     locale: &Locale) -> ParseResult<DateTime<FixedOffset>> {
+    rfc2822_to_datetime_with(s, Some(locale))
+}
+
+/// Makes a new `DateTime` from RFC2822, with the names in the locale if given.
+fn rfc2822_to_datetime_with(s: &str, locale: Option<&Locale>)
+        -> ParseResult<DateTime<FixedOffset>> {
+    let (datetime, offset) = try!(rfc2822_naive_datetime(s, locale));
+    let offset = offset.unwrap_or(FixedOffset::east(0));   // the time is still in UTC
+    match offset.from_local_datetime(&datetime).single() {
+        Some(dt) => Ok(dt),
+        None => Err(ParseError { kind: ParseErrorKind::OutOfRange, pos: s.len() }),
+    }
 }
 
 /// Formats a DateTime as an RF2822 string.
 /// This is primarily for debugging.
-pub fn fmt_rfc2822_datetime(dt: ::DateTime<::FixedOffset>) -> String {
+pub fn fmt_rfc2822_datetime(dt: DateTime<FixedOffset>) -> String {
     dt.format("%a, %e %b %Y %H:%M:%S %z").to_string()           // inverse of parsing
 }
 
@@ -208,59 +597,1227 @@ pub fn fmt_rfc2822_datetime(dt: ::DateTime<::FixedOffset>) -> String {
 //      readability, to specify a full-date and full-time separated by
 //      (say) a space character.
 //
+//   From section 4.3, "Unknown Local Offset Convention":
+//
+//      If the time in UTC is known, but the offset to local time is
+//      unknown, this can be represented with an offset of "-00:00".
+//
+
+/// Reads the RFC3339 offset in seconds, or `None` for the unknown local offset `-00:00`.
+/// The colon can be omitted for the compatibility.
+fn rfc3339_offset(sc: &mut Scanner) -> ParseResult<Option<i32>> {
+    if sc.eat_ci('Z') { return Ok(Some(0)); }
+    let sign = if sc.eat('+') { 1 } else if sc.eat('-') { -1 } else { return sc.unexpected(); };
+    let hh = try!(sc.digits_in(2, 2, 0, 23));
+    sc.eat(':');
+    let mm = try!(sc.digits_in(2, 2, 0, 59));
+    if sign < 0 && hh == 0 && mm == 0 { return Ok(None); }     // "-00:00", see section 4.3
+    Ok(Some(sign * (hh * 3600 + mm * 60) as i32))
+}
+
+/// Parse a string with a RFC3339 date, time, and offset into the local date and time,
+/// and the offset unless it is unknown (`-00:00`).
+pub fn rfc3339_to_naive_datetime(s: &str)
+        -> ParseResult<(NaiveDateTime, Option<FixedOffset>)> {
+    let mut sc = Scanner::new(s);
+    sc.skip_whitespace();
+    let date_pos = sc.pos;
+    let yyyy = try!(sc.digits(4, 4));                       // chrono wants a signed year
+    try!(sc.expect('-'));
+    let mo = try!(sc.digits_in(2, 2, 1, 12));               // month of year
+    try!(sc.expect('-'));
+    let dd = try!(sc.digits_in(2, 2, 1, 31));               // day of month
+    if !sc.eat_ci('T') && !sc.eat(' ') { return sc.unexpected(); }  // space also allowed
+    let hh = try!(sc.digits_in(2, 2, 0, 23));               // hour
+    try!(sc.expect(':'));
+    let mm = try!(sc.digits_in(2, 2, 0, 59));               // minute
+    try!(sc.expect(':'));
+    let ss = try!(sc.digits_in(2, 2, 0, 60));               // second, 60 is the leap second
+    let ns = if sc.eat('.') {                               // fractional seconds present?
+        try!(sc.nanosecond())                               // parse as nanoseconds
+    } else {
+        0                                                   // no fraction
+    };
+    let offset = try!(rfc3339_offset(&mut sc));             // "Z" or numeric only
+    sc.skip_whitespace();
+    try!(sc.expect_end());
+    //  Pack numeric values into NaiveDateTime object, failing on the invalid date.
+    let date = match NaiveDate::from_ymd_opt(yyyy as i32, mo as u32, dd as u32) {
+        Some(date) => date,
+        None => return Err(ParseError { kind: ParseErrorKind::Impossible, pos: date_pos }),
+    };
+    let (ss, ns) = if ss == 60 { (59, ns + 1_000_000_000) } else { (ss, ns) };
+    let time = NaiveTime::from_hms_nano(hh as u32, mm as u32, ss as u32, ns);
+    Ok((date.and_time(time), offset.map(|off| FixedOffset::east(off))))
+}
 
 /// Parse a string with a RFC3339 date, time, and offset into a DateTime.
 /// This is the subset of ISO 8601 date and time strings most used on the Web.
-pub fn rfc3339_to_datetime(s: &str) -> Option<::DateTime<::FixedOffset>> {
-    let datere = regex!(r"^(?i)(\d\d\d\d)-(\d\d)-(\d\d)T(\d\d):(\d\d):(\d\d)(\.\d+)??([+-]\d\d\d\d|[A-Z]+)$"); // format regex
-        let matches = datere.captures(s.trim());   // Pattern match the date
-    let captures = match matches {
-        Some(caps) => caps,             // succeed
-        None => return None             // fail
+/// The unknown local offset (`-00:00`) is treated as UTC.
+pub fn rfc3339_to_datetime(s: &str) -> ParseResult<DateTime<FixedOffset>> {
+    let (datetime, offset) = try!(rfc3339_to_naive_datetime(s));
+    let offset = offset.unwrap_or(FixedOffset::east(0));   // the time is still in UTC
+    match offset.from_local_datetime(&datetime).single() {
+        Some(dt) => Ok(dt),
+        None => Err(ParseError { kind: ParseErrorKind::OutOfRange, pos: s.len() }),
+    }
+}
+
+/// Formats a DateTime as an RFC 3339/ISO8601 date, with as many digits of fractional seconds
+/// as needed (none, 3, 6 or 9). This is the inverse operation of rfc3339 parsing.
+pub fn fmt_rfc3339_datetime(dt: DateTime<FixedOffset>) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S%.f%:z").to_string()          // inverse of parsing
+}
+
+//
+//  HTTP date parsing
+//
+//  Example: "Sun, 06 Nov 1994 08:49:37 GMT".
+//  Common use case: HTTP headers such as Date, Last-Modified and Expires.
+//
+//   From RFC7231, "HTTP/1.1: Semantics and Content", section 7.1.1.1:
+//
+//   HTTP-date    = IMF-fixdate / obs-date
+//
+//   IMF-fixdate  = day-name "," SP date1 SP time-of-day SP GMT
+//   ; fixed length/zone/capitalization subset of the format
+//   ; see Section 3.3 of [RFC5322]
+//
+//   date1        = day SP month SP year
+//                ; e.g., 02 Jun 1982
+//
+//   time-of-day  = hour ":" minute ":" second
+//                ; 00:00:00 - 23:59:60 (leap second)
+//
+//   obs-date     = rfc850-date / asctime-date
+//
+//   rfc850-date  = day-name-l "," SP date2 SP time-of-day SP GMT
+//   date2        = day "-" month "-" 2DIGIT
+//                ; e.g., 02-Jun-82
+//
+//   asctime-date = day-name SP date3 SP time-of-day SP year
+//   date3        = month SP ( 2DIGIT / ( SP DIGIT ))
+//                ; e.g., Jun  2
+//
+//   Recipients of a timestamp value in rfc850-date format, which uses a
+//   two-digit year, MUST interpret a timestamp that appears to be more
+//   than 50 years in the future as representing the most recent year in
+//   the past that had the same last two digits.
+//
+//   A sender MUST NOT generate additional whitespace in an HTTP-date
+//   beyond that specifically included as SP in the grammar.
+//
+//  All three formats are in UTC. The day of week must match the date.
+//
+
+/// Reads the HTTP time of day, `hh:mm:ss` with two digits each.
+fn http_time(sc: &mut Scanner) -> ParseResult<NaiveTime> {
+    let hh = try!(sc.digits_in(2, 2, 0, 23));               // hour
+    try!(sc.expect(':'));
+    let mm = try!(sc.digits_in(2, 2, 0, 59));               // minute
+    try!(sc.expect(':'));
+    let ss = try!(sc.digits_in(2, 2, 0, 60));               // second, 60 is the leap second
+    let (ss, ns) = if ss == 60 { (59, 1_000_000_000) } else { (ss, 0) };
+    Ok(NaiveTime::from_hms_nano(hh as u32, mm as u32, ss as u32, ns))
+}
+
+/// Reads a name as `Scanner::name`, but without the preceding whitespace.
+fn http_name(sc: &mut Scanner, long_names: &[&str],
+             short_names: &[&str]) -> ParseResult<usize> {
+    if sc.peek().map_or(false, |c| c.is_whitespace()) { return sc.unexpected(); }
+    sc.name(long_names, short_names)
+}
+
+/// Reads " GMT", the only zone allowed in HTTP dates.
+fn http_gmt(sc: &mut Scanner) -> ParseResult<()> {
+    try!(sc.expect(' '));
+    if sc.rest().starts_with("GMT") { sc.pos += 3; Ok(()) } else { sc.unexpected() }
+}
+
+/// Parses an HTTP date, resolving the two-digit year of rfc850-date against `this_year`.
+fn http_date(s: &str, this_year: i32) -> ParseResult<DateTime<UTC>> {
+    let mut sc = Scanner::new(s);
+    let weekday_pos = sc.pos;
+    let weekday = try!(http_name(&mut sc, &LONG_WEEKDAYS, &SHORT_WEEKDAYS));
+    let long = sc.pos - sc.last > 3;                        // Sunday or Sun?
+    let (date_pos, yyyy, mo, dd, time);
+    if long {                                               // rfc850-date
+        try!(sc.expect(','));
+        try!(sc.expect(' '));
+        date_pos = sc.pos;
+        dd = try!(sc.digits_in(2, 2, 1, 31));
+        try!(sc.expect('-'));
+        mo = try!(http_name(&mut sc, &SHORT_MONTHS, &[])) + 1;
+        try!(sc.expect('-'));
+        let yy = try!(sc.digits(2, 2)) as i32;
+        let year = this_year - mod_floor(this_year, 100) + yy;
+        yyyy = if year > this_year + 50 { year - 100 } else { year };   // not too far in the future
+        try!(sc.expect(' '));
+        time = try!(http_time(&mut sc));
+        try!(http_gmt(&mut sc));
+    } else if sc.eat(',') {                                 // IMF-fixdate
+        try!(sc.expect(' '));
+        date_pos = sc.pos;
+        dd = try!(sc.digits_in(2, 2, 1, 31));
+        try!(sc.expect(' '));
+        mo = try!(http_name(&mut sc, &SHORT_MONTHS, &[])) + 1;
+        try!(sc.expect(' '));
+        yyyy = try!(sc.digits(4, 4)) as i32;
+        try!(sc.expect(' '));
+        time = try!(http_time(&mut sc));
+        try!(http_gmt(&mut sc));
+    } else {                                                // asctime-date
+        try!(sc.expect(' '));
+        date_pos = sc.pos;
+        mo = try!(http_name(&mut sc, &SHORT_MONTHS, &[])) + 1;
+        try!(sc.expect(' '));
+        dd = if sc.eat(' ') {                               // " 6" or "06"
+            try!(sc.digits_in(1, 1, 1, 9))
+        } else {
+            try!(sc.digits_in(2, 2, 1, 31))
+        };
+        try!(sc.expect(' '));
+        time = try!(http_time(&mut sc));
+        try!(sc.expect(' '));
+        yyyy = try!(sc.digits(4, 4)) as i32;
+    }
+    try!(sc.expect_end());
+    //  Pack values into DateTime object, failing on the invalid date.
+    let date = match NaiveDate::from_ymd_opt(yyyy, mo as u32, dd as u32) {
+        Some(date) => date,
+        None => return Err(ParseError { kind: ParseErrorKind::Impossible, pos: date_pos }),
     };
-    //  Unwrapping numeric fields is safe because we've matched the regular expression.
-    let yyyy = captures.at(1).unwrap().parse::<i32>().unwrap(); // chrono wants a signed year
-    let mo = captures.at(2).unwrap().parse::<u32>().unwrap();   // month of year
-    let dd = captures.at(3).unwrap().parse::<u32>().unwrap();   // day of month
-    let hh = captures.at(4).unwrap().parse::<u32>().unwrap();   // hour
-    let mm = captures.at(5).unwrap().parse::<u32>().unwrap();   // minute
-    let ss = captures.at(6).unwrap().parse::<u32>().unwrap();   // second
-    let ns = match captures.at(7) {                             // fractional seconds present?
-        Some(fractstr) =>  parsensfract(fractstr),              // parse as nanoseconds
-        None => 0                                               // no fraction
+    if date.weekday().num_days_from_monday() != weekday as u32 {    // day of week must match
+        return Err(ParseError { kind: ParseErrorKind::Impossible, pos: weekday_pos });
+    }
+    Ok(DateTime::from_utc(date.and_time(time), UTC))
+}
+
+/// Parse a string with an HTTP date into a DateTime in UTC.
+/// All three formats allowed by HTTP/1.1 are accepted:
+/// "Sun, 06 Nov 1994 08:49:37 GMT" (IMF-fixdate), "Sunday, 06-Nov-94 08:49:37 GMT"
+/// (obsolete RFC 850 format) and "Sun Nov  6 08:49:37 1994" (ANSI C's `asctime()` format).
+pub fn http_date_to_datetime(s: &str) -> ParseResult<DateTime<UTC>> {
+    http_date(s, UTC::today().year())
+}
+
+/// Formats a DateTime as an HTTP date (IMF-fixdate), the only format HTTP senders may use.
+/// This is the inverse operation of HTTP date parsing.
+pub fn fmt_http_date(dt: DateTime<UTC>) -> String {
+    dt.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+//
+//  ISO 8601 date and time parsing
+//
+//  RFC3339 above is a profile of ISO 8601; this accepts the rest of it.
+//  Examples: "2015-01-20T17:35:20-08:00", "2015-020", "2015-W04-2",
+//  "20150120T173520Z", "2015-01-20T17:35", "+012015-01-20".
+//
+//   Dates, extended / basic format:
+//
+//   calendar        = YYYY "-" MM "-" DD  /  YYYYMMDD
+//   ordinal         = YYYY "-" DDD        /  YYYYDDD
+//   week            = YYYY "-W" ww "-" D  /  YYYY "W" ww D
+//   expanded year   = ("+" / "-") 4*DIGIT ; sign required for more than 4 digits
+//
+//   Times, extended / basic format, after "T":
+//
+//   time            = hh [":" mm [":" ss]] [fraction]  /  hh [mm [ss]] [fraction]
+//   fraction        = ("." / ",") 1*DIGIT ; applies to the last component given
+//   offset          = "Z" / ("+" / "-") hh [[":"] mm]
+//
+//   "24:00" (with all the lower components zero) is the end of the day,
+//   i.e. the midnight of the next day. A second of "60" is the leap second.
+//
+//   In the basic format, an expanded year takes all but the last four digits
+//   of the calendar date; basic ordinal dates with expanded years are ambiguous
+//   and not accepted.
+//
+
+/// Makes the `NaiveDate` for the ISO 8601 date fields, starting at `date_pos`.
+/// A date outside the supported range is `OutOfRange`, an invalid one `Impossible`.
+fn iso8601_make_date(date_pos: usize, year: i32,
+                     date: Option<NaiveDate>) -> ParseResult<NaiveDate> {
+    match date {
+        Some(date) => Ok(date),
+        None => {
+            let kind = if NaiveDate::from_yo_opt(year, 1).is_none() {
+                ParseErrorKind::OutOfRange
+            } else {
+                ParseErrorKind::Impossible
+            };
+            Err(ParseError { kind: kind, pos: date_pos })
+        }
+    }
+}
+
+/// Reads an ISO 8601 calendar, ordinal or week date.
+fn iso8601_date(sc: &mut Scanner) -> ParseResult<NaiveDate> {
+    let date_pos = sc.pos;
+    let sign = if sc.eat('+') { Some(1) } else if sc.eat('-') { Some(-1) } else { None };
+    let ndigits = sc.rest().bytes().take_while(|&b| b'0' <= b && b <= b'9').count();
+    let next = sc.rest().as_bytes().get(ndigits).map(|&b| b as char);
+    let extended = next == Some('-');
+    let yeardigits = match (sign, extended || next == Some('W')) {
+        (None, true) if ndigits == 4 => 4,
+        (Some(_), true) if ndigits >= 4 => ndigits,
+        (None, false) if ndigits == 7 || ndigits == 8 => 4,
+        (Some(_), false) if ndigits >= 8 => ndigits - 4,
+        _ => { sc.pos += ndigits; return sc.unexpected(); }
+    };
+    if yeardigits > 9 { return sc.error(ParseErrorKind::OutOfRange); }
+    let year = try!(sc.digits(yeardigits, yeardigits)) as i32 * sign.unwrap_or(1);
+    if extended { try!(sc.expect('-')); }
+
+    if sc.eat('W') {                                        // week date
+        let week = try!(sc.digits_in(2, 2, 1, 53));
+        if extended { try!(sc.expect('-')); }
+        let weekday = try!(sc.digits_in(1, 1, 1, 7));
+        let weekday = num::from_u32(weekday as u32 - 1).unwrap();
+        return iso8601_make_date(date_pos, year,
+                                 NaiveDate::from_isoywd_opt(year, week as u32, weekday));
+    }
+
+    let rest = sc.rest().as_bytes();
+    let ndigits = rest.iter().take_while(|&&b| b'0' <= b && b <= b'9').count();
+    if ndigits == 3 && (extended || sign.is_none()) {      // ordinal date
+        let ordinal = try!(sc.digits_in(3, 3, 1, 366));
+        return iso8601_make_date(date_pos, year, NaiveDate::from_yo_opt(year, ordinal as u32));
+    }
+    let month = try!(sc.digits_in(2, 2, 1, 12));            // calendar date
+    if extended { try!(sc.expect('-')); }
+    let day = try!(sc.digits_in(2, 2, 1, 31));
+    iso8601_make_date(date_pos, year, NaiveDate::from_ymd_opt(year, month as u32, day as u32))
+}
+
+/// Reads an ISO 8601 time of day, and returns the seconds from midnight
+/// (86400 for `24:00`) and the nanosecond (1,000,000,000 or more for the leap second).
+fn iso8601_time(sc: &mut Scanner) -> ParseResult<(u32, u32)> {
+    let time_pos = sc.pos;
+    let hh = try!(sc.digits_in(2, 2, 0, 24));
+    let extended = sc.eat(':');
+    let mut unit = 3600;                                    // seconds in the last component
+    let mut mm = 0;
+    let mut ss = 0;
+    if extended || sc.peek_digit() {
+        mm = try!(sc.digits_in(2, 2, 0, 59));
+        unit = 60;
+        if (extended && sc.eat(':')) || (!extended && sc.peek_digit()) {
+            ss = try!(sc.digits_in(2, 2, 0, 60));
+            unit = 1;
+        }
+    }
+    let frac = if sc.eat('.') || sc.eat(',') {
+        try!(sc.nanosecond()) as i64 * unit
+    } else {
+        0
+    };
+    if hh == 24 && (mm != 0 || ss != 0 || frac != 0) {
+        return Err(ParseError { kind: ParseErrorKind::OutOfRange, pos: time_pos });
+    }
+    if ss == 60 {                                           // leap second
+        let secs = hh * 3600 + mm * 60 + 59;
+        return Ok((secs as u32, 1_000_000_000 + frac as u32));
+    }
+    let (secs, nano) = div_mod_floor(frac, 1_000_000_000);
+    Ok(((hh * 3600 + mm * 60 + ss + secs) as u32, nano as u32))
+}
+
+/// Reads an ISO 8601 offset in seconds: `Z`, `+hh`, `+hhmm` or `+hh:mm` (or `-`).
+fn iso8601_offset(sc: &mut Scanner) -> ParseResult<i32> {
+    if sc.eat('Z') { return Ok(0); }
+    let start = sc.pos;
+    let sign = if sc.eat('+') { 1 } else if sc.eat('-') { -1 } else { return sc.unexpected(); };
+    let hh = try!(sc.digits_in(2, 2, 0, 23));
+    let mm = if sc.eat(':') || sc.peek_digit() {
+        try!(sc.digits_in(2, 2, 0, 59))
+    } else {
+        0
     };
-    let offsetstr = captures.at(8).unwrap();                    // time zone offset, numeric
-    let offsetmm = match offsetmins(offsetstr) {                // also accepts named time zones, not required.
-        Some(v) => v,
-        None => return None
-    };    
-    let tz = ::FixedOffset::east(offsetmm*60);                    // decode time zone offset
-    //  Pack numeric values into DateTime object, returning None if fail.
-    let date = tz.ymd_opt(yyyy, mo, dd);                        // date or none
-    match date {                                                // check for invalid date
-        ::LocalResult::Single(d) => d.and_hms_nano_opt(hh, mm, ss, ns), // encode into DateTime
-        _ => return None                                        // date conversion failed
-    }     
+    sc.last = start;
+    Ok(sign * (hh * 3600 + mm * 60) as i32)
+}
+
+/// Reads an ISO 8601 date and time separated by `T`.
+fn iso8601_naive_datetime(sc: &mut Scanner) -> ParseResult<NaiveDateTime> {
+    let date = try!(iso8601_date(sc));
+    try!(sc.expect('T'));
+    let time_pos = sc.pos;
+    let (secs, nano) = try!(iso8601_time(sc));
+    let (date, secs) = if secs == 86400 {                   // `24:00` of the day
+        match date.succ_opt() {
+            Some(date) => (date, 0),
+            None => return Err(ParseError { kind: ParseErrorKind::OutOfRange, pos: time_pos }),
+        }
+    } else {
+        (date, secs)
+    };
+    Ok(date.and_time(NaiveTime::from_num_seconds_from_midnight(secs, nano)))
+}
+
+/// Parse a string with an ISO 8601 calendar, ordinal or week date into a NaiveDate.
+pub fn iso8601_to_naive_date(s: &str) -> ParseResult<NaiveDate> {
+    let mut sc = Scanner::new(s);
+    let date = try!(iso8601_date(&mut sc));
+    try!(sc.expect_end());
+    Ok(date)
 }
 
-/// Parse ".NNN" into nanoseconds.
-/// Assumes input has already been checked for ".NNN" format.
-fn parsensfract(s: &str) -> u32 {
-    let sdigits = &s[1..];                                      // trim off leading "."
-    let sdigits9 = &sdigits[0..(cmp::min(sdigits.len(),9))]; // truncate at 9 digits after "."
-    let v = sdigits9.parse::<u32>().unwrap();                   // digits as u32 (will fit)
-    let vl = 9-sdigits9.len();                                  // power of 10 for scaling
-    let scale = Int::pow(10,vl);                                // scale factor to get to 
-    //panic!("parsens: s: {}  sdigits9: {}  v: {}  scale: {}  result: {}", s, sdigits9 , v, scale, v*scale);   // ***TEMP***       
-    v*scale                                                     // as nanoseconds
+/// Parse a string with an ISO 8601 date and time, without an offset, into a NaiveDateTime.
+pub fn iso8601_to_naive_datetime(s: &str) -> ParseResult<NaiveDateTime> {
+    let mut sc = Scanner::new(s);
+    let datetime = try!(iso8601_naive_datetime(&mut sc));
+    try!(sc.expect_end());
+    Ok(datetime)
 }
 
-/// Formats a DateTime as an RFC 3339/ISO8601 date, with 9 digits of nanoseconds.
-/// This is the inverse operation of rfc3339 parsing.
-pub fn fmt_rfc3339_datetime(dt: ::DateTime<::FixedOffset>) -> String {
-    dt.format("%Y-%m-%dT%H:%M:%S.%f%z").to_string()           // inverse of parsing
+/// Parse a string with an ISO 8601 date, time and offset into a DateTime.
+pub fn iso8601_to_datetime(s: &str) -> ParseResult<DateTime<FixedOffset>> {
+    let mut sc = Scanner::new(s);
+    let datetime = try!(iso8601_naive_datetime(&mut sc));
+    let offset = FixedOffset::east(try!(iso8601_offset(&mut sc)));
+    try!(sc.expect_end());
+    match offset.from_local_datetime(&datetime).single() {
+        Some(dt) => Ok(dt),
+        None => sc.error(ParseErrorKind::OutOfRange),
+    }
 }
 
+//
+//  ISO 8601 duration parsing
+//
+//  Examples: "P3DT4H5M6.5S", "PT0.001S", "P2W", "-PT30M".
+//  `Duration` is an exact length of time, so the years and months of varying lengths
+//  are not accepted (see `Period` for them), and a day is always 24 hours.
+//
+//   duration        = ["+" / "-"] "P" (weeks / days-time)
+//   weeks           = number "W"
+//   days-time       = number "D" [time]  /  time
+//   time            = "T" [number "H"] [number "M"] [number "S"] ; at least one
+//   number          = 1*DIGIT [("." / ",") 1*DIGIT] ; fraction only in the last component
+//
+//  The letters are also accepted in lower case, as `Period` does.
+//
+
+/// Parse a string with an ISO 8601 duration in weeks, days, hours, minutes and seconds
+/// into a Duration. The leading `-` makes a negative duration.
+pub fn iso8601_to_duration(s: &str) -> ParseResult<Duration> {
+    let mut sc = Scanner::new(s);
+    let negative = if sc.eat('-') { true } else { sc.eat('+'); false };
+    if !sc.eat_ci('P') { return sc.unexpected(); }
+    let mut secs = 0i64;
+    let mut nanos = 0i64;                                   // from the fraction, if any
+    let mut units = "WD";                                   // the units allowed from now on
+    let mut in_time = false;
+    loop {
+        if !in_time && sc.eat_ci('T') { in_time = true; units = "HMS"; }
+        let start = sc.pos;
+        let v = try!(sc.digits(1, 18));
+        let frac = if sc.eat('.') || sc.eat(',') { Some(try!(sc.nanosecond())) } else { None };
+        let i = match sc.peek().and_then(|c| units.find(c.to_ascii_uppercase())) {
+            Some(i) => i,
+            None => return sc.unexpected(),
+        };
+        let unit = units.char_at(i);
+        let unit_secs = match unit { 'W' => 604800, 'D' => 86400, 'H' => 3600, 'M' => 60, _ => 1 };
+        secs = match v.checked_mul(unit_secs).and_then(|v| v.checked_add(secs)) {
+            Some(secs) => secs,
+            None => return Err(ParseError { kind: ParseErrorKind::OutOfRange, pos: start }),
+        };
+        sc.pos += 1;
+        units = &units[i+1..];
+        if let Some(frac) = frac { nanos = frac as i64 * unit_secs; break; }
+        if unit == 'W' || sc.is_end() { break; }
+    }
+    try!(sc.expect_end());
+
+    let (carry, nanos) = div_mod_floor(nanos, 1_000_000_000);
+    let d = match secs.checked_add(carry) {
+        Some(secs) if secs <= duration::MAX.num_seconds() =>
+            Duration::seconds(secs).checked_add(&Duration::nanoseconds(nanos)),
+        _ => None,
+    };
+    match d {
+        Some(d) => Ok(if negative { -d } else { d }),
+        None => sc.error(ParseErrorKind::OutOfRange),
+    }
+}
+
+//
+//  FromStr implementations
+//
+//  Every date and time type can be parsed from its `Debug` output,
+//  so that `format!("{:?}", x).parse()` gives `x` back. Examples:
+//  "2015-01-20", "-0001-12-31", "17:35:20.500", "23:59:60",
+//  "2015-01-20T17:35:20-08:00", "2015-01-20T17:35:20Z", "+09:30", "Tue".
+//
+
+/// Reads a date as printed by `NaiveDate`'s `Debug`: `YYYY-MM-DD`, or with a sign
+/// and 4 or more digits for the year.
+fn debug_date(sc: &mut Scanner) -> ParseResult<NaiveDate> {
+    let date_pos = sc.pos;
+    let sign = if sc.eat('+') { 1 } else if sc.eat('-') { -1 } else { 0 };
+    let year = if sign == 0 { try!(sc.digits(4, 4)) } else { sign * try!(sc.digits(4, 9)) };
+    try!(sc.expect('-'));
+    let month = try!(sc.digits_in(2, 2, 1, 12));
+    try!(sc.expect('-'));
+    let day = try!(sc.digits_in(2, 2, 1, 31));
+    iso8601_make_date(date_pos, year as i32,
+                      NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32))
+}
+
+/// Reads a time as printed by `NaiveTime`'s `Debug`: `HH:MM:SS` with an optional fraction.
+/// The second 60 is the leap second.
+fn debug_time(sc: &mut Scanner) -> ParseResult<NaiveTime> {
+    let hh = try!(sc.digits_in(2, 2, 0, 23));
+    try!(sc.expect(':'));
+    let mm = try!(sc.digits_in(2, 2, 0, 59));
+    try!(sc.expect(':'));
+    let ss = try!(sc.digits_in(2, 2, 0, 60));
+    let ns = if sc.eat('.') { try!(sc.nanosecond()) } else { 0 };
+    let (ss, ns) = if ss == 60 { (59, ns + 1_000_000_000) } else { (ss, ns) };
+    Ok(NaiveTime::from_hms_nano(hh as u32, mm as u32, ss as u32, ns))
+}
+
+/// Reads an offset as printed by `FixedOffset`'s `Debug`: `+HH:MM` or `+HH:MM:SS` (or `-`).
+fn debug_offset(sc: &mut Scanner) -> ParseResult<FixedOffset> {
+    let sign = if sc.eat('+') { 1 } else if sc.eat('-') { -1 } else { return sc.unexpected(); };
+    let hh = try!(sc.digits_in(2, 2, 0, 23));
+    try!(sc.expect(':'));
+    let mm = try!(sc.digits_in(2, 2, 0, 59));
+    let ss = if sc.eat(':') { try!(sc.digits_in(2, 2, 0, 59)) } else { 0 };
+    Ok(FixedOffset::east(sign * (hh * 3600 + mm * 60 + ss) as i32))
+}
+
+/// Reads a date and time as printed by `NaiveDateTime`'s `Debug`.
+fn debug_datetime(sc: &mut Scanner) -> ParseResult<NaiveDateTime> {
+    let date = try!(debug_date(sc));
+    try!(sc.expect('T'));
+    let time = try!(debug_time(sc));
+    Ok(date.and_time(time))
+}
+
+/// Runs `f` over the whole of `s`.
+fn parse_whole<T, F>(s: &str, f: F) -> ParseResult<T>
+        where F: FnOnce(&mut Scanner) -> ParseResult<T> {
+    let mut sc = Scanner::new(s);
+    let v = try!(f(&mut sc));
+    try!(sc.expect_end());
+    Ok(v)
+}
+
+/// Makes a `DateTime` for the local date and time, failing at the end of `s` if impossible.
+fn local_to_datetime<Off: Offset>(s: &str, offset: Off,
+                                  local: &NaiveDateTime) -> ParseResult<DateTime<Off>> {
+    match offset.from_local_datetime(local).single() {
+        Some(dt) => Ok(dt),
+        None => Err(ParseError { kind: ParseErrorKind::OutOfRange, pos: s.len() }),
+    }
+}
+
+impl FromStr for NaiveDate {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<NaiveDate> {
+        parse_whole(s, |sc| debug_date(sc))
+    }
+}
+
+impl FromStr for NaiveTime {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<NaiveTime> {
+        parse_whole(s, |sc| debug_time(sc))
+    }
+}
+
+impl FromStr for NaiveDateTime {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<NaiveDateTime> {
+        parse_whole(s, |sc| debug_datetime(sc))
+    }
+}
+
+impl FromStr for FixedOffset {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<FixedOffset> {
+        parse_whole(s, |sc| debug_offset(sc))
+    }
+}
+
+/// Accepts the local date and time followed by the offset, e.g. `2015-01-20T17:35:20-08:00`.
+impl FromStr for DateTime<FixedOffset> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<DateTime<FixedOffset>> {
+        let (local, offset) = try!(parse_whole(s, |sc| {
+            let local = try!(debug_datetime(sc));
+            let offset = try!(debug_offset(sc));
+            Ok((local, offset))
+        }));
+        local_to_datetime(s, offset, &local)
+    }
+}
+
+/// Accepts the date and time followed by `Z`, e.g. `2015-01-20T17:35:20Z`.
+impl FromStr for DateTime<UTC> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<DateTime<UTC>> {
+        let local = try!(parse_whole(s, |sc| {
+            let local = try!(debug_datetime(sc));
+            try!(sc.expect('Z'));
+            Ok(local)
+        }));
+        local_to_datetime(s, UTC, &local)
+    }
+}
+
+/// Accepts the abbreviated (`Mon`) or full (`Monday`) English name, ignoring the case.
+impl FromStr for Weekday {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<Weekday> {
+        parse_whole(s, |sc| {
+            if sc.peek().map_or(false, |c| c.is_whitespace()) { return sc.unexpected(); }
+            let i = try!(sc.name(&LONG_WEEKDAYS, &SHORT_WEEKDAYS));
+            Ok(num::from_u32(i as u32).unwrap())
+        })
+    }
+}
+
+/// Parses the ISO 8601 period `PnYnMnWnD` (e.g. `P1Y2M3D`), where the units are optional
+/// but in this order, and at least one is required. The weeks are counted as 7 days.
+/// The leading `-` negates the whole period, and each number can be negative as well.
+impl FromStr for Period {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<Period> {
+        parse_whole(s, |sc| {
+            let negative = if sc.eat('-') { true } else { sc.eat('+'); false };
+            if !sc.eat_ci('P') { return sc.unexpected(); }
+            let (mut years, mut months, mut days) = (0i64, 0i64, 0i64);
+            let mut units = "YMWD";                         // the units allowed from now on
+            loop {
+                let v = if sc.eat('-') { -try!(sc.digits(1, 9)) } else { try!(sc.digits(1, 9)) };
+                let i = match sc.peek().and_then(|c| units.find(c.to_ascii_uppercase())) {
+                    Some(i) => i,
+                    None => return sc.unexpected(),
+                };
+                match units.char_at(i) {
+                    'Y' => years = v,
+                    'M' => months = v,
+                    'W' => days += v * 7,
+                    _ => days += v,
+                }
+                sc.pos += 1;
+                units = &units[i+1..];
+                if sc.is_end() { break; }
+            }
+            if negative { years = -years; months = -months; days = -days; }
+            if days.to_i32().is_none() { return sc.error(ParseErrorKind::OutOfRange); }
+            Ok(Period::new(years as i32, months as i32, days as i32))
+        })
+    }
+}
+
+//
+//  RFC 5545 recurrence rule parsing
+//
+//  Example: "FREQ=MONTHLY;BYDAY=2TU;COUNT=10", optionally after "RRULE:".
+//  Common use case: recurring events in iCalendar.
+//
+//   From RFC5545, "Internet Calendaring and Scheduling Core Object Specification
+//   (iCalendar)", section 3.3.10:
+//
+//   recur           = recur-rule-part *( ";" recur-rule-part )
+//                   ;
+//                   ; The rule parts are not ordered in any
+//                   ; particular sequence.
+//                   ;
+//                   ; The FREQ rule part is REQUIRED,
+//                   ; but MUST NOT occur more than once.
+//                   ;
+//                   ; The UNTIL or COUNT rule parts are OPTIONAL,
+//                   ; but they MUST NOT occur in the same 'recur'.
+//                   ;
+//                   ; The other rule parts are OPTIONAL,
+//                   ; but MUST NOT occur more than once.
+//
+//   recur-rule-part = ( "FREQ" "=" freq )
+//                   / ( "UNTIL" "=" enddate )
+//                   / ( "COUNT" "=" 1*DIGIT )
+//                   / ( "INTERVAL" "=" 1*DIGIT )
+//                   / ( "BYSECOND" "=" byseclist )
+//                   / ( "BYMINUTE" "=" byminlist )
+//                   / ( "BYHOUR" "=" byhrlist )
+//                   / ( "BYDAY" "=" bywdaylist )
+//                   / ( "BYMONTHDAY" "=" bymodaylist )
+//                   / ( "BYYEARDAY" "=" byyrdaylist )
+//                   / ( "BYWEEKNO" "=" bywknolist )
+//                   / ( "BYMONTH" "=" bymolist )
+//                   / ( "BYSETPOS" "=" bysplist )
+//                   / ( "WKST" "=" weekday )
+//
+//   freq        = "SECONDLY" / "MINUTELY" / "HOURLY" / "DAILY"
+//               / "WEEKLY" / "MONTHLY" / "YEARLY"
+//
+//   enddate     = date / date-time
+//
+//   bywdaylist  = ( weekdaynum *("," weekdaynum) )
+//   weekdaynum  = [[plus / minus] ordwk] weekday
+//   ordwk       = 1*2DIGIT       ;1 to 53
+//   weekday     = "SU" / "MO" / "TU" / "WE" / "TH" / "FR" / "SA"
+//
+//  The other lists are comma-separated numbers, signed where the negative ones are allowed.
+//  The names are case-insensitive. The date of `UNTIL` is `YYYYMMDD`, and the date-time
+//  `YYYYMMDDThhmmss` with an optional `Z`; the date alone covers the whole day.
+//
+
+/// The names of the rule parts, in the order of `RRule`'s `Display`.
+static RRULE_PARTS: [&'static str; 14] =
+    ["FREQ", "UNTIL", "COUNT", "INTERVAL", "BYSECOND", "BYMINUTE", "BYHOUR", "BYDAY",
+     "BYMONTHDAY", "BYYEARDAY", "BYWEEKNO", "BYMONTH", "BYSETPOS", "WKST"];
+
+/// Reads a comma-separated list of numbers from `lo` to `hi` for the `BY*` rule parts.
+/// The negative numbers are allowed when `lo` is negative, but not zero then.
+fn rrule_numbers(sc: &mut Scanner, lo: i64, hi: i64) -> ParseResult<Vec<i64>> {
+    let mut values = Vec::new();
+    loop {
+        let start = sc.pos;
+        let negative = lo < 0 && sc.eat('-');
+        if lo < 0 && !negative { sc.eat('+'); }
+        let v = try!(sc.digits(1, 3));
+        let v = if negative { -v } else { v };
+        if v < lo || v > hi || (lo < 0 && v == 0) {
+            return Err(ParseError { kind: ParseErrorKind::OutOfRange, pos: start });
+        }
+        values.push(v);
+        if !sc.eat(',') { return Ok(values); }
+    }
+}
+
+/// Reads one of `names` as `Scanner::name` does, but without skipping the whitespace.
+fn rrule_name(sc: &mut Scanner, names: &[&str]) -> ParseResult<usize> {
+    if sc.peek().map_or(false, |c| c.is_whitespace()) { return sc.unexpected(); }
+    sc.name(names, &[])
+}
+
+/// Reads a two-letter weekday code.
+fn rrule_weekday(sc: &mut Scanner) -> ParseResult<Weekday> {
+    let i = try!(rrule_name(sc, &rrule::WEEKDAY_CODES));
+    Ok(num::from_u32(i as u32).unwrap())
+}
+
+/// Reads the `UNTIL` date or date-time, and returns it with true if it is in UTC
+/// and true if it is a date alone.
+fn rrule_until(sc: &mut Scanner) -> ParseResult<(NaiveDateTime, bool, bool)> {
+    let date_pos = sc.pos;
+    let year = try!(sc.digits(4, 4));
+    let month = try!(sc.digits_in(2, 2, 1, 12));
+    let day = try!(sc.digits_in(2, 2, 1, 31));
+    let date = match NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32) {
+        Some(date) => date,
+        None => return Err(ParseError { kind: ParseErrorKind::Impossible, pos: date_pos }),
+    };
+    if !sc.eat_ci('T') {                                    // the end of the day
+        return Ok((date.and_hms_nano(23, 59, 59, 999_999_999), false, true));
+    }
+    let hh = try!(sc.digits_in(2, 2, 0, 23));
+    let mm = try!(sc.digits_in(2, 2, 0, 59));
+    let ss = try!(sc.digits_in(2, 2, 0, 60));               // second, 60 is the leap second
+    let (ss, ns) = if ss == 60 { (59, 1_000_000_000) } else { (ss, 0) };
+    let utc = sc.eat_ci('Z');
+    Ok((date.and_hms_nano(hh as u32, mm as u32, ss as u32, ns), utc, false))
+}
+
+/// Parses the RFC 5545 recurrence rule (e.g. `FREQ=MONTHLY;BYDAY=2TU;COUNT=10`),
+/// optionally preceded by `RRULE:`. The rule parts can be in any order,
+/// but each of them can appear only once. See the `rrule` module for the expansion.
+impl FromStr for RRule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> ParseResult<RRule> {
+        parse_whole(s, |sc| {
+            match prefix_len_ci(sc.rest(), "RRULE:") {
+                Some(len) => sc.pos += len,
+                None => {}
+            }
+            let mut rule = RRule::new(rrule::Frequency::Yearly);
+            let mut seen = [false; 14];
+            loop {
+                let name_pos = sc.pos;
+                let i = try!(rrule_name(sc, &RRULE_PARTS));
+                if seen[i] {
+                    return Err(ParseError { kind: ParseErrorKind::Impossible, pos: name_pos });
+                }
+                seen[i] = true;
+                try!(sc.expect('='));
+                let signed = |&: values: Vec<i64>| -> Vec<i32> {
+                    values.iter().map(|&v| v as i32).collect()
+                };
+                let unsigned = |&: values: Vec<i64>| -> Vec<u32> {
+                    values.iter().map(|&v| v as u32).collect()
+                };
+                match RRULE_PARTS[i] {
+                    "FREQ" => {
+                        let freq = try!(rrule_name(sc, &rrule::FREQUENCY_NAMES));
+                        rule.freq = num::from_u32(freq as u32).unwrap();
+                    }
+                    "UNTIL" => {
+                        let (until, utc, date) = try!(rrule_until(sc));
+                        rule.until = Some(until);
+                        rule.until_utc = utc;
+                        rule.until_date = date;
+                    }
+                    "COUNT" => rule.count = Some(try!(sc.digits_in(1, 9, 1, 999_999_999)) as u32),
+                    "INTERVAL" => rule.interval = try!(sc.digits_in(1, 9, 1, 999_999_999)) as u32,
+                    "BYSECOND" => rule.by_second = unsigned(try!(rrule_numbers(sc, 0, 60))),
+                    "BYMINUTE" => rule.by_minute = unsigned(try!(rrule_numbers(sc, 0, 59))),
+                    "BYHOUR" => rule.by_hour = unsigned(try!(rrule_numbers(sc, 0, 23))),
+                    "BYDAY" => loop {
+                        let start = sc.pos;
+                        let sign = if sc.eat('-') { -1 } else { sc.eat('+'); 1 };
+                        let n = if sc.peek_digit() {
+                            let n = try!(sc.digits(1, 2));
+                            if n < 1 || n > 53 {
+                                return Err(ParseError { kind: ParseErrorKind::OutOfRange,
+                                                        pos: start });
+                            }
+                            Some(sign * n as i32)
+                        } else if sc.pos > start {
+                            return sc.unexpected();                 // a sign without digits
+                        } else {
+                            None
+                        };
+                        rule.by_day.push((n, try!(rrule_weekday(sc))));
+                        if !sc.eat(',') { break; }
+                    },
+                    "BYMONTHDAY" => rule.by_month_day = signed(try!(rrule_numbers(sc, -31, 31))),
+                    "BYYEARDAY" => rule.by_year_day = signed(try!(rrule_numbers(sc, -366, 366))),
+                    "BYWEEKNO" => rule.by_week_no = signed(try!(rrule_numbers(sc, -53, 53))),
+                    "BYMONTH" => rule.by_month = unsigned(try!(rrule_numbers(sc, 1, 12))),
+                    "BYSETPOS" => rule.by_set_pos = signed(try!(rrule_numbers(sc, -366, 366))),
+                    _ => rule.week_start = try!(rrule_weekday(sc)),        // WKST
+                }
+                if !sc.eat(';') { break; }
+            }
+            if !seen[0] { return sc.error(ParseErrorKind::NotEnough); }        // no FREQ
+            if seen[1] && seen[2] { return sc.error(ParseErrorKind::Impossible); } // UNTIL, COUNT
+            Ok(rule)
+        })
+    }
+}
+
+//
+//  Format-string parsing (strptime)
+//
+//  The inverse of `format::DelayedFormat`, driven by the same formatting items
+//  (`format::Item`), so a format string compiled once serves both directions.
+//  The fields are first collected into `Parsed`, then resolved into the actual
+//  date and time, so the fields can appear in any order and the redundant
+//  fields (e.g. the day of week) are checked against the result.
+//
+//  Whitespace in the format string matches zero or more whitespace in the input,
+//  and numeric fields may be preceded by whitespace (as `%e` and `%k` pad with spaces).
+//  `%Z` skips the time zone name, which cannot be resolved into the offset.
+//
+
+/// Date and time fields collected from the input string,
+/// before they are resolved into the actual date and time.
+///
+/// A field can be set multiple times only with the same value,
+/// so the conflicting inputs (e.g. `%H` and `%p`) are detected.
+/// The errors from the `to_*` methods are not tied to the input and have the position 0;
+/// the `parse_from_str` methods report them at the end of the input instead.
+#[derive(Clone, PartialEq, Eq, Show, Default)]
+pub struct Parsed {
+    /// Year (`%Y`).
+    pub year: Option<i32>,
+    /// Year divided by 100 (`%C`).
+    pub year_div_100: Option<i32>,
+    /// Year modulo 100 (`%y`).
+    pub year_mod_100: Option<i32>,
+    /// Year in the ISO week date (`%G`).
+    pub isoyear: Option<i32>,
+    /// Year in the ISO week date modulo 100 (`%g`).
+    pub isoyear_mod_100: Option<i32>,
+    /// Month from 1 to 12 (`%m`, `%b`, `%B`).
+    pub month: Option<u32>,
+    /// Week number starting from Sunday, from 0 to 53 (`%U`).
+    pub week_from_sun: Option<u32>,
+    /// Week number starting from Monday, from 0 to 53 (`%W`).
+    pub week_from_mon: Option<u32>,
+    /// Week number in the ISO week date, from 1 to 53 (`%V`).
+    pub isoweek: Option<u32>,
+    /// Day of week (`%a`, `%A`, `%w`, `%u`).
+    pub weekday: Option<Weekday>,
+    /// Day of year from 1 to 366 (`%j`).
+    pub ordinal: Option<u32>,
+    /// Day of month from 1 to 31 (`%d`, `%e`).
+    pub day: Option<u32>,
+    /// Hour divided by 12, i.e. 1 for PM (`%p`, `%P`, also set by `%H`).
+    pub hour_div_12: Option<u32>,
+    /// Hour modulo 12 (`%I`, `%l`, also set by `%H`).
+    pub hour_mod_12: Option<u32>,
+    /// Minute (`%M`).
+    pub minute: Option<u32>,
+    /// Second from 0 to 60, where 60 is the leap second (`%S`).
+    pub second: Option<u32>,
+    /// Nanosecond (`%f`).
+    pub nanosecond: Option<u32>,
+    /// Offset from UTC in seconds (`%z`, `%:z`, `%::z`, `%#z`).
+    pub offset: Option<i32>,
+    /// The number of non-leap seconds since January 1, 1970 0:00:00 UTC (`%s`).
+    pub timestamp: Option<i64>,
+}
+
+/// Sets `field` to `value`. Fails when the field has been set to the different value,
+/// reporting the error at the start of the last scanned field.
+fn set<T: PartialEq>(sc: &Scanner, field: &mut Option<T>, value: T) -> ParseResult<()> {
+    match *field {
+        Some(ref old) if *old != value => return sc.error_last(ParseErrorKind::Impossible),
+        _ => {}
+    }
+    *field = Some(value);
+    Ok(())
+}
+
+/// Makes an error for the `to_*` methods of `Parsed`.
+fn resolve_error<T>(kind: ParseErrorKind) -> ParseResult<T> {
+    Err(ParseError { kind: kind, pos: 0 })
+}
+
+/// Resolves the year from the full year, or the century and the year within the century.
+/// The year within the century alone is from 1969 to 2068, as in POSIX `strptime`.
+fn resolve_year(year: Option<i32>, div_100: Option<i32>, mod_100: Option<i32>) -> Option<i32> {
+    match (year, div_100, mod_100) {
+        (Some(y), _, _) => Some(y),
+        (None, Some(q), Some(r)) => Some(q * 100 + r),
+        (None, None, Some(r)) => Some(if r < 69 { 2000 + r } else { 1900 + r }),
+        (None, _, None) => None,
+    }
+}
+
+impl Parsed {
+    /// Makes a new `Parsed` with no fields set.
+    pub fn new() -> Parsed {
+        Parsed::default()
+    }
+
+    /// Returns true if every date field set is consistent with given date.
+    fn is_consistent_date(&self, date: &NaiveDate) -> bool {
+        let (year_div_100, year_mod_100) = div_mod_floor(date.year(), 100);
+        let (isoyear, isoweek, _) = date.isoweekdate();
+        let week_from_sun = (date.ordinal() + 7 - date.weekday().num_days_from_sunday()) / 7;
+        let week_from_mon = (date.ordinal() + 7 - date.weekday().num_days_from_monday()) / 7;
+        fn check<T: PartialEq>(field: Option<T>, value: T) -> bool {
+            field.map_or(true, |v| v == value)
+        }
+        check(self.year, date.year()) &&
+            check(self.year_div_100, year_div_100) &&
+            check(self.year_mod_100, year_mod_100) &&
+            check(self.isoyear, isoyear) &&
+            check(self.isoyear_mod_100, mod_floor(isoyear, 100)) &&
+            check(self.month, date.month()) &&
+            check(self.week_from_sun, week_from_sun) &&
+            check(self.week_from_mon, week_from_mon) &&
+            check(self.isoweek, isoweek) &&
+            check(self.weekday, date.weekday()) &&
+            check(self.ordinal, date.ordinal()) &&
+            check(self.day, date.day())
+    }
+
+    /// Returns true if every time field set is consistent with given time.
+    fn is_consistent_time(&self, time: &NaiveTime) -> bool {
+        fn check<T: PartialEq>(field: Option<T>, value: T) -> bool {
+            field.map_or(true, |v| v == value)
+        }
+        check(self.hour_div_12, time.hour() / 12) &&
+            check(self.hour_mod_12, time.hour() % 12) &&
+            check(self.minute, time.minute()) &&
+            check(self.second, time.second())
+    }
+
+    /// Resolves the parsed fields into a `NaiveDate`. The date can be given as
+    /// the year, month and day; the year and day of year; the year, week number and day of week;
+    /// or the ISO week date.
+    ///
+    /// Fails with `NotEnough` when the fields are insufficient,
+    /// and `Impossible` when they are inconsistent or do not form a valid date.
+    pub fn to_naive_date(&self) -> ParseResult<NaiveDate> {
+        let year = resolve_year(self.year, self.year_div_100, self.year_mod_100);
+        let isoyear = resolve_year(self.isoyear, None, self.isoyear_mod_100);
+
+        // the date of given week number, where `first` is the number of days
+        // from the first day of the week (Sunday or Monday) to January 1.
+        let from_week = |&: year: i32, week: u32, days: u32, first: u32| {
+            let ordinal = (week as i32 - 1) * 7 + days as i32 + (7 - first as i32) % 7 + 1;
+            if ordinal < 1 { return None; }
+            NaiveDate::from_yo_opt(year, ordinal as u32)
+        };
+        let jan1_weekday = |&: year: i32| NaiveDate::from_ymd_opt(year, 1, 1).map(|d| d.weekday());
+
+        let date = match (year, self.month, self.day, self.ordinal) {
+            (Some(year), Some(month), Some(day), _) => NaiveDate::from_ymd_opt(year, month, day),
+            (Some(year), _, _, Some(ordinal)) => NaiveDate::from_yo_opt(year, ordinal),
+            _ => match (year, self.week_from_sun, self.week_from_mon,
+                        isoyear, self.isoweek, self.weekday) {
+                (Some(year), Some(week), _, _, _, Some(weekday)) =>
+                    jan1_weekday(year).and_then(|jan1| {
+                        from_week(year, week, weekday.num_days_from_sunday(),
+                                  jan1.num_days_from_sunday())
+                    }),
+                (Some(year), _, Some(week), _, _, Some(weekday)) =>
+                    jan1_weekday(year).and_then(|jan1| {
+                        from_week(year, week, weekday.num_days_from_monday(),
+                                  jan1.num_days_from_monday())
+                    }),
+                (_, _, _, Some(isoyear), Some(isoweek), Some(weekday)) =>
+                    NaiveDate::from_isoywd_opt(isoyear, isoweek, weekday),
+                _ => return resolve_error(ParseErrorKind::NotEnough),
+            },
+        };
+
+        match date {
+            Some(date) if self.is_consistent_date(&date) => Ok(date),
+            _ => resolve_error(ParseErrorKind::Impossible),
+        }
+    }
+
+    /// Resolves the parsed fields into a `NaiveTime`. The hour and minute are required;
+    /// the second and nanosecond default to zero.
+    ///
+    /// Fails with `NotEnough` when the fields are insufficient,
+    /// and `OutOfRange` when they do not form a valid time.
+    pub fn to_naive_time(&self) -> ParseResult<NaiveTime> {
+        let (hour_div_12, hour_mod_12, minute) =
+            match (self.hour_div_12, self.hour_mod_12, self.minute) {
+                (Some(hour_div_12), Some(hour_mod_12), Some(minute)) =>
+                    (hour_div_12, hour_mod_12, minute),
+                _ => return resolve_error(ParseErrorKind::NotEnough),
+            };
+        if hour_div_12 > 1 || hour_mod_12 > 11 { return resolve_error(ParseErrorKind::OutOfRange); }
+        let (second, nano) = match (self.second.unwrap_or(0), self.nanosecond.unwrap_or(0)) {
+            (60, nano) => (59, nano + 1_000_000_000), // the leap second
+            (second, nano) => (second, nano),
+        };
+        match NaiveTime::from_hms_nano_opt(hour_div_12 * 12 + hour_mod_12, minute, second, nano) {
+            Some(time) => Ok(time),
+            None => resolve_error(ParseErrorKind::OutOfRange),
+        }
+    }
+
+    /// Resolves the parsed fields into a `NaiveDateTime`.
+    /// Fails as `to_naive_date` and `to_naive_time` do.
+    ///
+    /// When the timestamp is given, it is shifted by the offset (if any) into the local time,
+    /// and the other date and time fields only have to be consistent with it.
+    pub fn to_naive_datetime(&self) -> ParseResult<NaiveDateTime> {
+        if let Some(timestamp) = self.timestamp {
+            let nano = self.nanosecond.unwrap_or(0);
+            let datetime = timestamp.checked_add(self.offset.unwrap_or(0) as i64)
+                .and_then(|secs| NaiveDateTime::from_num_seconds_from_unix_epoch_opt(secs, nano));
+            return match datetime {
+                Some(dt) if self.is_consistent_date(&dt.date()) &&
+                            self.is_consistent_time(&dt.time()) => Ok(dt),
+                Some(_) => resolve_error(ParseErrorKind::Impossible),
+                None => resolve_error(ParseErrorKind::OutOfRange),
+            };
+        }
+
+        let date = try!(self.to_naive_date());
+        let time = try!(self.to_naive_time());
+        Ok(date.and_time(time))
+    }
+
+    /// Resolves the parsed offset into a `FixedOffset`.
+    /// Fails with `NotEnough` when the offset is missing, and `OutOfRange` when it is invalid.
+    pub fn to_fixed_offset(&self) -> ParseResult<FixedOffset> {
+        match self.offset {
+            Some(offset) => match FixedOffset::east_opt(offset) {
+                Some(offset) => Ok(offset),
+                None => resolve_error(ParseErrorKind::OutOfRange),
+            },
+            None => resolve_error(ParseErrorKind::NotEnough),
+        }
+    }
+
+    /// Resolves the parsed fields into a `DateTime` with the parsed offset.
+    /// Fails as `to_naive_datetime` and `to_fixed_offset` do.
+    pub fn to_datetime(&self) -> ParseResult<DateTime<FixedOffset>> {
+        let offset = try!(self.to_fixed_offset());
+        let datetime = try!(self.to_naive_datetime());
+        match offset.from_local_datetime(&datetime).single() {
+            Some(dt) => Ok(dt),
+            None => resolve_error(ParseErrorKind::OutOfRange),
+        }
+    }
+}
+
+/// Parses the input from the scanner with the formatting items, leaving the trailing input.
+fn parse_prefix<'a, I>(parsed: &mut Parsed, sc: &mut Scanner, items: I,
+                       locale: &Locale) -> ParseResult<()>
+        where I: Iterator<Item=Item<'a>> {
+    static WEEKDAYS_FROM_SUN: [Weekday; 7] = [Weekday::Sun, Weekday::Mon, Weekday::Tue,
+                                              Weekday::Wed, Weekday::Thu, Weekday::Fri,
+                                              Weekday::Sat];
+
+    for item in items {
+        match item {
+            Item::Literal(s) => {                                   // literal characters
+                for c in s.chars() { try!(sc.expect(c)); }
+            }
+            Item::Space(_) => sc.skip_whitespace(),                 // any amount of whitespace
+
+            Item::Numeric(spec, _) => {
+                use format::Numeric::*;
+
+                let v = match spec {
+                    Year | IsoYear => try!(sc.year()) as i64,
+                    YearDiv100 | YearMod100 | IsoYearMod100 => try!(sc.number(1, 2, 0, 99)),
+                    Month => try!(sc.number(1, 2, 1, 12)),
+                    Day => try!(sc.number(1, 2, 1, 31)),
+                    WeekFromSun | WeekFromMon => try!(sc.number(1, 2, 0, 53)),
+                    IsoWeek => try!(sc.number(1, 2, 1, 53)),
+                    NumDaysFromSun => try!(sc.number(1, 1, 0, 6)),
+                    WeekdayFromMon => try!(sc.number(1, 1, 1, 7)),
+                    Ordinal => try!(sc.number(1, 3, 1, 366)),
+                    Hour => try!(sc.number(1, 2, 0, 23)),
+                    Hour12 => try!(sc.number(1, 2, 1, 12)),
+                    Minute => try!(sc.number(1, 2, 0, 59)),
+                    Second => try!(sc.number(1, 2, 0, 60)),
+                    Nanosecond => try!(sc.nanosecond()) as i64,
+                    Timestamp => {
+                        sc.skip_whitespace();
+                        let negative = sc.eat('-');
+                        let v = try!(sc.digits(1, 18));
+                        if negative { -v } else { v }
+                    }
+                };
+
+                match spec {
+                    Year => try!(set(sc, &mut parsed.year, v as i32)),
+                    YearDiv100 => try!(set(sc, &mut parsed.year_div_100, v as i32)),
+                    YearMod100 => try!(set(sc, &mut parsed.year_mod_100, v as i32)),
+                    IsoYear => try!(set(sc, &mut parsed.isoyear, v as i32)),
+                    IsoYearMod100 => try!(set(sc, &mut parsed.isoyear_mod_100, v as i32)),
+                    Month => try!(set(sc, &mut parsed.month, v as u32)),
+                    Day => try!(set(sc, &mut parsed.day, v as u32)),
+                    WeekFromSun => try!(set(sc, &mut parsed.week_from_sun, v as u32)),
+                    WeekFromMon => try!(set(sc, &mut parsed.week_from_mon, v as u32)),
+                    IsoWeek => try!(set(sc, &mut parsed.isoweek, v as u32)),
+                    NumDaysFromSun =>
+                        try!(set(sc, &mut parsed.weekday, WEEKDAYS_FROM_SUN[v as usize])),
+                    WeekdayFromMon =>
+                        try!(set(sc, &mut parsed.weekday, WEEKDAYS_FROM_SUN[v as usize % 7])),
+                    Ordinal => try!(set(sc, &mut parsed.ordinal, v as u32)),
+                    Hour => {
+                        try!(set(sc, &mut parsed.hour_div_12, v as u32 / 12));
+                        try!(set(sc, &mut parsed.hour_mod_12, v as u32 % 12));
+                    }
+                    Hour12 => try!(set(sc, &mut parsed.hour_mod_12, v as u32 % 12)),
+                    Minute => try!(set(sc, &mut parsed.minute, v as u32)),
+                    Second => try!(set(sc, &mut parsed.second, v as u32)),
+                    Nanosecond => try!(set(sc, &mut parsed.nanosecond, v as u32)),
+                    Timestamp => try!(set(sc, &mut parsed.timestamp, v)),
+                }
+            }
+
+            Item::Fixed(spec) | Item::Upper(spec) => {
+                use format::Fixed::*;
+
+                match spec {
+                    ShortMonthName | LongMonthName => {
+                        let i = try!(sc.name(locale.long_months, locale.short_months));
+                        try!(set(sc, &mut parsed.month, i as u32 + 1));
+                    }
+                    ShortWeekdayName | LongWeekdayName => {
+                        let i = try!(sc.name(locale.long_weekdays, locale.short_weekdays));
+                        try!(set(sc, &mut parsed.weekday, num::from_u32(i as u32).unwrap()));
+                    }
+                    LowerAmPm | UpperAmPm => {
+                        let is_pm = try!(sc.name(locale.am_pm, &[]));
+                        try!(set(sc, &mut parsed.hour_div_12, is_pm as u32));
+                    }
+                    TimezoneName => {
+                        sc.skip_whitespace();
+                        let rest = sc.rest();
+                        let len = rest.find(|&: c: char| c.is_whitespace()).unwrap_or(rest.len());
+                        if len == 0 { return sc.unexpected(); }
+                        sc.pos += len;
+                    }
+                    TimezoneOffset | TimezoneOffsetColon |
+                    TimezoneOffsetDoubleColon | TimezoneOffsetPermissive => {
+                        let v = try!(match spec {
+                            TimezoneOffset => sc.offset(Some(false)),
+                            TimezoneOffsetColon => sc.offset(Some(true)),
+                            TimezoneOffsetDoubleColon => sc.offset_hms(),
+                            _ => sc.offset(None),
+                        });
+                        try!(set(sc, &mut parsed.offset, v));
+                    }
+                    Nanosecond => {                         // the fraction is optional
+                        if sc.eat('.') {
+                            let v = try!(sc.nanosecond());
+                            try!(set(sc, &mut parsed.nanosecond, v));
+                        }
+                    }
+                    Nanosecond3 | Nanosecond6 | Nanosecond9 => {
+                        try!(sc.expect('.'));
+                        let v = try!(sc.nanosecond());
+                        try!(set(sc, &mut parsed.nanosecond, v));
+                    }
+                    Nanosecond3NoDot | Nanosecond6NoDot | Nanosecond9NoDot => {
+                        let ndigits = match spec {
+                            Nanosecond3NoDot => 3, Nanosecond6NoDot => 6, _ => 9,
+                        };
+                        let v = try!(sc.digits(ndigits, ndigits)) as u32;
+                        try!(set(sc, &mut parsed.nanosecond, v * Int::pow(10, 9 - ndigits)));
+                    }
+                }
+            }
+
+            Item::Error => return sc.error(ParseErrorKind::BadFormat),
+        }
+    }
+    Ok(())
+}
+
+/// Parses the string `s` with the formatting items and sets the parsed fields to `parsed`.
+/// The format string can be given as `format::StrftimeItems::new(fmt)`,
+/// which accepts the same specifiers as `format::DelayedFormat`.
+///
+/// Fails when the string does not match the items (including the trailing input),
+/// or when the fields are inconsistent with the already parsed fields.
+pub fn parse<'a, I>(parsed: &mut Parsed, s: &str, items: I) -> ParseResult<()>
+        where I: Iterator<Item=Item<'a>> {
+    parse_localized(parsed, s, items, &locale::EN_US)
+}
+
+/// Parses the string `s` as `parse` does, but reads the month and weekday names
+/// and AM/PM markers in given locale (full or abbreviated, ignoring the case).
+/// The items can be given as `format::StrftimeItems::new_with_locale(fmt, locale)`
+/// for the localized layouts of `%c`, `%x`, `%X` and `%r`.
+pub fn parse_localized<'a, I>(parsed: &mut Parsed, s: &str, items: I,
+                              locale: &Locale) -> ParseResult<()>
+        where I: Iterator<Item=Item<'a>> {
+    let mut sc = Scanner::new(s);
+    try!(parse_prefix(parsed, &mut sc, items, locale));
+    sc.expect_end()
+}
+
+/// Parses the string `s` with the formatting items and resolves the fields with `resolve`.
+/// The resolution errors are reported at the end of the input.
+pub fn parse_and_resolve<'a, T, I, F>(s: &str, items: I, resolve: F) -> ParseResult<T>
+        where I: Iterator<Item=Item<'a>>, F: Fn(&Parsed) -> ParseResult<T> {
+    let mut parsed = Parsed::new();
+    try!(parse(&mut parsed, s, items));
+    resolve(&parsed).map_err(|e| ParseError { pos: s.len(), ..e })
+}
 
 //
 //  Unit tests
@@ -280,8 +1837,21 @@ fn testrfc2822parser() {
         ["Tue, 20 Jan 2015 25:35:20 -0800",""], // bad hour
         ["Tue, 20 Jan 2015 17:65:20 -0800",""], // bad minute
         ["Tue, 20 Jan 2015 17:35:90 -0800",""], // bad second
-        ["Tue, 20 Jan 2015 17:35:20 -1800",""], // bad offset
-        ["Tue, 20 Jan 2015 17:35:20 HAS",""]    // bad named time zone
+        ["Tue, 20 Jan 2015 17:35:20 -2400",""], // bad offset
+        ["Tue, 20 Jan 2015 17:35:20 HAS",""],   // bad named time zone
+        ["Tue, 20 Jan 2015 17:35 -0800", "Tue, 20 Jan 2015 17:35:00 -0800"],  // no seconds
+        ["Tue, 20 Jan 2015 17:35:20 +1400", "Tue, 20 Jan 2015 17:35:20 +1400"],  // Kiribati
+        ["Tue, 20 Jan 2015 17:35:20 -0000", "Tue, 20 Jan 2015 17:35:20 +0000"],  // no zone information
+        ["Tue (Tuesday), 20 Jan 2015 17:35:20 -0800 (PST)", "Tue, 20 Jan 2015 17:35:20 -0800"],  // comments
+        ["Tue, 20 (a (nested) \\) comment) Jan 2015 17:35:20 -0800", "Tue, 20 Jan 2015 17:35:20 -0800"],
+        ["Tue,\r\n 20 Jan\r\n 2015 17 : 35 : 20\r\n -0800", "Tue, 20 Jan 2015 17:35:20 -0800"],  // folding
+        ["20 Jan 15 17:35:20 -0800", "Tue, 20 Jan 2015 17:35:20 -0800"],    // obs-year
+        ["20 Jan 99 17:35:20 -0800", "Wed, 20 Jan 1999 17:35:20 -0800"],
+        ["20 Jan 115 17:35:20 -0800", "Tue, 20 Jan 2015 17:35:20 -0800"],
+        ["Wed, 20 Jan 2015 17:35:20 -0800",""], // wrong day of week
+        ["Tue, 20 Jan 2015 17:35:20 -0800 (unterminated",""],   // bad comment
+        ["Tue, 20 Jan 5 17:35:20 -0800",""],    // one-digit year
+        ["Tue, 20Jan 2015 17:35:20 -0800",""],  // no space after day
         ];
     //  Test against test data above
     for testdate in testdates.iter() {
@@ -289,8 +1859,8 @@ fn testrfc2822parser() {
         let checkdate = testdate[1];            // expected result or ""
         let d = rfc2822_to_datetime(date);      // parse a date
         let dt = match d {                      // did we get a value?
-            Some(dt) => dt,                     // yes, go on
-            None => if checkdate != "" { panic!("Failed to convert date {}", date)} else { continue },
+            Ok(dt) => dt,                       // yes, go on
+            Err(_) => if checkdate != "" { panic!("Failed to convert date {}", date)} else { continue },
         };
         // let mut s = String::new();
         let s = fmt_rfc2822_datetime(dt);       // convert date/time back to string
@@ -298,24 +1868,42 @@ fn testrfc2822parser() {
             panic!("Date conversion failed for {}\nReceived: {}\nExpected: {}",date, s, checkdate);
         }
     };  
+
+    //  Unknown offset and leap second, kept in the naive date and time
+    let dt = NaiveDate::from_ymd(2015, 6, 30).and_hms(18, 0, 0);
+    assert_eq!(rfc2822_to_naive_datetime("30 Jun 2015 18:00:00 -0000"), Ok((dt, None)));
+    assert_eq!(rfc2822_to_naive_datetime("30 Jun 2015 18:00:00 A"), Ok((dt, None)));
+    assert_eq!(rfc2822_to_naive_datetime("30 Jun 2015 18:00:00 GMT"),
+               Ok((dt, Some(FixedOffset::east(0)))));
+    let dt = NaiveDate::from_ymd(2015, 6, 30).and_hms_milli(23, 59, 59, 1_000);
+    assert_eq!(rfc2822_to_naive_datetime("30 Jun 2015 23:59:60 +0000"),
+               Ok((dt, Some(FixedOffset::east(0)))));
 }
 #[test]
 /// Test RFC3339/ISO8601 parser.
 fn testrfc3339parser() {
     //  Test data - [input, expected result after parse and format]
     let testdates = [
-        ["2015-01-20T17:35:20-0800", "2015-01-20T17:35:20.000000000-0800"],   // normal case
-        ["1944-06-06T04:04:00Z", "1944-06-06T04:04:00.000000000+0000"],        // D-day
-        ["2001-09-11T09:45:00-0800", "2001-09-11T09:45:00.000000000-0800"],
-        ["2015-01-20T17:35:20.001-0800", "2015-01-20T17:35:20.001000000-0800"],   // milliseconds
-        ["2015-01-20T17:35:20.000031-0800", "2015-01-20T17:35:20.000031000-0800"],   // microseconds
-        ["2015-01-20T17:35:20.000000004-0800", "2015-01-20T17:35:20.000000004-0800"],   // nanoseconds
-        ["2015-01-20T17:35:20.000000000452-0800", "2015-01-20T17:35:20.000000000-0800"],   // picoseconds (too small)
+        ["2015-01-20T17:35:20-0800", "2015-01-20T17:35:20-08:00"],   // normal case
+        ["1944-06-06T04:04:00Z", "1944-06-06T04:04:00+00:00"],        // D-day
+        ["2001-09-11T09:45:00-0800", "2001-09-11T09:45:00-08:00"],
+        ["2015-01-20T17:35:20.001-0800", "2015-01-20T17:35:20.001-08:00"],   // milliseconds
+        ["2015-01-20T17:35:20.000031-0800", "2015-01-20T17:35:20.000031-08:00"],   // microseconds
+        ["2015-01-20T17:35:20.000000004-0800", "2015-01-20T17:35:20.000000004-08:00"],   // nanoseconds
+        ["2015-01-20T17:35:20.000000000452-0800", "2015-01-20T17:35:20-08:00"],   // picoseconds (too small)
         ["2015-02-30T17:35:20-0800", ""],                           // bad day of month
         ["2015-01-20T25:35:20-0800", ""],                           // bad hour
         ["2015-01-20T17:65:20-0800", ""],                           // bad minute
         ["2015-01-20T17:35:90-0800", ""],                           // bad second
-        ["2015-01-20T17:35:20-1800", ""],                           // bad offset
+        ["2015-01-20T17:35:20-2400", ""],                           // bad offset
+        ["2012-09-09T18:00:00-07:00", "2012-09-09T18:00:00-07:00"],   // colon in offset
+        ["2012-09-09t18:00:00z", "2012-09-09T18:00:00+00:00"],        // lowercase
+        ["2012-09-09 18:00:00+09:30", "2012-09-09T18:00:00+09:30"],   // space separator
+        ["2015-01-20T17:35:20-18:00", "2015-01-20T17:35:20-18:00"],   // large offset
+        ["2015-01-20T17:35:20-00:00", "2015-01-20T17:35:20+00:00"],   // unknown offset
+        ["2015-01-20T17:35:20 -08:00", ""],                         // space before offset
+        ["2015-01-20T17:35:20 PST", ""],                            // named zones not allowed
+        ["2015-01-20T17:35:20", ""],                                // offset required
         ];
     //  Test against test data above
     for testdate in testdates.iter() {
@@ -323,8 +1911,8 @@ fn testrfc3339parser() {
         let checkdate = testdate[1];            // expected result or ""
         let d = rfc3339_to_datetime(date);      // parse a date
         let dt = match d {                      // did we get a value?
-            Some(dt) => dt,                     // yes, go on
-            None => if checkdate != "" { panic!("Failed to convert date {}", date)} else { continue },
+            Ok(dt) => dt,                       // yes, go on
+            Err(_) => if checkdate != "" { panic!("Failed to convert date {}", date)} else { continue },
         };
         // let mut s = String::new();
         let s = fmt_rfc3339_datetime(dt);       // convert date/time back to string
@@ -332,4 +1920,401 @@ fn testrfc3339parser() {
             panic!("Date conversion failed for {}\nReceived: {}\nExpected: {}",date, s, checkdate);
         }
     };  
+
+    //  Unknown local offset and leap second, kept in the naive date and time
+    let dt = NaiveDate::from_ymd(2015, 6, 30).and_hms(18, 0, 0);
+    assert_eq!(rfc3339_to_naive_datetime("2015-06-30T18:00:00-00:00"), Ok((dt, None)));
+    assert_eq!(rfc3339_to_naive_datetime("2015-06-30T18:00:00+00:00"),
+               Ok((dt, Some(FixedOffset::east(0)))));
+    let dt = NaiveDate::from_ymd(2015, 6, 30).and_hms_milli(23, 59, 59, 1_500);
+    assert_eq!(rfc3339_to_naive_datetime("2015-06-30T23:59:60.5Z"),
+               Ok((dt, Some(FixedOffset::east(0)))));
+}
+#[test]
+/// Test HTTP date parser.
+fn testhttpdateparser() {
+    //  Test data - [input, expected result after parse and format]
+    let testdates = [
+        ["Sun, 06 Nov 1994 08:49:37 GMT", "Sun, 06 Nov 1994 08:49:37 GMT"],    // IMF-fixdate
+        ["Sunday, 06-Nov-94 08:49:37 GMT", "Sun, 06 Nov 1994 08:49:37 GMT"],   // RFC 850
+        ["Sun Nov  6 08:49:37 1994", "Sun, 06 Nov 1994 08:49:37 GMT"],         // asctime
+        ["Tue Jan 20 17:35:20 2015", "Tue, 20 Jan 2015 17:35:20 GMT"],
+        ["Tuesday, 20-Jan-15 17:35:20 GMT", "Tue, 20 Jan 2015 17:35:20 GMT"],
+        ["Sun, 06 Nov 1994 08:49:37 UTC", ""],  // only GMT
+        ["Sun, 6 Nov 1994 08:49:37 GMT", ""],   // two-digit day required
+        ["Sun,  06 Nov 1994 08:49:37 GMT", ""], // no extra whitespace
+        ["Sun, 06  Nov 1994 08:49:37 GMT", ""],
+        [" Sun, 06 Nov 1994 08:49:37 GMT", ""],
+        ["Sun, 06 Nov 1994 08:49:37 +0000", ""],
+        ["Sun, 06-Nov-94 08:49:37 GMT", ""],    // RFC 850 needs the long day name
+        ["Sunday, 06 Nov 1994 08:49:37 GMT", ""],
+        ["Sun Nov 6 08:49:37 1994", ""],        // asctime pads the day with a space
+        ["Mon, 06 Nov 1994 08:49:37 GMT", ""],  // wrong day of week
+        ["Sun, 31 Nov 1994 08:49:37 GMT", ""],  // bad day of month
+        ["Sun, 06 Nov 1994 08:49:37 GMT ", ""], // trailing whitespace
+        ];
+    //  Test against test data above
+    for testdate in testdates.iter() {
+        let date = testdate[0];                 // input
+        let checkdate = testdate[1];            // expected result or ""
+        let dt = match http_date(date, 2015) {  // parse a date as in 2015
+            Ok(dt) => dt,                       // yes, go on
+            Err(_) => if checkdate != "" { panic!("Failed to convert date {}", date)} else { continue },
+        };
+        let s = fmt_http_date(dt);              // convert date/time back to string
+        if s != checkdate {                     // check for expected result
+            panic!("Date conversion failed for {}\nReceived: {}\nExpected: {}",date, s, checkdate);
+        }
+    };
+
+    //  Two-digit years more than 50 years in the future are in the past
+    let roundtrip = |&: dt: ParseResult<DateTime<UTC>>| dt.map(|dt| fmt_http_date(dt));
+    assert_eq!(roundtrip(http_date("Thursday, 01-Jan-65 00:00:00 GMT", 2015)),
+               Ok("Thu, 01 Jan 2065 00:00:00 GMT".to_string()));
+    assert_eq!(roundtrip(http_date("Saturday, 01-Jan-66 00:00:00 GMT", 2015)),
+               Ok("Sat, 01 Jan 1966 00:00:00 GMT".to_string()));
+    assert_eq!(roundtrip(http_date("Wednesday, 01-Jan-70 00:00:00 GMT", 2099)),
+               Ok("Wed, 01 Jan 2070 00:00:00 GMT".to_string()));
+}
+
+#[test]
+/// Test ISO 8601 parser.
+fn testiso8601parser() {
+    //  Test data - [input, expected result in Debug format or ""]
+    let testdates = [
+        ["2015-01-20", "2015-01-20"],                           // calendar date
+        ["20150120", "2015-01-20"],                             // basic format
+        ["2015-020", "2015-01-20"],                             // ordinal date
+        ["2015020", "2015-01-20"],
+        ["2015-W04-2", "2015-01-20"],                           // week date
+        ["2015W042", "2015-01-20"],
+        ["2009-W53-7", "2010-01-03"],                           // week 53
+        ["+012015-01-20", "+12015-01-20"],                      // expanded year
+        ["-0001-12-31", "-0001-12-31"],                         // 2 BCE
+        ["+0020150120", "2015-01-20"],                          // expanded year, basic format
+        ["2015-02-29", ""],                                     // not a leap year
+        ["2015-366", ""],                                       // same
+        ["2015-W54-1", ""],                                     // bad week
+        ["2015-W04-8", ""],                                     // bad day of week
+        ["2015-01", ""],                                        // not a date
+        ["201501-20", ""],                                      // mixed formats
+        ["2015-01-20T", ""],                                    // trailing garbage
+        ];
+    for testdate in testdates.iter() {
+        let (date, checkdate) = (testdate[0], testdate[1]);
+        match iso8601_to_naive_date(date) {
+            Ok(d) => assert_eq!(format!("{:?}", d), checkdate),
+            Err(_) => if checkdate != "" { panic!("Failed to convert date {}", date) },
+        }
+    }
+
+    let testdatetimes = [
+        ["2015-01-20T17:35:20", "2015-01-20T17:35:20"],
+        ["20150120T173520", "2015-01-20T17:35:20"],             // basic format
+        ["2015-01-20T17:35", "2015-01-20T17:35:00"],            // reduced precision
+        ["2015-01-20T17", "2015-01-20T17:00:00"],
+        ["2015-01-20T1735", "2015-01-20T17:35:00"],
+        ["2015-01-20T17:35:20,5", "2015-01-20T17:35:20.500"],   // decimal comma
+        ["2015-01-20T17:35:20.000031", "2015-01-20T17:35:20.000031"],
+        ["2015-01-20T17:35.25", "2015-01-20T17:35:15"],         // fraction of minute
+        ["2015-01-20T17.5", "2015-01-20T17:30:00"],             // fraction of hour
+        ["2015-01-20T24:00", "2015-01-21T00:00:00"],            // end of day
+        ["2015-06-30T23:59:60", "2015-06-30T23:59:60"],         // leap second
+        ["2015-01-20T24:00:01", ""],                            // past end of day
+        ["2015-01-20T17:3520", ""],                             // mixed formats
+        ["2015-01-20T1735:20", ""],
+        ["2015-01-20T17:35:20Z", ""],                           // offset not allowed
+        ["2015-01-20 17:35:20", ""],                            // T required
+        ];
+    for testdatetime in testdatetimes.iter() {
+        let (datetime, checkdatetime) = (testdatetime[0], testdatetime[1]);
+        match iso8601_to_naive_datetime(datetime) {
+            Ok(dt) => assert_eq!(format!("{:?}", dt), checkdatetime),
+            Err(_) => if checkdatetime != "" { panic!("Failed to convert date {}", datetime) },
+        }
+    }
+
+    let testdatetimes = [
+        ["2015-01-20T17:35:20-08:00", "2015-01-20T17:35:20-08:00"],
+        ["20150120T173520Z", "2015-01-20T17:35:20+00:00"],      // basic format
+        ["2015-W04-2T17:35:20+0530", "2015-01-20T17:35:20+05:30"],
+        ["2015-020T17:35+09", "2015-01-20T17:35:00+09:00"],     // hour-only offset
+        ["2015-01-20T17:35:20", ""],                            // offset required
+        ["2015-01-20T17:35:20+24:00", ""],                      // bad offset
+        ["2015-01-20T17:35:20+08:0", ""],
+        ];
+    for testdatetime in testdatetimes.iter() {
+        let (datetime, checkdatetime) = (testdatetime[0], testdatetime[1]);
+        match iso8601_to_datetime(datetime) {
+            Ok(dt) => assert_eq!(format!("{:?}", dt), checkdatetime),
+            Err(_) => if checkdatetime != "" { panic!("Failed to convert date {}", datetime) },
+        }
+    }
+}
+
+#[test]
+/// Test ISO 8601 duration parser.
+fn testiso8601durationparser() {
+    use format::fmt_iso8601_duration;
+
+    //  Test data - [input, expected result in fmt_iso8601_duration or ""]
+    let testdurations = [
+        ["P3DT4H5M6.5S", "P3DT4H5M6.5S"],
+        ["PT0S", "PT0S"],
+        ["P0D", "PT0S"],
+        ["P2W", "P14D"],                                        // week form
+        ["PT36H", "P1DT12H"],                                   // not normalized
+        ["PT1.5H", "PT1H30M"],                                  // fraction of hour
+        ["PT0,001S", "PT0.001S"],                               // decimal comma
+        ["P1DT0.000000001S", "P1DT0.000000001S"],
+        ["-PT30M", "-PT30M"],                                   // negative
+        ["+P1D", "P1D"],
+        ["p1dt2h", "P1DT2H"],                                   // lower case
+        ["P1Y", ""],                                            // years not allowed
+        ["P1M", ""],                                            // nor months
+        ["P2W1D", ""],                                          // weeks alone
+        ["P2WT1H", ""],
+        ["PT1H1D", ""],                                         // out of order
+        ["PT1M1H", ""],
+        ["PT1.5H1M", ""],                                       // fraction not last
+        ["P1D1D", ""],                                          // duplicate
+        ["P", ""],                                              // no component
+        ["PT", ""],
+        ["P1DT", ""],
+        ["PT-1S", ""],                                          // signed component
+        ["1D", ""],                                             // P required
+        ["P999999999999999999W", ""],                           // overflow
+        ["PT9223372036854776S", ""],                            // out of Duration range
+        ];
+    for testduration in testdurations.iter() {
+        let (duration, checkduration) = (testduration[0], testduration[1]);
+        match iso8601_to_duration(duration) {
+            Ok(d) => assert_eq!(fmt_iso8601_duration(d), checkduration),
+            Err(_) => if checkduration != "" { panic!("Failed to convert {}", duration) },
+        }
+    }
+
+    assert_eq!(iso8601_to_duration("PT9223372036854775S"), Ok(Duration::seconds(9223372036854775)));
+    assert_eq!(iso8601_to_duration("P1Y").unwrap_err().pos, 2);
+}
+
+#[test]
+/// Test RFC 5545 recurrence rule parser.
+fn testrrulefromstr() {
+    //  Test data - [input, expected result in Display or ""]
+    let testrules = [
+        ["FREQ=MONTHLY;BYDAY=2TU;COUNT=10", "FREQ=MONTHLY;COUNT=10;BYDAY=2TU"],
+        ["RRULE:FREQ=DAILY", "FREQ=DAILY"],                     // property name
+        ["rrule:freq=weekly;byday=mo,we;wkst=su", "FREQ=WEEKLY;BYDAY=MO,WE;WKST=SU"],
+        ["FREQ=YEARLY;INTERVAL=1", "FREQ=YEARLY"],
+        ["FREQ=WEEKLY;UNTIL=19971224T000000Z;INTERVAL=2;BYDAY=+1MO,-1FR",
+         "FREQ=WEEKLY;UNTIL=19971224T000000Z;INTERVAL=2;BYDAY=1MO,-1FR"],
+        ["FREQ=DAILY;UNTIL=19971224", "FREQ=DAILY;UNTIL=19971224"],  // whole day
+        ["FREQ=DAILY;UNTIL=19971224T235960", "FREQ=DAILY;UNTIL=19971224T235960"],
+        ["FREQ=MONTHLY;BYMONTHDAY=1,-1;BYMONTH=1,12", "FREQ=MONTHLY;BYMONTHDAY=1,-1;BYMONTH=1,12"],
+        ["FREQ=YEARLY;BYYEARDAY=-366;BYWEEKNO=+53", "FREQ=YEARLY;BYYEARDAY=-366;BYWEEKNO=53"],
+        ["FREQ=HOURLY;BYHOUR=0,23;BYMINUTE=59;BYSECOND=60",
+         "FREQ=HOURLY;BYSECOND=60;BYMINUTE=59;BYHOUR=0,23"],
+        ["FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+         "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"],
+        ["COUNT=3", ""],                                        // no FREQ
+        ["FREQ=DAILY;FREQ=DAILY", ""],                          // duplicate
+        ["FREQ=DAILY;COUNT=3;UNTIL=19971224", ""],              // both COUNT and UNTIL
+        ["FREQ=FORTNIGHTLY", ""],
+        ["FREQ=DAILY;BYWHATEVER=1", ""],                        // unknown part
+        ["FREQ=DAILY;", ""],
+        ["FREQ=DAILY; COUNT=3", ""],
+        ["FREQ=DAILY;INTERVAL=0", ""],
+        ["FREQ=DAILY;COUNT=0", ""],
+        ["FREQ=MONTHLY;BYMONTHDAY=0", ""],
+        ["FREQ=MONTHLY;BYMONTHDAY=32", ""],
+        ["FREQ=YEARLY;BYMONTH=-1", ""],                         // unsigned
+        ["FREQ=DAILY;BYHOUR=24", ""],
+        ["FREQ=YEARLY;BYDAY=54MO", ""],
+        ["FREQ=YEARLY;BYDAY=+MO", ""],
+        ["FREQ=YEARLY;BYDAY=MO,", ""],
+        ["FREQ=DAILY;UNTIL=19970230", ""],                      // invalid date
+        ["FREQ=DAILY;UNTIL=19971224T24", ""],
+        ];
+    for testrule in testrules.iter() {
+        let (rule, checkrule) = (testrule[0], testrule[1]);
+        match rule.parse::<RRule>() {
+            Ok(r) => {
+                assert_eq!(r.to_string(), checkrule);
+                assert_eq!(checkrule.parse::<RRule>(), Ok(r));    // round trip
+            }
+            Err(_) => if checkrule != "" { panic!("Failed to convert {}", rule) },
+        }
+    }
+
+    let err = "FREQ=DAILY;COUNT=1;COUNT=2".parse::<RRule>().unwrap_err();
+    assert_eq!((err.kind, err.pos), (ParseErrorKind::Impossible, 19));
+    let err = "FREQ=DAILY;BYMONTHDAY=1,40".parse::<RRule>().unwrap_err();
+    assert_eq!((err.kind, err.pos), (ParseErrorKind::OutOfRange, 24));
+    assert_eq!("INTERVAL=2".parse::<RRule>().unwrap_err().kind, ParseErrorKind::NotEnough);
+}
+
+#[test]
+/// Test `FromStr` against the `Debug` output.
+fn testfromstr() {
+    fn roundtrip<T: FromStr<Err=ParseError> + fmt::Debug + PartialEq>(x: T) {
+        let s = format!("{:?}", x);
+        assert_eq!(s.parse::<T>(), Ok(x));
+    }
+    roundtrip(NaiveDate::from_ymd(2015, 1, 20));
+    roundtrip(NaiveDate::from_ymd(-1, 12, 31));                 // "-0001-12-31"
+    roundtrip(NaiveDate::from_ymd(12015, 1, 20));               // "+12015-01-20"
+    roundtrip(NaiveTime::from_hms(17, 35, 20));
+    roundtrip(NaiveTime::from_hms_milli(17, 35, 20, 500));      // milliseconds
+    roundtrip(NaiveTime::from_hms_micro(17, 35, 20, 31));       // microseconds
+    roundtrip(NaiveTime::from_hms_nano(17, 35, 20, 4));         // nanoseconds
+    roundtrip(NaiveTime::from_hms_milli(23, 59, 59, 1_500));    // leap second
+    roundtrip(NaiveDate::from_ymd(2015, 1, 20).and_hms_nano(17, 35, 20, 123456789));
+    roundtrip(FixedOffset::east(0));
+    roundtrip(FixedOffset::west(8 * 3600));
+    roundtrip(FixedOffset::east(9 * 3600 + 30 * 60 + 15));     // "+09:30:15"
+    roundtrip(FixedOffset::west(8 * 3600).ymd(2015, 1, 20).and_hms(17, 35, 20));
+    roundtrip(UTC.ymd(2015, 1, 20).and_hms_milli(17, 35, 20, 1));
+    for i in range(0u32, 7) {
+        roundtrip(num::from_u32::<Weekday>(i).unwrap());
+    }
+
+    assert_eq!("monday".parse::<Weekday>(), Ok(Weekday::Mon));
+    assert!("2015-1-20".parse::<NaiveDate>().is_err());         // two-digit month required
+    assert!("2015-02-29".parse::<NaiveDate>().is_err());        // not a leap year
+    assert!("12015-01-20".parse::<NaiveDate>().is_err());       // sign required
+    assert!("17:35".parse::<NaiveTime>().is_err());             // second required
+    assert!("2015-01-20 17:35:20".parse::<NaiveDateTime>().is_err());   // T required
+    assert!("2015-01-20T17:35:20".parse::<DateTime<FixedOffset>>().is_err());   // no offset
+    assert!("2015-01-20T17:35:20+00:00".parse::<DateTime<UTC>>().is_err());     // Z required
+    assert!("+0900".parse::<FixedOffset>().is_err());           // colon required
+    assert!(" Mon".parse::<Weekday>().is_err());
+    assert_eq!("2015-01-20 ".parse::<NaiveDate>(),
+               Err(ParseError { kind: ParseErrorKind::TooLong, pos: 10 }));
+
+    //  ISO 8601 periods
+    roundtrip(Period::new(1, 2, 3));
+    roundtrip(Period::new(-1, -2, 0));                          // "-P1Y2M"
+    roundtrip(Period::new(1, -2, 0));                           // "P1Y-2M"
+    roundtrip(Period::zero());                                  // "P0D"
+    assert_eq!("P2W".parse::<Period>(), Ok(Period::days(14)));
+    assert_eq!("p1y3d".parse::<Period>(), Ok(Period::new(1, 0, 3)));
+    assert_eq!("-P1M-1D".parse::<Period>(), Ok(Period::new(0, -1, 1)));
+    assert_eq!("+P1W2D".parse::<Period>(), Ok(Period::days(9)));
+    assert!("P".parse::<Period>().is_err());                    // a unit required
+    assert!("P1D2M".parse::<Period>().is_err());                // out of order
+    assert!("P1M1M".parse::<Period>().is_err());                // repeated
+    assert!("P1Y2".parse::<Period>().is_err());                 // unit required
+    assert!("PT1H".parse::<Period>().is_err());                 // no time components
+    assert!("P999999999W".parse::<Period>().is_err());          // too many days
+    assert_eq!("P-306783378W-2D".parse::<Period>(), Ok(Period::days(-2147483648)));
+    assert!("-P-306783378W-2D".parse::<Period>().is_err());     // cannot be negated
+}
+
+#[test]
+/// Test the kind and position of parse errors.
+fn testparseerrors() {
+    let err = |&: kind, pos| Err(ParseError { kind: kind, pos: pos });
+    assert_eq!(rfc2822_to_datetime("Tue, 20 Avr 2015 17:35:20 -0800"),
+               err(ParseErrorKind::Invalid, 8));                    // bad month name
+    assert_eq!(rfc2822_to_datetime("Tue, 20 Jan 2015 17:65:20 -0800"),
+               err(ParseErrorKind::OutOfRange, 20));                // bad minute
+    assert_eq!(rfc2822_to_datetime("Tue, 30 Feb 2015 17:35:20 -0800"),
+               err(ParseErrorKind::Impossible, 5));                 // bad day of month
+    assert_eq!(rfc2822_to_datetime("Tue, 20 Jan 2015 17:35:20 HAS"),
+               err(ParseErrorKind::Invalid, 26));                   // bad named time zone
+    assert_eq!(rfc2822_to_datetime("Tue, 20 Jan 2015 17:35"),
+               err(ParseErrorKind::TooShort, 22));                  // no zone
+    assert_eq!(rfc2822_to_datetime("Wed, 20 Jan 2015 17:35:20 -0800"),
+               err(ParseErrorKind::Impossible, 0));                 // wrong day of week
+    assert_eq!(http_date_to_datetime("Mon, 06 Nov 1994 08:49:37 GMT"),
+               err(ParseErrorKind::Impossible, 0));                 // wrong day of week
+    assert_eq!(http_date_to_datetime("Sun, 31 Nov 1994 08:49:37 GMT"),
+               err(ParseErrorKind::Impossible, 5));                 // bad day of month
+    assert_eq!(rfc3339_to_datetime("2015-01-20T17:35:20-0800 x"),
+               err(ParseErrorKind::TooLong, 25));                   // trailing input
+    assert_eq!(rfc3339_to_datetime("2015-01-20T17:35:20-2400"),
+               err(ParseErrorKind::OutOfRange, 20));                // bad offset
+
+    let mut parsed = Parsed::new();
+    assert_eq!(parse(&mut parsed, "2015-01-20", StrftimeItems::new("%Y-%m-%d %")),
+               err(ParseErrorKind::BadFormat, 10));                 // stray `%`
+    assert_eq!(parse_and_resolve("2015-02-30", StrftimeItems::new("%F"),
+                                 |&: p: &Parsed| p.to_naive_date()),
+               err(ParseErrorKind::Impossible, 10));                // resolution error
+    assert_eq!(parse_and_resolve("2015-01-20", StrftimeItems::new("%F"),
+                                 |&: p: &Parsed| p.to_naive_datetime()),
+               err(ParseErrorKind::NotEnough, 10));                 // no time
+    assert_eq!(format!("{}", ParseError { kind: ParseErrorKind::TooLong, pos: 3 }),
+               "trailing input at position 3");
+}
+
+#[test]
+/// Test format-string parsing into `Parsed`.
+fn testparsed() {
+    let mut parsed = Parsed::new();
+    assert_eq!(parse(&mut parsed, "2015-01-20 17:35", StrftimeItems::new("%Y-%m-%d %H:%M")),
+               Ok(()));
+    assert_eq!(parsed.year, Some(2015));
+    assert_eq!((parsed.hour_div_12, parsed.hour_mod_12), (Some(1), Some(5)));
+    assert_eq!(parsed.second, None);
+    assert_eq!(parse(&mut parsed, "Tue 05 PM", StrftimeItems::new("%a %I %p")),
+               Ok(()));                                                 // consistent fields
+    assert_eq!(parse(&mut parsed, "AM", StrftimeItems::new("%p")),     // conflicting field
+               Err(ParseError { kind: ParseErrorKind::Impossible, pos: 0 }));
+    assert_eq!(parsed.to_naive_datetime(),
+               Ok(NaiveDate::from_ymd(2015, 1, 20).and_hms(17, 35, 0)));
+    assert_eq!(parsed.to_datetime(),                                    // no offset
+               Err(ParseError { kind: ParseErrorKind::NotEnough, pos: 0 }));
+
+    //  The same compiled items for formatting and parsing
+    let items: Vec<Item> = StrftimeItems::new("%a, %d %b %Y %T").collect();
+    let dt = NaiveDate::from_ymd(2015, 1, 20).and_hms(17, 35, 20);
+    let s = dt.format_with_items(items.iter().cloned()).to_string();
+    assert_eq!(s, "Tue, 20 Jan 2015 17:35:20");
+    let mut parsed = Parsed::new();
+    assert_eq!(parse(&mut parsed, s.as_slice(), items.iter().cloned()), Ok(()));
+    assert_eq!(parsed.to_naive_datetime(), Ok(dt));
+
+    //  The timestamp, shifted by the offset and checked against the other fields
+    let mut parsed = Parsed::new();
+    assert_eq!(parse(&mut parsed, "1421804120 -0800", StrftimeItems::new("%s %z")), Ok(()));
+    assert_eq!(parsed.to_naive_datetime(), Ok(dt.clone()));
+    assert_eq!(parse(&mut parsed, "Tue 17", StrftimeItems::new("%a %H")), Ok(()));
+    assert_eq!(parsed.to_naive_datetime(), Ok(dt));
+    let mut parsed = Parsed::new();
+    assert_eq!(parse(&mut parsed, "-1 Thu", StrftimeItems::new("%s %a")), Ok(()));
+    assert_eq!(parsed.to_naive_datetime(),
+               Err(ParseError { kind: ParseErrorKind::Impossible, pos: 0 }));
+}
+#[test]
+/// Test parsing with the localized names.
+fn testlocalizedparser() {
+    let dt = FixedOffset::east(3600).ymd(2015, 1, 20).and_hms(17, 35, 20);
+    let de = |&: s| rfc2822_to_datetime_localized(s, &locale::DE_DE);
+    let fr = |&: s| rfc2822_to_datetime_localized(s, &locale::FR_FR);
+    assert_eq!(de("Di, 20 Jan 2015 17:35:20 +0100"), Ok(dt.clone()));
+    assert_eq!(de("dienstag, 20 JANUAR 2015 17:35:20 +0100"), Ok(dt.clone()));
+    assert_eq!(fr("20 janv. 2015 17:35:20 +0100"), Ok(dt.clone()));    // abbreviated
+    assert_eq!(fr("20 Janv 2015 17:35:20 +0100"), Ok(dt.clone()));     // without the dot
+    assert_eq!(fr("mardi, 20 janvier 2015 17:35:20 +0100"), Ok(dt.clone()));
+    assert_eq!(de("Mo, 20 Jan 2015 17:35:20 +0100"),                    // wrong weekday
+               Err(ParseError { kind: ParseErrorKind::Impossible, pos: 0 }));
+    assert_eq!(fr("20 Jan 2015 17:35:20 +0100"),                        // not French
+               Err(ParseError { kind: ParseErrorKind::Invalid, pos: 3 }));
+    assert!(rfc2822_to_datetime("Di, 20 Jan 2015 17:35:20 +0100").is_err());
+
+    //  Format strings, including the localized layouts
+    let mut parsed = Parsed::new();
+    assert_eq!(parse_localized(&mut parsed, "Sonntag, 4. MÄRZ 2012",
+                               StrftimeItems::new("%A, %e. %B %Y"), &locale::DE_DE), Ok(()));
+    assert_eq!(parsed.to_naive_date(), Ok(NaiveDate::from_ymd(2012, 3, 4)));
+    let mut parsed = Parsed::new();
+    assert_eq!(parse_localized(&mut parsed, "04.03.2012 dim.",
+                               StrftimeItems::new("%d.%m.%Y %a"), &locale::FR_FR), Ok(()));
+    assert_eq!(parsed.to_naive_date(), Ok(NaiveDate::from_ymd(2012, 3, 4)));
+    let mut parsed = Parsed::new();
+    let items = StrftimeItems::new_with_locale("%r", &locale::KO_KR);
+    assert_eq!(parse_localized(&mut parsed, "오후 03시 05분 07초", items, &locale::KO_KR), Ok(()));
+    assert_eq!(parsed.to_naive_time(), Ok(NaiveTime::from_hms(15, 5, 7)));
 }
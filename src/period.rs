@@ -0,0 +1,230 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2014-2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * ISO 8601 calendar period, in years, months and days.
+ *
+ * Unlike `Duration`, the actual length of a period depends on the date it applies to:
+ * `P1M` from January 31 ends on February 28 or 29 (the day is clamped as `Months` does).
+ * A period is written as `PnYnMnD` (e.g. `P1Y2M3D`) and parsed via `FromStr`.
+ */
+
+use std::fmt;
+use std::num::Int;
+use std::ops::{Add, Sub, Neg};
+
+/// ISO 8601 calendar period, in years, months and days.
+/// Each component can be negative; they are not normalized unless requested.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Period {
+    years: i32,
+    months: i32,
+    days: i32,
+}
+
+impl Period {
+    /// Makes a new `Period` from the years, months and days.
+    #[inline]
+    pub fn new(years: i32, months: i32, days: i32) -> Period {
+        Period { years: years, months: months, days: days }
+    }
+
+    /// Makes a new `Period` with given number of years.
+    #[inline]
+    pub fn years(years: i32) -> Period {
+        Period::new(years, 0, 0)
+    }
+
+    /// Makes a new `Period` with given number of months.
+    #[inline]
+    pub fn months(months: i32) -> Period {
+        Period::new(0, months, 0)
+    }
+
+    /// Makes a new `Period` with given number of days.
+    #[inline]
+    pub fn days(days: i32) -> Period {
+        Period::new(0, 0, days)
+    }
+
+    /// Makes a new zero-length `Period`.
+    #[inline]
+    pub fn zero() -> Period {
+        Period::new(0, 0, 0)
+    }
+
+    /// Returns the number of years.
+    #[inline]
+    pub fn num_years(&self) -> i32 {
+        self.years
+    }
+
+    /// Returns the number of months, excluding the years.
+    #[inline]
+    pub fn num_months(&self) -> i32 {
+        self.months
+    }
+
+    /// Returns the number of days.
+    #[inline]
+    pub fn num_days(&self) -> i32 {
+        self.days
+    }
+
+    /// Returns the total number of months, counting a year as 12 months.
+    #[inline]
+    pub fn num_total_months(&self) -> i64 {
+        self.years as i64 * 12 + self.months as i64
+    }
+
+    /// Returns true if every component is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.years == 0 && self.months == 0 && self.days == 0
+    }
+
+    /// Normalizes the years and months so that the months are from -11 to 11
+    /// and have the same sign as the years. The days are kept as they are,
+    /// as the number of days in a month is not fixed.
+    ///
+    /// Fails when the years overflow.
+    pub fn normalized(&self) -> Period {
+        let total = self.num_total_months();
+        let years = total / 12;
+        assert!(years as i32 as i64 == years, "`Period::normalized` overflowed");
+        Period::new(years as i32, (total % 12) as i32, self.days)
+    }
+
+    /// Adds two periods component-wise, or returns `None` when any component overflows.
+    pub fn checked_add(&self, rhs: &Period) -> Option<Period> {
+        match (self.years.checked_add(rhs.years), self.months.checked_add(rhs.months),
+               self.days.checked_add(rhs.days)) {
+            (Some(years), Some(months), Some(days)) => Some(Period::new(years, months, days)),
+            _ => None,
+        }
+    }
+
+    /// Subtracts two periods component-wise, or returns `None` when any component overflows.
+    pub fn checked_sub(&self, rhs: &Period) -> Option<Period> {
+        match (self.years.checked_sub(rhs.years), self.months.checked_sub(rhs.months),
+               self.days.checked_sub(rhs.days)) {
+            (Some(years), Some(months), Some(days)) => Some(Period::new(years, months, days)),
+            _ => None,
+        }
+    }
+}
+
+/// Negates every component.
+///
+/// Fails when any component is `i32::MIN`, which cannot be negated.
+impl Neg for Period {
+    type Output = Period;
+
+    fn neg(self) -> Period {
+        let neg = |&: v: i32| 0.checked_sub(v).expect("`Period` negation overflowed");
+        Period::new(neg(self.years), neg(self.months), neg(self.days))
+    }
+}
+
+/// Adds every component.
+///
+/// Fails when any component overflows. See `Period::checked_add` for the non-failing version.
+impl Add<Period> for Period {
+    type Output = Period;
+
+    #[inline]
+    fn add(self, rhs: Period) -> Period {
+        self.checked_add(&rhs).expect("`Period` addition overflowed")
+    }
+}
+
+/// Subtracts every component.
+///
+/// Fails when any component overflows. See `Period::checked_sub` for the non-failing version.
+impl Sub<Period> for Period {
+    type Output = Period;
+
+    #[inline]
+    fn sub(self, rhs: Period) -> Period {
+        self.checked_sub(&rhs).expect("`Period` subtraction overflowed")
+    }
+}
+
+/// The ISO 8601 representation, e.g. `P1Y2M3D`. The zero components are omitted (`P0D` if all).
+/// A period with no positive component is written with the leading `-` (`-P1Y2M`),
+/// while the mixed signs are written per component (`P1Y-2M`).
+impl fmt::Debug for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() { return write!(f, "P0D"); }
+
+        let negative = self.years <= 0 && self.months <= 0 && self.days <= 0;
+        // widened, as the negated `i32::MIN` does not fit
+        let sign = if negative {-1i64} else {1};
+        try!(write!(f, "{}P", if negative {"-"} else {""}));
+        if self.years != 0 { try!(write!(f, "{}Y", sign * self.years as i64)); }
+        if self.months != 0 { try!(write!(f, "{}M", sign * self.months as i64)); }
+        if self.days != 0 { try!(write!(f, "{}D", sign * self.days as i64)); }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Period;
+    use std::i32;
+
+    #[test]
+    fn test_period_fmt() {
+        assert_eq!(Period::new(1, 2, 3).to_string(), "P1Y2M3D");
+        assert_eq!(Period::new(0, 14, 0).to_string(), "P14M");
+        assert_eq!(Period::zero().to_string(), "P0D");
+        assert_eq!(Period::new(-1, -2, 0).to_string(), "-P1Y2M");
+        assert_eq!(Period::new(1, -2, 0).to_string(), "P1Y-2M");
+        assert_eq!(format!("{:?}", Period::days(-30)), "-P30D");
+        assert_eq!(Period::new(i32::MIN, 0, -1).to_string(), "-P2147483648Y1D");
+    }
+
+    #[test]
+    fn test_period_arithmetic() {
+        assert_eq!(Period::years(1) + Period::months(2) + Period::days(3), Period::new(1, 2, 3));
+        assert_eq!(Period::new(1, 2, 3) - Period::new(1, 3, 3), Period::months(-1));
+        assert_eq!(-Period::new(1, -2, 3), Period::new(-1, 2, -3));
+        assert_eq!(Period::new(1, 14, 40).normalized(), Period::new(2, 2, 40));
+        assert_eq!(Period::new(1, -2, 0).normalized(), Period::new(0, 10, 0));
+        assert_eq!(Period::new(-1, -14, 0).normalized(), Period::new(-2, -2, 0));
+        assert_eq!(Period::new(1, -14, 0).normalized(), Period::new(0, -2, 0));
+        assert_eq!(Period::new(1, 2, 3).num_total_months(), 14);
+        assert_eq!(-Period::new(i32::MAX, 0, -i32::MAX), Period::new(-i32::MAX, 0, i32::MAX));
+        assert_eq!(Period::days(i32::MAX).checked_add(&Period::days(-1)),
+                   Some(Period::days(i32::MAX - 1)));
+        assert_eq!(Period::days(i32::MAX).checked_add(&Period::days(1)), None);
+        assert_eq!(Period::months(i32::MIN).checked_sub(&Period::months(1)), None);
+        assert_eq!(Period::years(-1).checked_sub(&Period::years(i32::MAX)),
+                   Some(Period::years(i32::MIN)));
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_period_add_overflow() {
+        let _ = Period::years(i32::MAX) + Period::years(1);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_period_sub_overflow() {
+        let _ = Period::days(i32::MIN) - Period::days(1);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_period_neg_overflow() {
+        let _ = -Period::days(i32::MIN);
+    }
+}
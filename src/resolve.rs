@@ -0,0 +1,105 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+//! Resolution of the local date and time which does not map to a single instant.
+
+use std::num::ToPrimitive;
+
+use {Datelike, Timelike};
+use div::div_mod_floor;
+use offset::{Offset, LocalResult};
+use naive::date::NaiveDate;
+use naive::time::NaiveTime;
+use naive::datetime::NaiveDateTime;
+use date::Date;
+use datetime::DateTime;
+
+macro_rules! try_opt {
+    ($e:expr) => (match $e { Some(v) => v, None => return None })
+}
+
+/// Adds given number of days to `date`, or returns `None` when out of range.
+pub fn add_days(date: &NaiveDate, days: i64) -> Option<NaiveDate> {
+    let days = try_opt!((date.num_days_from_ce() as i64 + days).to_i32());
+    NaiveDate::from_num_days_from_ce_opt(days)
+}
+
+/// Adds given number of seconds to `dt`, or returns `None` when out of range.
+pub fn add_seconds(dt: &NaiveDateTime, secs: i64) -> Option<NaiveDateTime> {
+    let (days, secs) = div_mod_floor(dt.num_seconds_from_midnight() as i64 + secs, 86400);
+    let time = NaiveTime::from_num_seconds_from_midnight(secs as u32, dt.nanosecond());
+    add_days(&dt.date(), days).map(|date| date.and_time(time))
+}
+
+/// Returns the local-to-UTC difference in seconds a day before `local`,
+/// which is the offset before the gap containing `local` if any
+/// (every offset change is less than a day apart from others).
+fn offset_before<Off: Offset>(offset: &Off, local: &NaiveDateTime) -> Option<i64> {
+    add_seconds(local, -86400)
+        .and_then(|dt| offset.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.offset().local_minus_utc().num_seconds())
+}
+
+/// Converts the local date and time to `DateTime` in given offset.
+/// The local date and time occurring twice is resolved to the earlier one,
+/// and the nonexistent one is resolved to the end of the gap.
+///
+/// Returns `None` only when the instant is out of range.
+pub fn resolve_datetime<Off: Offset>(offset: &Off,
+                                     local: &NaiveDateTime) -> Option<DateTime<Off>> {
+    match offset.from_local_datetime(local) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earlier, _) => Some(earlier),
+        LocalResult::None => {
+            // the gap ends at the transition, which is bisected between `local` in the offsets
+            // a day earlier and a day later (every offset change is less than a day apart)
+            let before = try_opt!(offset_before(offset, local));
+            let later = try_opt!(add_seconds(local, 86400)
+                                 .and_then(|dt| offset.from_local_datetime(&dt).latest()));
+            let after = later.offset().local_minus_utc().num_seconds();
+            let mut lo = try_opt!(add_seconds(local, -after)); // before the transition
+            let mut hi = try_opt!(add_seconds(local, -before)); // at or after the transition
+            while (hi - lo).num_seconds() > 1 {
+                let mid = try_opt!(add_seconds(&lo, (hi - lo).num_seconds() / 2));
+                if offset.at_utc(&mid).local_minus_utc().num_seconds() == before {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            Some(DateTime::from_utc(hi, offset.clone()))
+        }
+    }
+}
+
+/// Converts the local date and time to `DateTime` in given offset as RFC 5545 does.
+/// The local date and time occurring twice is resolved to the earlier one,
+/// and the nonexistent one is read in the offset before the gap
+/// (so it moves forward by the length of the gap).
+///
+/// Returns `None` only when the instant is out of range.
+pub fn resolve_datetime_before_gap<Off: Offset>(offset: &Off,
+                                                local: &NaiveDateTime) -> Option<DateTime<Off>> {
+    match offset.from_local_datetime(local) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earlier, _) => Some(earlier),
+        LocalResult::None => {
+            let before = try_opt!(offset_before(offset, local));
+            let utc = try_opt!(add_seconds(local, -before));
+            Some(DateTime::from_utc(utc, offset.clone()))
+        }
+    }
+}
+
+/// Converts the local date to `Date` in given offset, in the same way as `resolve_datetime`
+/// does for the start of the day.
+///
+/// Returns `None` only when the date is out of range.
+pub fn resolve_date<Off: Offset>(offset: &Off, local: &NaiveDate) -> Option<Date<Off>> {
+    match offset.from_local_date(local) {
+        LocalResult::Single(d) => Some(d),
+        LocalResult::Ambiguous(earlier, _) => Some(earlier),
+        LocalResult::None => resolve_datetime(offset, &local.and_hms(0, 0, 0)).map(|dt| dt.date()),
+    }
+}
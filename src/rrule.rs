@@ -0,0 +1,797 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2014-2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * Recurrence rules of iCalendar (RFC 5545).
+ *
+ * An `RRule` is the value of the `RRULE` property (e.g. `FREQ=MONTHLY;BYDAY=2TU;COUNT=10`),
+ * parsed via `FromStr` and printed back via `Display`. `RRule::iter` expands it
+ * from given `DTSTART` into the local date and time, and a `Recurrence` combines
+ * the rules with `RDATE` and `EXDATE` and can give the occurrences in an offset.
+ *
+ * Each period of `FREQ` is expanded or limited by the `BY*` parts as the table
+ * in the section 3.3.10 of RFC 5545 says, and the missing parts default to
+ * the fields of `DTSTART` (e.g. the day of month for `FREQ=MONTHLY`).
+ * The expansion stops at the last representable date, or when no occurrence has been
+ * found for 400 years (the Gregorian calendar repeats itself) times `INTERVAL`.
+ * A rule shorter than a day whose `BYHOUR`, `BYMINUTE` or `BYSECOND` can never be reached
+ * from `DTSTART` with its `INTERVAL` (e.g. `FREQ=SECONDLY;INTERVAL=2;BYSECOND=1` from
+ * an even second) has no occurrences at all. `BYSECOND=60` gives the leap second.
+ */
+
+use std::{fmt, iter};
+use std::num::ToPrimitive;
+
+use {Weekday, Datelike, Timelike};
+use div::div_mod_floor;
+use offset::Offset;
+use naive::date::NaiveDate;
+use naive::time::NaiveTime;
+use naive::datetime::NaiveDateTime;
+use datetime::DateTime;
+use months::{Months, MonthPolicy, days_in_month};
+use resolve::{add_days, add_seconds, resolve_datetime_before_gap};
+
+/// The names of `Frequency` in the `FREQ` rule part, starting from `Yearly`.
+pub static FREQUENCY_NAMES: [&'static str; 7] =
+    ["YEARLY", "MONTHLY", "WEEKLY", "DAILY", "HOURLY", "MINUTELY", "SECONDLY"];
+
+/// The two-letter weekday codes of iCalendar, starting from Monday.
+pub static WEEKDAY_CODES: [&'static str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// The frequency of a recurrence rule (`FREQ`), from the longest to the shortest period.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, FromPrimitive, Show)]
+pub enum Frequency {
+    /// Every year.
+    Yearly = 0,
+    /// Every month.
+    Monthly = 1,
+    /// Every week, starting from `RRule::week_start`.
+    Weekly = 2,
+    /// Every day.
+    Daily = 3,
+    /// Every hour.
+    Hourly = 4,
+    /// Every minute.
+    Minutely = 5,
+    /// Every second.
+    Secondly = 6,
+}
+
+/// A recurrence rule (`RRULE`).
+///
+/// The empty `BY*` lists are not in effect. The numbers can be negative
+/// where RFC 5545 allows, counting from the end (e.g. -1 for the last day of month).
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct RRule {
+    /// The frequency (`FREQ`).
+    pub freq: Frequency,
+    /// The number of periods between the occurrences (`INTERVAL`), 1 by default.
+    pub interval: u32,
+    /// The maximum number of occurrences (`COUNT`).
+    pub count: Option<u32>,
+    /// The last possible occurrence (`UNTIL`), inclusive.
+    pub until: Option<NaiveDateTime>,
+    /// True if `until` is in UTC (`UNTIL` ends with `Z`) rather than in the local time.
+    /// It is only considered by `Recurrence::iter_with_offset`.
+    pub until_utc: bool,
+    /// True if `UNTIL` is a date alone, so that `until` is the end of that day
+    /// and is printed back as the date.
+    pub until_date: bool,
+    /// Seconds from 0 to 60 (`BYSECOND`). 60 is the leap second, which never occurs
+    /// with `FREQ=SECONDLY` as the periods do not include it.
+    pub by_second: Vec<u32>,
+    /// Minutes from 0 to 59 (`BYMINUTE`).
+    pub by_minute: Vec<u32>,
+    /// Hours from 0 to 23 (`BYHOUR`).
+    pub by_hour: Vec<u32>,
+    /// Days of week (`BYDAY`), optionally with the occurrence within the month
+    /// (`FREQ=MONTHLY` or with `BYMONTH`) or the year (`FREQ=YEARLY`),
+    /// e.g. `(Some(2), Weekday::Tue)` for `2TU` and `(Some(-1), Weekday::Fri)` for `-1FR`.
+    /// The occurrence is ignored for other frequencies.
+    pub by_day: Vec<(Option<i32>, Weekday)>,
+    /// Days of month from 1 to 31 or -31 to -1 (`BYMONTHDAY`).
+    pub by_month_day: Vec<i32>,
+    /// Days of year from 1 to 366 or -366 to -1 (`BYYEARDAY`).
+    pub by_year_day: Vec<i32>,
+    /// Weeks of year from 1 to 53 or -53 to -1 (`BYWEEKNO`), only for `FREQ=YEARLY`.
+    /// The week 1 is the first week with at least four days in the year.
+    pub by_week_no: Vec<i32>,
+    /// Months from 1 to 12 (`BYMONTH`).
+    pub by_month: Vec<u32>,
+    /// The occurrences to keep in each period from 1 to 366 or -366 to -1 (`BYSETPOS`).
+    pub by_set_pos: Vec<i32>,
+    /// The first day of week (`WKST`), Monday by default.
+    pub week_start: Weekday,
+}
+
+impl RRule {
+    /// Makes a new `RRule` with given frequency, occurring every period without an end.
+    pub fn new(freq: Frequency) -> RRule {
+        RRule { freq: freq, interval: 1, count: None, until: None,
+                until_utc: false, until_date: false,
+                by_second: Vec::new(), by_minute: Vec::new(), by_hour: Vec::new(),
+                by_day: Vec::new(), by_month_day: Vec::new(), by_year_day: Vec::new(),
+                by_week_no: Vec::new(), by_month: Vec::new(), by_set_pos: Vec::new(),
+                week_start: Weekday::Mon }
+    }
+
+    /// Returns an iterator over the occurrences from `dtstart` in the local date and time.
+    /// `dtstart` itself is returned only when it matches the rule,
+    /// and `until` is compared with the local date and time.
+    pub fn iter(&self, dtstart: NaiveDateTime) -> RRuleIter {
+        let rule = self.with_defaults(&dtstart);
+        let period = if rule.reaches_time(&dtstart) { first_period(&rule, &dtstart) } else { None };
+        RRuleIter { rule: rule, dtstart: dtstart, period: period,
+                    last_year: dtstart.year(), pending: Vec::new(), count: 0 }
+    }
+
+    /// Fills the `BY*` parts derived from `dtstart`, and sorts the time parts.
+    fn with_defaults(&self, dtstart: &NaiveDateTime) -> RRule {
+        let mut rule = self.clone();
+        if rule.interval == 0 { rule.interval = 1; }
+        if rule.by_week_no.is_empty() && rule.by_year_day.is_empty() &&
+           rule.by_month_day.is_empty() && rule.by_day.is_empty() {
+            match rule.freq {
+                Frequency::Yearly => {
+                    if rule.by_month.is_empty() { rule.by_month.push(dtstart.month()); }
+                    rule.by_month_day.push(dtstart.day() as i32);
+                }
+                Frequency::Monthly => rule.by_month_day.push(dtstart.day() as i32),
+                Frequency::Weekly => rule.by_day.push((None, dtstart.weekday())),
+                _ => {}
+            }
+        }
+        for values in [&mut rule.by_hour, &mut rule.by_minute, &mut rule.by_second].iter_mut() {
+            values.sort();
+            values.dedup();
+        }
+        rule
+    }
+
+    /// Returns false if the frequency is shorter than a day and no period from `dtstart`
+    /// can satisfy the `BY*` parts limiting the time, so that the iteration would never end.
+    fn reaches_time(&self, dtstart: &NaiveDateTime) -> bool {
+        let unit = match self.freq {
+            Frequency::Hourly => 3600,
+            Frequency::Minutely => 60,
+            Frequency::Secondly => 1,
+            _ => return true,
+        };
+        // the periods start at the seconds from midnight congruent to `dtstart`
+        // modulo the greatest common divisor of the interval and a day
+        let (mut step, mut rem) = (unit * self.interval as i64, 86400);
+        while rem != 0 {
+            let next = step % rem;
+            step = rem;
+            rem = next;
+        }
+        let limits = |&: freq: Frequency, values: &Vec<u32>, value: i64| {
+            self.freq < freq || values.is_empty() || values.contains(&(value as u32))
+        };
+        let first = dtstart.num_seconds_from_midnight() as i64 % step;
+        iter::range_step(first, 86400, step).any(|secs| {
+            limits(Frequency::Hourly, &self.by_hour, secs / 3600) &&
+                limits(Frequency::Minutely, &self.by_minute, secs / 60 % 60) &&
+                limits(Frequency::Secondly, &self.by_second, secs % 60)
+        })
+    }
+}
+
+/// The `RRULE` value, e.g. `FREQ=MONTHLY;BYDAY=2TU;COUNT=10`.
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "FREQ={}", FREQUENCY_NAMES[self.freq as usize]));
+        match self.until {
+            Some(until) if self.until_date => try!(write!(f, ";UNTIL={}",
+                                                          until.format("%Y%m%d"))),
+            Some(until) => try!(write!(f, ";UNTIL={}{}", until.format("%Y%m%dT%H%M%S"),
+                                       if self.until_utc {"Z"} else {""})),
+            None => {}
+        }
+        match self.count {
+            Some(count) => try!(write!(f, ";COUNT={}", count)),
+            None => {}
+        }
+        if self.interval != 1 { try!(write!(f, ";INTERVAL={}", self.interval)); }
+        try!(write_list(f, "BYSECOND", self.by_second.as_slice()));
+        try!(write_list(f, "BYMINUTE", self.by_minute.as_slice()));
+        try!(write_list(f, "BYHOUR", self.by_hour.as_slice()));
+        if !self.by_day.is_empty() {
+            try!(write!(f, ";BYDAY="));
+            for (i, &(n, weekday)) in self.by_day.iter().enumerate() {
+                if i > 0 { try!(write!(f, ",")); }
+                match n {
+                    Some(n) => try!(write!(f, "{}", n)),
+                    None => {}
+                }
+                try!(write!(f, "{}", WEEKDAY_CODES[weekday.num_days_from_monday() as usize]));
+            }
+        }
+        try!(write_list(f, "BYMONTHDAY", self.by_month_day.as_slice()));
+        try!(write_list(f, "BYYEARDAY", self.by_year_day.as_slice()));
+        try!(write_list(f, "BYWEEKNO", self.by_week_no.as_slice()));
+        try!(write_list(f, "BYMONTH", self.by_month.as_slice()));
+        try!(write_list(f, "BYSETPOS", self.by_set_pos.as_slice()));
+        if self.week_start != Weekday::Mon {
+            let wkst = self.week_start.num_days_from_monday() as usize;
+            try!(write!(f, ";WKST={}", WEEKDAY_CODES[wkst]));
+        }
+        Ok(())
+    }
+}
+
+/// Writes a `BY*` rule part unless `values` is empty.
+fn write_list<T: fmt::Display>(f: &mut fmt::Formatter, name: &str, values: &[T]) -> fmt::Result {
+    if values.is_empty() { return Ok(()); }
+    try!(write!(f, ";{}=", name));
+    for (i, v) in values.iter().enumerate() {
+        try!(write!(f, "{}{}", if i > 0 {","} else {""}, v));
+    }
+    Ok(())
+}
+
+/// Returns the number of days in the year of `date`.
+fn days_in_year(date: &NaiveDate) -> u32 {
+    NaiveDate::from_ymd(date.year(), 12, 31).ordinal()
+}
+
+/// Returns the start of the period containing `dtstart`.
+fn first_period(rule: &RRule, dtstart: &NaiveDateTime) -> Option<NaiveDateTime> {
+    let date = dtstart.date();
+    let start = match rule.freq {
+        Frequency::Yearly => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+        Frequency::Monthly => date.with_day(1),
+        Frequency::Weekly => {
+            let days = 7 + date.weekday().num_days_from_monday() -
+                       rule.week_start.num_days_from_monday();
+            add_days(&date, -((days % 7) as i64))
+        }
+        _ => return Some(*dtstart),
+    };
+    start.map(|date| date.and_time(dtstart.time()))
+}
+
+/// An iterator over the occurrences of `RRule`, made by `RRule::iter`.
+#[derive(Clone)]
+pub struct RRuleIter {
+    /// The rule with the defaults filled.
+    rule: RRule,
+    dtstart: NaiveDateTime,
+    /// The start of the next period to expand, or `None` after the last period.
+    period: Option<NaiveDateTime>,
+    /// The year of the last period with any occurrence (or `dtstart`).
+    last_year: i32,
+    /// The occurrences of the last period not returned yet, in the reverse order.
+    pending: Vec<NaiveDateTime>,
+    /// The number of occurrences returned so far.
+    count: u32,
+}
+
+impl RRuleIter {
+    /// Returns true if `date` satisfies the day-level `BY*` parts of the rule.
+    fn matches_day(&self, date: &NaiveDate) -> bool {
+        let rule = &self.rule;
+        let matches = |&: values: &[i32], value: u32, len: u32| {
+            values.is_empty() ||
+                values.iter().any(|&v| v == value as i32 || v == value as i32 - len as i32 - 1)
+        };
+        (rule.by_month.is_empty() || rule.by_month.contains(&date.month())) &&
+            matches(rule.by_year_day.as_slice(), date.ordinal(), days_in_year(date)) &&
+            matches(rule.by_month_day.as_slice(), date.day(),
+                    days_in_month(date.year(), date.month())) &&
+            (rule.by_day.is_empty() || rule.by_day.iter().any(|&(n, weekday)| {
+                weekday == date.weekday() && n.map_or(true, |n| self.matches_nth(date, n))
+            }))
+    }
+
+    /// Returns true if `date` is the `n`-th (or the `-n`-th from the end) of its weekday
+    /// in the month or year, whichever applies to the rule.
+    fn matches_nth(&self, date: &NaiveDate, n: i32) -> bool {
+        let (index, len) = match self.rule.freq {
+            Frequency::Yearly if self.rule.by_month.is_empty() =>
+                (date.ordinal(), days_in_year(date)),
+            Frequency::Yearly | Frequency::Monthly =>
+                (date.day(), days_in_month(date.year(), date.month())),
+            _ => return true,
+        };
+        if n > 0 { (index - 1) / 7 + 1 == n as u32 } else { (len - index) / 7 + 1 == -n as u32 }
+    }
+
+    /// Returns the days in the weeks of `BYWEEKNO` in given year.
+    /// They can include the last days of the previous year or the first days of the next year.
+    fn week_no_days(&self, year: i32) -> Vec<NaiveDate> {
+        let jan1 = match NaiveDate::from_ymd_opt(year, 1, 1) {
+            Some(jan1) => jan1,
+            None => return Vec::new(),
+        };
+        let wkst = self.rule.week_start.num_days_from_monday() as i64;
+        let week1 = |&: jan1: i64| {
+            // the weekday of 0001-01-01 (day 1) is Monday
+            let offset = div_mod_floor(jan1 - 1 - wkst, 7).1;
+            if offset <= 3 { jan1 - offset } else { jan1 + 7 - offset }
+        };
+        let start = week1(jan1.num_days_from_ce() as i64);
+        let end = week1(jan1.num_days_from_ce() as i64 + days_in_year(&jan1) as i64);
+        let nweeks = (end - start) / 7;
+
+        let mut weeks: Vec<i64> = self.rule.by_week_no.iter().map(|&w| w as i64)
+            .map(|w| if w < 0 { nweeks + 1 + w } else { w })
+            .filter(|&w| 1 <= w && w <= nweeks).collect();
+        weeks.sort();
+        weeks.dedup();
+        weeks.iter().flat_map(|&w| range(0, 7).map(move |i| start + (w - 1) * 7 + i))
+             .filter_map(|days| days.to_i32().and_then(NaiveDate::from_num_days_from_ce_opt))
+             .collect()
+    }
+
+    /// Returns the occurrences in the period starting at `period`, in the order.
+    fn expand(&self, period: &NaiveDateTime) -> Vec<NaiveDateTime> {
+        let rule = &self.rule;
+        let date = period.date();
+        let days: Vec<NaiveDate> = match rule.freq {
+            Frequency::Yearly if !rule.by_week_no.is_empty() => self.week_no_days(date.year()),
+            Frequency::Yearly =>
+                date.iter_days().take_while(|d| d.year() == date.year()).collect(),
+            Frequency::Monthly =>
+                date.iter_days().take_while(|d| d.month() == date.month()).collect(),
+            Frequency::Weekly => date.iter_days().take(7).collect(),
+            _ => vec![date],
+        };
+
+        // the time parts are expanded for the longer periods and limited otherwise
+        let times = |&: freq: Frequency, values: &Vec<u32>, current: u32, start: u32| {
+            if rule.freq < freq {
+                if values.is_empty() { vec![start] } else { values.clone() }
+            } else if values.is_empty() || values.contains(&current) {
+                vec![current]
+            } else {
+                vec![]
+            }
+        };
+        let hours = times(Frequency::Hourly, &rule.by_hour, period.hour(), self.dtstart.hour());
+        let minutes = times(Frequency::Minutely, &rule.by_minute,
+                            period.minute(), self.dtstart.minute());
+        let seconds = times(Frequency::Secondly, &rule.by_second,
+                            period.second(), self.dtstart.second());
+
+        let mut set = Vec::new();
+        for date in days.iter().filter(|d| self.matches_day(d)) {
+            for &hour in hours.iter() {
+                for &minute in minutes.iter() {
+                    for &second in seconds.iter() {
+                        let nano = self.dtstart.nanosecond();
+                        // the second 60 is represented as the leap second
+                        let (second, nano) = if second == 60 { (59, nano + 1_000_000_000) }
+                                             else { (second, nano) };
+                        match NaiveTime::from_hms_nano_opt(hour, minute, second, nano) {
+                            Some(time) => set.push(date.and_time(time)),
+                            None => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        if !rule.by_set_pos.is_empty() {
+            let len = set.len() as i32;
+            let mut selected: Vec<NaiveDateTime> = rule.by_set_pos.iter().filter_map(|&pos| {
+                let i = if pos > 0 { pos - 1 } else { len + pos };
+                if 0 <= i && i < len { Some(set[i as usize]) } else { None }
+            }).collect();
+            selected.sort();
+            selected.dedup();
+            set = selected;
+        }
+        set
+    }
+
+    /// Returns the start of the next period.
+    fn next_period(&self, period: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let interval = self.rule.interval as i64;
+        let date = period.date();
+        let next = match self.rule.freq {
+            Frequency::Yearly => (date.year() as i64 + interval).to_i32()
+                                     .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1)),
+            Frequency::Monthly => date.checked_add_months(Months::new(self.rule.interval),
+                                                          MonthPolicy::Fail),
+            Frequency::Weekly => add_days(&date, 7 * interval),
+            Frequency::Daily => add_days(&date, interval),
+            Frequency::Hourly => return add_seconds(period, 3600 * interval),
+            Frequency::Minutely => return add_seconds(period, 60 * interval),
+            Frequency::Secondly => return add_seconds(period, interval),
+        };
+        next.map(|date| date.and_time(period.time()))
+    }
+
+    /// Returns the first period starting in the next day, hour or minute (`boundary` seconds)
+    /// for the frequencies shorter than a day, so that the periods which cannot match
+    /// the rule are skipped at once.
+    fn skip_period(&self, period: &NaiveDateTime, boundary: i64) -> Option<NaiveDateTime> {
+        let unit = match self.rule.freq {
+            Frequency::Hourly => 3600,
+            Frequency::Minutely => 60,
+            _ => 1,
+        };
+        let step = unit * self.rule.interval as i64;
+        let distance = boundary - period.num_seconds_from_midnight() as i64 % boundary;
+        add_seconds(period, (distance + step - 1) / step * step)
+    }
+}
+
+impl Iterator for RRuleIter {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        loop {
+            if self.rule.count.map_or(false, |count| self.count >= count) { return None; }
+            match self.pending.pop() {
+                Some(dt) => {
+                    if self.rule.until.map_or(false, |until| dt > until) {
+                        self.period = None;
+                        self.pending.clear();
+                        return None;
+                    }
+                    self.count += 1;
+                    return Some(dt);
+                }
+                None => {}
+            }
+
+            let period = match self.period { Some(period) => period, None => return None };
+            let skip = {
+                let rule = &self.rule;
+                if rule.freq >= Frequency::Hourly && !self.matches_day(&period.date()) {
+                    Some(86400)
+                } else if rule.freq >= Frequency::Minutely && !rule.by_hour.is_empty() &&
+                          !rule.by_hour.contains(&period.hour()) {
+                    Some(3600)
+                } else if rule.freq == Frequency::Secondly && !rule.by_minute.is_empty() &&
+                          !rule.by_minute.contains(&period.minute()) {
+                    Some(60)
+                } else {
+                    None
+                }
+            };
+            self.period = match skip {
+                Some(boundary) => self.skip_period(&period, boundary),
+                None => {
+                    let dtstart = self.dtstart;
+                    let mut found = self.expand(&period);
+                    found.retain(|dt| *dt >= dtstart);
+                    if !found.is_empty() { self.last_year = period.year(); }
+                    found.reverse();
+                    self.pending = found;
+                    self.next_period(&period)
+                }
+            };
+
+            // give up when the rule has not matched for the whole cycle of the calendar
+            match self.period {
+                Some(next) if (next.year() - self.last_year) as i64 >
+                              400 * self.rule.interval as i64 => self.period = None,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A recurrence set: `DTSTART` and the occurrences of the rules (`RRULE`)
+/// and the additional dates (`RDATE`), except for the excluded dates (`EXDATE`).
+/// The occurrences are in the local date and time, in the order and without duplicates.
+#[derive(Clone, PartialEq, Eq, Show)]
+pub struct Recurrence {
+    dtstart: NaiveDateTime,
+    rules: Vec<RRule>,
+    rdates: Vec<NaiveDateTime>,
+    exdates: Vec<NaiveDateTime>,
+}
+
+impl Recurrence {
+    /// Makes a new `Recurrence` with given `DTSTART`, which is always the first occurrence
+    /// unless excluded. RFC 5545 recommends `DTSTART` to match the rules.
+    pub fn new(dtstart: NaiveDateTime) -> Recurrence {
+        Recurrence { dtstart: dtstart, rules: Vec::new(), rdates: Vec::new(), exdates: Vec::new() }
+    }
+
+    /// Returns `DTSTART`.
+    #[inline]
+    pub fn dtstart(&self) -> NaiveDateTime {
+        self.dtstart
+    }
+
+    /// Adds a recurrence rule (`RRULE`).
+    pub fn add_rule(&mut self, rule: RRule) {
+        self.rules.push(rule);
+    }
+
+    /// Adds an additional occurrence (`RDATE`).
+    pub fn add_rdate(&mut self, rdate: NaiveDateTime) {
+        self.rdates.push(rdate);
+    }
+
+    /// Excludes an occurrence (`EXDATE`), whether from `DTSTART`, the rules or `RDATE`.
+    pub fn add_exdate(&mut self, exdate: NaiveDateTime) {
+        self.exdates.push(exdate);
+    }
+
+    /// Returns an iterator over the occurrences in the local date and time.
+    /// The `UNTIL` parts are compared with the local date and time.
+    pub fn iter(&self) -> RecurrenceIter {
+        self.iter_with_rules(self.rules.clone())
+    }
+
+    /// Returns an iterator over the occurrences in given offset.
+    /// The `UNTIL` parts in UTC are converted to the local date and time first,
+    /// in the offset in effect at `UNTIL`.
+    ///
+    /// The local date and time occurring twice is resolved to the earlier one,
+    /// and the nonexistent local date and time (e.g. in the gap of the daylight saving time)
+    /// is resolved with the offset before the gap, as RFC 5545 says.
+    pub fn iter_with_offset<Off: Offset>(&self, offset: Off) -> RecurrenceIterWithOffset<Off> {
+        let rules = self.rules.iter().map(|rule| {
+            let mut rule = rule.clone();
+            if rule.until_utc {
+                rule.until = rule.until.map(|until| {
+                    offset.at_utc(&until).to_local_datetime(&until)
+                });
+                rule.until_utc = false;
+            }
+            rule
+        }).collect();
+        RecurrenceIterWithOffset { iter: self.iter_with_rules(rules), offset: offset }
+    }
+
+    fn iter_with_rules(&self, rules: Vec<RRule>) -> RecurrenceIter {
+        let mut rdates = self.rdates.clone();
+        rdates.push(self.dtstart);
+        rdates.sort_by(|a, b| b.cmp(a)); // in the reverse order
+        rdates.dedup();
+        let rules = rules.iter().map(|rule| {
+            let mut iter = rule.iter(self.dtstart);
+            (iter.next(), iter)
+        }).collect();
+        RecurrenceIter { rules: rules, rdates: rdates, exdates: self.exdates.clone() }
+    }
+}
+
+/// An iterator over the occurrences of `Recurrence`, made by `Recurrence::iter`.
+#[derive(Clone)]
+pub struct RecurrenceIter {
+    /// The next occurrence of each rule and the rest.
+    rules: Vec<(Option<NaiveDateTime>, RRuleIter)>,
+    /// `RDATE` and `DTSTART` not returned yet, in the reverse order.
+    rdates: Vec<NaiveDateTime>,
+    exdates: Vec<NaiveDateTime>,
+}
+
+impl Iterator for RecurrenceIter {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<NaiveDateTime> {
+        loop {
+            let mut next = self.rdates.last().map(|&dt| dt);
+            for &(head, _) in self.rules.iter() {
+                match (head, next) {
+                    (Some(head), Some(dt)) if head >= dt => {}
+                    (Some(head), _) => next = Some(head),
+                    (None, _) => {}
+                }
+            }
+            let next = match next { Some(next) => next, None => return None };
+
+            // consume every source at `next`, so that it is returned only once
+            if self.rdates.last() == Some(&next) { self.rdates.pop(); }
+            for &mut (ref mut head, ref mut iter) in self.rules.iter_mut() {
+                if *head == Some(next) { *head = iter.next(); }
+            }
+            if !self.exdates.contains(&next) { return Some(next); }
+        }
+    }
+}
+
+/// An iterator over the occurrences of `Recurrence` in an offset,
+/// made by `Recurrence::iter_with_offset`.
+#[derive(Clone)]
+pub struct RecurrenceIterWithOffset<Off> {
+    iter: RecurrenceIter,
+    offset: Off,
+}
+
+impl<Off: Offset> Iterator for RecurrenceIterWithOffset<Off> {
+    type Item = DateTime<Off>;
+
+    fn next(&mut self) -> Option<DateTime<Off>> {
+        loop {
+            let local = match self.iter.next() { Some(local) => local, None => return None };
+            match resolve_datetime_before_gap(&self.offset, &local) {
+                Some(dt) => return Some(dt),
+                None => {} // out of range, skip it
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RRule, Recurrence, Frequency};
+    use {Weekday, Timelike};
+    use offset::{Offset, FixedOffset};
+    use tz::posix::PosixTz;
+    use naive::date::NaiveDate;
+    use naive::datetime::NaiveDateTime;
+
+    fn ymd_hms(y: i32, m: u32, d: u32, h: u32, n: u32, s: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd(y, m, d).and_hms(h, n, s)
+    }
+
+    fn expand(rule: &str, dtstart: NaiveDateTime, limit: usize) -> Vec<String> {
+        let rule: RRule = rule.parse().unwrap();
+        rule.iter(dtstart).take(limit).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_rrule_fmt() {
+        let mut rule = RRule::new(Frequency::Monthly);
+        rule.count = Some(10);
+        rule.by_day.push((Some(2), Weekday::Tue));
+        assert_eq!(rule.to_string(), "FREQ=MONTHLY;COUNT=10;BYDAY=2TU");
+
+        let mut rule = RRule::new(Frequency::Weekly);
+        rule.interval = 2;
+        rule.until = Some(ymd_hms(1997, 12, 24, 0, 0, 0));
+        rule.until_utc = true;
+        rule.by_day.push((None, Weekday::Mon));
+        rule.by_day.push((Some(-1), Weekday::Fri));
+        rule.by_set_pos.push(-1);
+        rule.week_start = Weekday::Sun;
+        assert_eq!(rule.to_string(),
+                   "FREQ=WEEKLY;UNTIL=19971224T000000Z;INTERVAL=2;BYDAY=MO,-1FR;BYSETPOS=-1;\
+                    WKST=SU");
+    }
+
+    // the examples are from the section 3.8.5.3 of RFC 5545, with DTSTART in 1997.
+    #[test]
+    fn test_rrule_yearly() {
+        let start = ymd_hms(1997, 6, 10, 9, 0, 0);
+        assert_eq!(expand("FREQ=YEARLY;COUNT=3;BYMONTH=6,7", start, 10),
+                   ["1997-06-10 09:00:00", "1997-07-10 09:00:00", "1998-06-10 09:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY;BYDAY=20MO", ymd_hms(1997, 5, 19, 9, 0, 0), 3),
+                   ["1997-05-19 09:00:00", "1998-05-18 09:00:00", "1999-05-17 09:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY;BYWEEKNO=20;BYDAY=MO", ymd_hms(1997, 5, 12, 9, 0, 0), 3),
+                   ["1997-05-12 09:00:00", "1998-05-11 09:00:00", "1999-05-17 09:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY;BYWEEKNO=1;BYDAY=MO", ymd_hms(1997, 1, 1, 9, 0, 0), 3),
+                   ["1997-12-29 09:00:00", "1999-01-04 09:00:00", "2000-01-03 09:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY;INTERVAL=3;COUNT=4;BYYEARDAY=1,100,200",
+                          ymd_hms(1997, 1, 1, 9, 0, 0), 10),
+                   ["1997-01-01 09:00:00", "1997-04-10 09:00:00",
+                    "1997-07-19 09:00:00", "2000-01-01 09:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY;BYMONTH=3;BYDAY=TH", ymd_hms(1997, 3, 13, 9, 0, 0), 4),
+                   ["1997-03-13 09:00:00", "1997-03-20 09:00:00",
+                    "1997-03-27 09:00:00", "1998-03-05 09:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY;INTERVAL=4;BYMONTH=11;BYDAY=TU;BYMONTHDAY=2,3,4,5,6,7,8",
+                          ymd_hms(1996, 11, 5, 9, 0, 0), 3),
+                   ["1996-11-05 09:00:00", "2000-11-07 09:00:00", "2004-11-02 09:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY", ymd_hms(2012, 2, 29, 0, 0, 0), 2),
+                   ["2012-02-29 00:00:00", "2016-02-29 00:00:00"]);
+        assert_eq!(expand("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", start, 1), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_rrule_monthly() {
+        let start = ymd_hms(1997, 9, 5, 9, 0, 0);
+        assert_eq!(expand("FREQ=MONTHLY;COUNT=10;BYDAY=1FR", start, 3),
+                   ["1997-09-05 09:00:00", "1997-10-03 09:00:00", "1997-11-07 09:00:00"]);
+        assert_eq!(expand("FREQ=MONTHLY;INTERVAL=2;COUNT=10;BYDAY=1SU,-1SU",
+                          ymd_hms(1997, 9, 7, 9, 0, 0), 4),
+                   ["1997-09-07 09:00:00", "1997-09-28 09:00:00",
+                    "1997-11-02 09:00:00", "1997-11-30 09:00:00"]);
+        assert_eq!(expand("FREQ=MONTHLY;BYMONTHDAY=-3", ymd_hms(1997, 9, 28, 9, 0, 0), 3),
+                   ["1997-09-28 09:00:00", "1997-10-29 09:00:00", "1997-11-28 09:00:00"]);
+        assert_eq!(expand("FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13", ymd_hms(1997, 9, 2, 9, 0, 0), 3),
+                   ["1998-02-13 09:00:00", "1998-03-13 09:00:00", "1998-11-13 09:00:00"]);
+        assert_eq!(expand("FREQ=MONTHLY;COUNT=3;BYDAY=TU,WE,TH;BYSETPOS=3",
+                          ymd_hms(1997, 9, 4, 9, 0, 0), 10),
+                   ["1997-09-04 09:00:00", "1997-10-07 09:00:00", "1997-11-06 09:00:00"]);
+        assert_eq!(expand("FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-2",
+                          ymd_hms(1997, 9, 29, 9, 0, 0), 3),
+                   ["1997-09-29 09:00:00", "1997-10-30 09:00:00", "1997-11-27 09:00:00"]);
+        assert_eq!(expand("FREQ=MONTHLY", ymd_hms(2015, 1, 31, 0, 0, 0), 3),
+                   ["2015-01-31 00:00:00", "2015-03-31 00:00:00", "2015-05-31 00:00:00"]);
+    }
+
+    #[test]
+    fn test_rrule_weekly_daily() {
+        let start = ymd_hms(1997, 9, 2, 9, 0, 0);
+        assert_eq!(expand("FREQ=WEEKLY;UNTIL=19971007T000000Z;WKST=SU;BYDAY=TU,TH", start, 20)
+                       .len(), 10);
+        assert_eq!(expand("FREQ=WEEKLY;INTERVAL=2;WKST=SU;BYDAY=TU,TH", start, 4),
+                   ["1997-09-02 09:00:00", "1997-09-04 09:00:00",
+                    "1997-09-16 09:00:00", "1997-09-18 09:00:00"]);
+        // WKST changes the result
+        assert_eq!(expand("FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=MO",
+                          ymd_hms(1997, 8, 5, 9, 0, 0), 10),
+                   ["1997-08-05 09:00:00", "1997-08-10 09:00:00",
+                    "1997-08-19 09:00:00", "1997-08-24 09:00:00"]);
+        assert_eq!(expand("FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=SU",
+                          ymd_hms(1997, 8, 5, 9, 0, 0), 10),
+                   ["1997-08-05 09:00:00", "1997-08-17 09:00:00",
+                    "1997-08-19 09:00:00", "1997-08-31 09:00:00"]);
+        assert_eq!(expand("FREQ=DAILY;INTERVAL=10;COUNT=3", start, 10),
+                   ["1997-09-02 09:00:00", "1997-09-12 09:00:00", "1997-09-22 09:00:00"]);
+        assert_eq!(expand("FREQ=DAILY;UNTIL=19970904", start, 10),
+                   ["1997-09-02 09:00:00", "1997-09-03 09:00:00", "1997-09-04 09:00:00"]);
+        assert_eq!(expand("FREQ=DAILY;BYMONTH=1;BYHOUR=9,17", ymd_hms(1998, 1, 31, 9, 0, 0), 3),
+                   ["1998-01-31 09:00:00", "1998-01-31 17:00:00", "1999-01-01 09:00:00"]);
+    }
+
+    #[test]
+    fn test_rrule_hourly() {
+        let start = ymd_hms(1997, 9, 2, 9, 0, 0);
+        assert_eq!(expand("FREQ=HOURLY;INTERVAL=3;UNTIL=19970902T170000Z", start, 10),
+                   ["1997-09-02 09:00:00", "1997-09-02 12:00:00", "1997-09-02 15:00:00"]);
+        assert_eq!(expand("FREQ=MINUTELY;INTERVAL=15;COUNT=3", start, 10),
+                   ["1997-09-02 09:00:00", "1997-09-02 09:15:00", "1997-09-02 09:30:00"]);
+        assert_eq!(expand("FREQ=MINUTELY;INTERVAL=20;BYHOUR=9,10", start, 4),
+                   ["1997-09-02 09:00:00", "1997-09-02 09:20:00",
+                    "1997-09-02 09:40:00", "1997-09-02 10:00:00"]);
+        assert_eq!(expand("FREQ=MINUTELY;INTERVAL=20;BYHOUR=9,10", start, 7)[6],
+                   "1997-09-03 09:00:00");
+        assert_eq!(expand("FREQ=HOURLY;INTERVAL=5;BYDAY=SA", start, 2),
+                   ["1997-09-06 03:00:00", "1997-09-06 08:00:00"]);
+        assert_eq!(expand("FREQ=SECONDLY;BYMONTHDAY=3;BYHOUR=0;BYMINUTE=0;BYSECOND=1,2", start, 3),
+                   ["1997-09-03 00:00:01", "1997-09-03 00:00:02", "1997-10-03 00:00:01"]);
+
+        // the time parts which cannot be reached from `DTSTART`
+        assert!(expand("FREQ=SECONDLY;INTERVAL=2;BYSECOND=1", start, 1).is_empty());
+        assert_eq!(expand("FREQ=SECONDLY;INTERVAL=2;BYSECOND=1", ymd_hms(1997, 9, 2, 9, 0, 1), 2),
+                   ["1997-09-02 09:00:01", "1997-09-02 09:01:01"]);
+        assert_eq!(expand("FREQ=SECONDLY;INTERVAL=7;BYHOUR=0;BYMINUTE=0;BYSECOND=3", start, 1),
+                   ["1997-09-08 00:00:03"]);
+        assert!(expand("FREQ=HOURLY;INTERVAL=6;BYHOUR=1", start, 1).is_empty());
+        assert_eq!(expand("FREQ=HOURLY;INTERVAL=5;BYHOUR=1", start, 1), ["1997-09-04 01:00:00"]);
+        assert!(expand("FREQ=MINUTELY;INTERVAL=30;BYMINUTE=15", start, 1).is_empty());
+        assert!(expand("FREQ=SECONDLY;BYSECOND=60", start, 1).is_empty());
+
+        // the leap second
+        let rule: RRule = "FREQ=MINUTELY;BYSECOND=60".parse().unwrap();
+        let leaps: Vec<(u32, u32, u32)> = rule.iter(start).take(2)
+            .map(|dt| (dt.minute(), dt.second(), dt.nanosecond())).collect();
+        assert_eq!(leaps, [(0, 59, 1_000_000_000), (1, 59, 1_000_000_000)]);
+    }
+
+    #[test]
+    fn test_recurrence() {
+        let start = ymd_hms(1997, 9, 2, 9, 0, 0);
+        let mut recur = Recurrence::new(start);
+        recur.add_rule("FREQ=WEEKLY;COUNT=3;BYDAY=WE".parse().unwrap());
+        recur.add_rule("FREQ=WEEKLY;COUNT=2;BYDAY=WE,TH".parse().unwrap());
+        recur.add_rdate(ymd_hms(1997, 9, 3, 12, 0, 0));
+        recur.add_rdate(ymd_hms(1997, 9, 10, 9, 0, 0));
+        recur.add_exdate(ymd_hms(1997, 9, 4, 9, 0, 0));
+        assert_eq!(recur.iter().collect::<Vec<_>>(),
+                   [start, ymd_hms(1997, 9, 3, 9, 0, 0), ymd_hms(1997, 9, 3, 12, 0, 0),
+                    ymd_hms(1997, 9, 10, 9, 0, 0), ymd_hms(1997, 9, 17, 9, 0, 0)]);
+
+        let mut recur = Recurrence::new(start);
+        recur.add_rule("FREQ=DAILY;UNTIL=19970903T120000Z".parse().unwrap());
+        let dts = recur.iter_with_offset(FixedOffset::east(9 * 3600)).collect::<Vec<_>>();
+        assert_eq!(dts, [FixedOffset::east(9 * 3600).ymd(1997, 9, 2).and_hms(9, 0, 0),
+                         FixedOffset::east(9 * 3600).ymd(1997, 9, 3).and_hms(9, 0, 0)]);
+        assert_eq!(recur.iter().count(), 2);
+        let dts = recur.iter_with_offset(FixedOffset::west(5 * 3600)).collect::<Vec<_>>();
+        assert_eq!(dts.len(), 1);
+
+        // `UNTIL` is in the daylight saving time unlike the offset given
+        let tz = PosixTz::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        let winter = tz.at_utc(&ymd_hms(1997, 1, 1, 0, 0, 0));
+        let mut recur = Recurrence::new(ymd_hms(1997, 6, 28, 9, 0, 0));
+        recur.add_rule("FREQ=DAILY;UNTIL=19970701T130000Z".parse().unwrap());
+        let dts = recur.iter_with_offset(winter).map(|dt| format!("{:?}", dt))
+                       .collect::<Vec<_>>();
+        assert_eq!(dts.len(), 4);
+        assert_eq!(dts[3], "1997-07-01T09:00:00-04:00");
+    }
+}
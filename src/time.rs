@@ -14,7 +14,8 @@ use Timelike;
 use offset::Offset;
 use duration::Duration;
 use naive::time::NaiveTime;
-use format::DelayedFormat;
+use format::{Item, StrftimeItems, DelayedFormat};
+use locale::Locale;
 
 /// ISO 8601 time with timezone.
 #[derive(Clone)]
@@ -51,11 +52,26 @@ impl<Off:Offset> Time<Off> {
 }
 
 impl<Off: Offset + fmt::Display> Time<Off> {
+    /// Formats the time with the specified formatting items.
+    #[inline]
+    pub fn format_with_items<'a, I>(&self, items: I) -> DelayedFormat<'a, I>
+            where I: Iterator<Item=Item<'a>> + Clone {
+        DelayedFormat::new_with_offset(None, Some(self.local()), &self.offset, items)
+    }
+
     /// Formats the time in the specified format string.
     /// See the `format` module on the supported escape sequences.
     #[inline]
-    pub fn format<'a>(&'a self, fmt: &'a str) -> DelayedFormat<'a> {
-        DelayedFormat::new_with_offset(None, Some(self.local()), &self.offset, fmt)
+    pub fn format<'a>(&self, fmt: &'a str) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new(fmt))
+    }
+
+    /// Formats the time in the specified format string and locale.
+    /// The names, AM/PM markers and the layouts of `%c`, `%x`, `%X` and `%r` follow the locale.
+    #[inline]
+    pub fn format_localized<'a>(&self, fmt: &'a str,
+                                locale: &'a Locale<'a>) -> DelayedFormat<'a, StrftimeItems<'a>> {
+        self.format_with_items(StrftimeItems::new_with_locale(fmt, locale)).with_locale(locale)
     }
 }
 
@@ -0,0 +1,453 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * The time zone described by the POSIX `TZ` string (e.g. `EST5EDT,M3.2.0,M11.1.0`).
+ *
+ * This is the format of the `TZ` environment variable and also the footer of TZif files
+ * (version 2 and later), which describes the transitions after the last recorded one.
+ * The extensions from TZif version 3 (transition times from -167 to 167 hours) are supported.
+ */
+
+use std::fmt;
+use std::sync::Arc;
+
+use {Weekday, Datelike};
+use duration::Duration;
+use offset::{Offset, LocalResult, FixedOffset};
+use naive::date::NaiveDate;
+use naive::time::NaiveTime;
+use naive::datetime::NaiveDateTime;
+use date::Date;
+use time::Time;
+use datetime::DateTime;
+
+macro_rules! try_opt {
+    ($e:expr) => (match $e { Some(v) => v, None => return None })
+}
+
+/// The day of year when the transition occurs.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+enum RuleDate {
+    /// `Jn`: the day of year from 1 to 365, never counting February 29.
+    Julian1(u32),
+    /// `n`: the day of year from 0 to 365, counting February 29.
+    Julian0(u32),
+    /// `Mm.w.d`: the `w`-th given day of week in the month `m`. `w = 5` means the last one.
+    MonthWeekDay(u32, u32, Weekday),
+}
+
+/// The transition date and the local time of the transition in seconds.
+#[derive(Copy, Clone, PartialEq, Eq, Show)]
+struct Rule {
+    date: RuleDate,
+    time: i32,
+}
+
+/// The daylight saving time component of the POSIX `TZ` string.
+#[derive(Clone, PartialEq, Eq, Show)]
+struct Dst {
+    name: String,
+    offset: FixedOffset,
+    start: Rule,
+    end: Rule,
+}
+
+/// The parsed POSIX `TZ` string shared by every `PosixTz` value made out of it.
+#[derive(Clone, PartialEq, Eq, Show)]
+struct PosixTzData {
+    std_name: String,
+    std: FixedOffset,
+    dst: Option<Dst>,
+}
+
+/// The time zone with the rule-based daylight saving time, described by the POSIX `TZ` string.
+///
+/// The value also caches whether the daylight saving time is in effect,
+/// which is updated whenever the value is attached to the UTC datetime.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PosixTz {
+    data: Arc<PosixTzData>,
+    is_dst: bool,
+}
+
+/// The scanner over the POSIX `TZ` string.
+struct Scanner<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).map(|&c| c)
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) { self.pos += 1; true } else { false }
+    }
+
+    /// Reads a decimal number of at most `maxdigits` digits.
+    fn number(&mut self, maxdigits: usize) -> Option<u32> {
+        let start = self.pos;
+        let mut n = 0;
+        while self.pos - start < maxdigits {
+            match self.peek() {
+                Some(c @ b'0'...b'9') => { n = n * 10 + (c - b'0') as u32; self.pos += 1; }
+                _ => break,
+            }
+        }
+        if self.pos > start { Some(n) } else { None }
+    }
+
+    /// Reads a time zone name, either alphabetic (`EST`) or quoted (`<+0330>`).
+    fn name(&mut self) -> Option<String> {
+        let quoted = self.eat(b'<');
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(b'a'...b'z') | Some(b'A'...b'Z') => {}
+                Some(b'0'...b'9') | Some(b'+') | Some(b'-') if quoted => {}
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let name: String = self.s[start..self.pos].iter().map(|&c| c as char).collect();
+        if name.len() < 3 || (quoted && !self.eat(b'>')) { return None; }
+        Some(name)
+    }
+
+    /// Reads `[+-]hh[:mm[:ss]]` in seconds, where `hh` is at most `maxhour`.
+    fn hms(&mut self, maxhour: u32) -> Option<i32> {
+        let negative = if self.eat(b'-') { true } else { self.eat(b'+'); false };
+        let hour = try_opt!(self.number(3));
+        let mut secs = hour * 3600;
+        if self.eat(b':') {
+            let min = try_opt!(self.number(2));
+            secs += min * 60;
+            if min >= 60 { return None; }
+            if self.eat(b':') {
+                let sec = try_opt!(self.number(2));
+                secs += sec;
+                if sec >= 60 { return None; }
+            }
+        }
+        if hour > maxhour { return None; }
+        Some(if negative { -(secs as i32) } else { secs as i32 })
+    }
+
+    /// Reads a transition rule: `Jn`, `n` or `Mm.w.d`, optionally followed by `/time`.
+    fn rule(&mut self) -> Option<Rule> {
+        let date = if self.eat(b'J') {
+            let n = try_opt!(self.number(3));
+            if n < 1 || n > 365 { return None; }
+            RuleDate::Julian1(n)
+        } else if self.eat(b'M') {
+            let m = try_opt!(self.number(2));
+            if !self.eat(b'.') { return None; }
+            let w = try_opt!(self.number(1));
+            if !self.eat(b'.') { return None; }
+            let d = try_opt!(self.number(1));
+            if m < 1 || m > 12 || w < 1 || w > 5 || d > 6 { return None; }
+            static WEEKDAYS: [Weekday; 7] = [Weekday::Sun, Weekday::Mon, Weekday::Tue,
+                                             Weekday::Wed, Weekday::Thu, Weekday::Fri,
+                                             Weekday::Sat];
+            RuleDate::MonthWeekDay(m, w, WEEKDAYS[d as usize])
+        } else {
+            let n = try_opt!(self.number(3));
+            if n > 365 { return None; }
+            RuleDate::Julian0(n)
+        };
+        let time = if self.eat(b'/') { try_opt!(self.hms(167)) } else { 2 * 3600 };
+        Some(Rule { date: date, time: time })
+    }
+}
+
+impl RuleDate {
+    /// Returns the local date of the transition in given year.
+    fn to_date(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            RuleDate::Julian1(n) => {
+                let leap = NaiveDate::from_ymd_opt(year, 2, 29).is_some();
+                NaiveDate::from_yo_opt(year, if leap && n >= 60 { n + 1 } else { n })
+            }
+            RuleDate::Julian0(n) => {
+                let jan1 = try_opt!(NaiveDate::from_yo_opt(year, 1));
+                NaiveDate::from_num_days_from_ce_opt(jan1.num_days_from_ce() + n as i32)
+            }
+            RuleDate::MonthWeekDay(m, w, weekday) => {
+                let first = try_opt!(NaiveDate::from_ymd_opt(year, m, 1));
+                let firstday = 1 + (weekday.num_days_from_sunday() + 7 -
+                                    first.weekday().num_days_from_sunday()) % 7;
+                let mut day = firstday + (w - 1) * 7;
+                loop { // the fifth week may not exist, then it refers to the fourth week
+                    match first.with_day(day) {
+                        Some(date) => return Some(date),
+                        None => day -= 7,
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Rule {
+    /// Returns the UTC time of the transition in given year in seconds since the Unix epoch,
+    /// where `offset` is the offset in effect just before the transition.
+    fn transition(&self, year: i32, offset: &FixedOffset) -> Option<i64> {
+        let date = try_opt!(self.date.to_date(year));
+        Some(date.and_hms(0, 0, 0).num_seconds_from_unix_epoch() + self.time as i64 -
+             offset.local_minus_utc().num_seconds())
+    }
+}
+
+impl PosixTz {
+    /// Makes a new `PosixTz` from the POSIX `TZ` string,
+    /// e.g. `EST5EDT,M3.2.0,M11.1.0` or `<+0330>-3:30`.
+    ///
+    /// Note that the offset in the `TZ` string is positive in the *west* of the prime meridian.
+    /// The daylight saving time is one hour ahead of the standard time unless specified.
+    /// When the transition rules are omitted, the current U.S. rule `M3.2.0,M11.1.0` is assumed.
+    ///
+    /// Returns `None` when the string is invalid.
+    pub fn parse(s: &str) -> Option<PosixTz> {
+        let mut sc = Scanner { s: s.as_bytes(), pos: 0 };
+
+        let std_name = try_opt!(sc.name());
+        let std = try_opt!(sc.hms(24).and_then(|secs| FixedOffset::west_opt(secs)));
+
+        let dst = if sc.peek().is_none() {
+            None
+        } else {
+            let name = try_opt!(sc.name());
+            let offset = match sc.peek() {
+                Some(b',') | None => FixedOffset::east_opt(std.local_minus_utc().num_seconds()
+                                                           as i32 + 3600),
+                Some(_) => sc.hms(24).and_then(|secs| FixedOffset::west_opt(secs)),
+            };
+            let offset = try_opt!(offset);
+            let (start, end) = if sc.eat(b',') {
+                let start = try_opt!(sc.rule());
+                if !sc.eat(b',') { return None; }
+                (start, try_opt!(sc.rule()))
+            } else {
+                (Rule { date: RuleDate::MonthWeekDay(3, 2, Weekday::Sun), time: 2 * 3600 },
+                 Rule { date: RuleDate::MonthWeekDay(11, 1, Weekday::Sun), time: 2 * 3600 })
+            };
+            Some(Dst { name: name, offset: offset, start: start, end: end })
+        };
+
+        if sc.peek().is_some() { return None; }
+        let data = PosixTzData { std_name: std_name, std: std, dst: dst };
+        Some(PosixTz { data: Arc::new(data), is_dst: false })
+    }
+
+    /// Returns the abbreviated name of the standard time, e.g. `EST`.
+    pub fn std_name(&self) -> &str {
+        &self.data.std_name[..]
+    }
+
+    /// Returns the offset of the standard time.
+    pub fn std_offset(&self) -> FixedOffset {
+        self.data.std
+    }
+
+    /// Returns the abbreviated name of the daylight saving time if any, e.g. `EDT`.
+    pub fn dst_name(&self) -> Option<&str> {
+        self.data.dst.as_ref().map(|dst| &dst.name[..])
+    }
+
+    /// Returns the offset of the daylight saving time if any.
+    pub fn dst_offset(&self) -> Option<FixedOffset> {
+        self.data.dst.as_ref().map(|dst| dst.offset)
+    }
+
+    /// Returns true if the cached offset is the daylight saving time.
+    pub fn is_dst(&self) -> bool {
+        self.is_dst
+    }
+
+    /// Returns the abbreviated name of the cached offset, e.g. `EDT`.
+    pub fn abbreviation(&self) -> &str {
+        match self.data.dst {
+            Some(ref dst) if self.is_dst => &dst.name[..],
+            _ => &self.data.std_name[..],
+        }
+    }
+
+    /// Returns true if the daylight saving time is in effect at given UTC `NaiveDateTime`.
+    pub fn is_dst_at(&self, utc: &NaiveDateTime) -> bool {
+        let dst = match self.data.dst { Some(ref dst) => dst, None => return false };
+        let secs = utc.num_seconds_from_unix_epoch();
+
+        // the transitions in the current year may not be enough when the rule spans
+        // over the year boundary (e.g. the southern hemisphere), so we also look around.
+        let year = utc.year();
+        let mut transitions = Vec::with_capacity(6);
+        for &y in [year - 1, year, year + 1].iter() {
+            match dst.start.transition(y, &self.data.std) {
+                Some(t) => transitions.push((t, true)),
+                None => {}
+            }
+            match dst.end.transition(y, &dst.offset) {
+                Some(t) => transitions.push((t, false)),
+                None => {}
+            }
+        }
+        transitions.sort();
+        transitions.iter().rev().find(|&&(t, _)| t <= secs).map_or(false, |&(_, isdst)| isdst)
+    }
+
+    /// Returns the fixed offset in effect at given UTC `NaiveDateTime`.
+    pub fn offset_at(&self, utc: &NaiveDateTime) -> FixedOffset {
+        match self.data.dst {
+            Some(ref dst) if self.is_dst_at(utc) => dst.offset,
+            _ => self.data.std,
+        }
+    }
+
+    /// Returns the cached fixed offset.
+    fn cached_offset(&self) -> FixedOffset {
+        match self.data.dst {
+            Some(ref dst) if self.is_dst => dst.offset,
+            _ => self.data.std,
+        }
+    }
+}
+
+impl Offset for PosixTz {
+    fn local_minus_utc(&self) -> Duration {
+        self.cached_offset().local_minus_utc()
+    }
+
+    fn from_local_date(&self, local: &NaiveDate) -> LocalResult<Date<PosixTz>> {
+        // the date itself does not need the offset; the offset at the local noon is cached.
+        let offset = self.at_utc(&local.and_hms(12, 0, 0));
+        LocalResult::Single(Date::from_utc(local.clone(), offset))
+    }
+
+    fn from_local_time(&self, local: &NaiveTime) -> LocalResult<Time<PosixTz>> {
+        // we don't have enough information here, so we assume that the cached offset remains
+        LocalResult::Single(Time::from_utc(*local - self.local_minus_utc(), self.clone()))
+    }
+
+    fn from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<DateTime<PosixTz>> {
+        let mut utcs: Vec<NaiveDateTime> = Vec::with_capacity(2);
+        let mut candidates = vec![self.data.std];
+        candidates.extend(self.data.dst.iter().map(|dst| dst.offset));
+        for offset in candidates.iter() {
+            let utc = *local - offset.local_minus_utc();
+            if self.offset_at(&utc) == *offset && !utcs.contains(&utc) {
+                utcs.push(utc);
+            }
+        }
+        utcs.sort();
+
+        match utcs.len() {
+            0 => LocalResult::None,
+            1 => LocalResult::Single(DateTime::from_utc(utcs[0], self.clone())),
+            _ => LocalResult::Ambiguous(DateTime::from_utc(utcs[0], self.clone()),
+                                        DateTime::from_utc(utcs[1], self.clone())),
+        }
+    }
+
+    fn to_local_date(&self, utc: &NaiveDate) -> NaiveDate {
+        utc.clone()
+    }
+
+    fn to_local_time(&self, utc: &NaiveTime) -> NaiveTime {
+        *utc + self.local_minus_utc()
+    }
+
+    fn to_local_datetime(&self, utc: &NaiveDateTime) -> NaiveDateTime {
+        *utc + self.offset_at(utc).local_minus_utc()
+    }
+
+    fn at_utc(&self, utc: &NaiveDateTime) -> PosixTz {
+        PosixTz { data: self.data.clone(), is_dst: self.is_dst_at(utc) }
+    }
+}
+
+impl fmt::Debug for PosixTz {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.cached_offset(), f)
+    }
+}
+
+impl fmt::Display for PosixTz {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PosixTz;
+    use offset::{Offset, LocalResult, FixedOffset, UTC};
+    use naive::date::NaiveDate;
+
+    #[test]
+    fn test_posix_tz_parse() {
+        let tz = PosixTz::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        assert_eq!((tz.std_name(), tz.std_offset()), ("EST", FixedOffset::west(5 * 3600)));
+        assert_eq!((tz.dst_name(), tz.dst_offset()),
+                   (Some("EDT"), Some(FixedOffset::west(4 * 3600))));
+        assert_eq!(PosixTz::parse("EST5EDT"), Some(tz));
+
+        let tz = PosixTz::parse("<+0330>-3:30").unwrap();
+        assert_eq!((tz.std_name(), tz.std_offset()), ("+0330", FixedOffset::east(12600)));
+        assert_eq!(tz.dst_offset(), None);
+
+        let tz = PosixTz::parse("<-02>2<-01>+1:00:30,J60/-1,300/26:59:59").unwrap();
+        assert_eq!((tz.std_name(), tz.std_offset()), ("-02", FixedOffset::west(7200)));
+        assert_eq!(tz.dst_offset(), Some(FixedOffset::west(3630)));
+
+        for &s in ["", "ES5", "EST", "EST5ED", "<EST5", "EST25", "EST5EDT,M3.2.0",
+                   "EST5EDT,M13.2.0,M11.1.0", "EST5EDT,M3.6.0,M11.1.0", "EST5EDT,M3.2.7,M11.1.0",
+                   "EST5EDT,J0,J365", "EST5EDT,0,366", "EST5EDT,0/168,365",
+                   "EST5EDT,M3.2.0,M11.1.0 "].iter() {
+            assert!(PosixTz::parse(s).is_none(), "{:?} should be invalid", s);
+        }
+    }
+
+    #[test]
+    fn test_posix_tz_transitions() {
+        let tz = PosixTz::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        let ymdhms = |&: y,m,d,h,n,s| NaiveDate::from_ymd(y, m, d).and_hms(h, n, s);
+
+        assert_eq!(format!("{:?}", tz.ymd(2015, 1, 20).and_hms(17, 35, 20)),
+                   "2015-01-20T17:35:20-05:00");
+        assert_eq!(tz.ymd(2015, 7, 20).and_hms(17, 35, 20).format("%Z").to_string(), "EDT");
+
+        // 2015-03-08 and 2015-11-01
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 8, 1, 59, 59)).unwrap(),
+                   UTC.ymd(2015, 3, 8).and_hms(6, 59, 59));
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 8, 2, 30, 0)), LocalResult::None);
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 8, 3, 0, 0)).unwrap(),
+                   UTC.ymd(2015, 3, 8).and_hms(7, 0, 0));
+        match tz.from_local_datetime(&ymdhms(2015, 11, 1, 1, 30, 0)) {
+            LocalResult::Ambiguous(earlier, later) => {
+                assert_eq!(earlier, UTC.ymd(2015, 11, 1).and_hms(5, 30, 0));
+                assert_eq!(later, UTC.ymd(2015, 11, 1).and_hms(6, 30, 0));
+            }
+            r => panic!("expected an ambiguous result, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_posix_tz_southern_and_permanent() {
+        let tz = PosixTz::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(format!("{:?}", tz.ymd(2015, 1, 20).and_hms(12, 0, 0)),
+                   "2015-01-20T12:00:00+11:00");
+        assert_eq!(format!("{:?}", tz.ymd(2015, 7, 20).and_hms(12, 0, 0)),
+                   "2015-07-20T12:00:00+10:00");
+        assert_eq!(format!("{:?}", tz.ymd(2015, 12, 31).and_hms(23, 0, 0)),
+                   "2015-12-31T23:00:00+11:00");
+
+        // the daylight saving time all year round
+        let tz = PosixTz::parse("EST5EDT,0/0,J365/25").unwrap();
+        for &(m, d) in [(1, 1), (3, 1), (7, 1), (12, 31)].iter() {
+            assert!(tz.ymd(2015, m, d).and_hms(12, 0, 0).offset().is_dst());
+        }
+    }
+}
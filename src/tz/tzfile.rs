@@ -0,0 +1,522 @@
+// This is a part of rust-chrono.
+// Copyright (c) 2015, Kang Seonghoon.
+// See README.md and LICENSE.txt for details.
+
+/*!
+ * The time zone loaded from the compiled TZif file (see `tzfile(5)`).
+ */
+
+use std::{str, fmt};
+use std::sync::Arc;
+use std::old_io::File;
+
+use duration::Duration;
+use offset::{Offset, LocalResult, FixedOffset};
+use naive::date::NaiveDate;
+use naive::time::NaiveTime;
+use naive::datetime::NaiveDateTime;
+use date::Date;
+use time::Time;
+use datetime::DateTime;
+use super::posix::PosixTz;
+
+/// The directory where the compiled IANA time zone database is normally installed.
+pub const ZONEINFO_DIR: &'static str = "/usr/share/zoneinfo";
+
+macro_rules! try_opt {
+    ($e:expr) => (match $e { Some(v) => v, None => return None })
+}
+
+/// A local time type in the TZif file.
+#[derive(Clone, PartialEq, Eq, Show)]
+struct LocalTimeType {
+    /// The difference from UTC in seconds.
+    utoff: i32,
+    /// True if this type is the daylight saving time.
+    isdst: bool,
+    /// The abbreviated name, e.g. `CEST`.
+    abbr: String,
+}
+
+/// The TZif data shared by every `Tz` value loaded from the same file.
+struct TzData {
+    /// The UTC times of transitions in seconds since the Unix epoch, strictly increasing.
+    transitions: Vec<i64>,
+    /// The local time type index in effect after each transition.
+    transition_types: Vec<usize>,
+    /// Local time types. There is at least one type, and the first one is used
+    /// before the first transition.
+    types: Vec<LocalTimeType>,
+    /// The POSIX `TZ` rule for times after the last transition (version 2 and later),
+    /// with the indices to `types` for its standard and daylight saving times.
+    footer: Option<(PosixTz, usize, usize)>,
+}
+
+/// The time zone with historical transitions, loaded from the compiled TZif file.
+///
+/// The value also caches the local time type in effect,
+/// which is updated whenever the value is attached to the UTC datetime.
+#[derive(Clone)]
+pub struct Tz {
+    data: Arc<TzData>,
+    cached: usize,
+}
+
+/// A big-endian reader over the TZif data.
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.buf.len() - self.pos < n { return None; }
+        let ret = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Some(ret)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn be32(&mut self) -> Option<i32> {
+        self.bytes(4).map(|b| ((b[0] as u32) << 24 | (b[1] as u32) << 16 |
+                               (b[2] as u32) << 8 | (b[3] as u32)) as i32)
+    }
+
+    fn be64(&mut self) -> Option<i64> {
+        let hi = try_opt!(self.be32()) as u32 as u64;
+        let lo = try_opt!(self.be32()) as u32 as u64;
+        Some((hi << 32 | lo) as i64)
+    }
+}
+
+/// The counts in the TZif header, in the order of `tzfile(5)`.
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Header {
+    fn read(r: &mut Reader) -> Option<Header> {
+        if try_opt!(r.bytes(4)) != &b"TZif"[..] { return None; }
+        let version = try_opt!(r.u8());
+        try_opt!(r.bytes(15)); // reserved
+        let mut counts = [0us; 6];
+        for count in counts.iter_mut() {
+            let v = try_opt!(r.be32());
+            if v < 0 { return None; }
+            *count = v as usize;
+        }
+        Some(Header { version: version, isutcnt: counts[0], isstdcnt: counts[1],
+                      leapcnt: counts[2], timecnt: counts[3], typecnt: counts[4],
+                      charcnt: counts[5] })
+    }
+
+    /// Returns the length of the data block following the header.
+    fn data_len(&self, timesize: usize) -> usize {
+        self.timecnt * timesize + self.timecnt + self.typecnt * 6 + self.charcnt +
+            self.leapcnt * (timesize + 4) + self.isstdcnt + self.isutcnt
+    }
+}
+
+/// Reads the data block following given header, with `timesize`-byte transition times.
+fn read_data(r: &mut Reader, h: &Header, timesize: usize) -> Option<TzData> {
+    if h.typecnt == 0 { return None; }
+
+    let mut transitions = Vec::with_capacity(h.timecnt);
+    for _ in 0..h.timecnt {
+        let t = if timesize == 8 { try_opt!(r.be64()) } else { try_opt!(r.be32()) as i64 };
+        match transitions.last() {
+            Some(&last) if last >= t => return None,
+            _ => {}
+        }
+        transitions.push(t);
+    }
+
+    let mut transition_types = Vec::with_capacity(h.timecnt);
+    for _ in 0..h.timecnt {
+        let idx = try_opt!(r.u8()) as usize;
+        if idx >= h.typecnt { return None; }
+        transition_types.push(idx);
+    }
+
+    let mut ttinfos = Vec::with_capacity(h.typecnt);
+    for _ in 0..h.typecnt {
+        let utoff = try_opt!(r.be32());
+        let isdst = try_opt!(r.u8());
+        let abbrind = try_opt!(r.u8()) as usize;
+        if FixedOffset::east_opt(utoff).is_none() || isdst > 1 { return None; }
+        ttinfos.push((utoff, isdst == 1, abbrind));
+    }
+
+    let chars = try_opt!(r.bytes(h.charcnt));
+    let mut types = Vec::with_capacity(h.typecnt);
+    for &(utoff, isdst, abbrind) in ttinfos.iter() {
+        if abbrind >= chars.len() { return None; }
+        let abbr: String = chars[abbrind..].iter().take_while(|&&c| c != 0)
+                                                  .map(|&c| c as char).collect();
+        types.push(LocalTimeType { utoff: utoff, isdst: isdst, abbr: abbr });
+    }
+
+    // leap seconds and standard/UT indicators are not used
+    try_opt!(r.bytes(h.leapcnt * (timesize + 4) + h.isstdcnt + h.isutcnt));
+
+    Some(TzData { transitions: transitions, transition_types: transition_types, types: types,
+                  footer: None })
+}
+
+/// Reads the footer following the version 2+ data block, i.e. the POSIX `TZ` string
+/// enclosed by newlines. Returns `None` when the footer is empty or cannot be used.
+fn read_footer(r: &mut Reader) -> Option<PosixTz> {
+    if try_opt!(r.u8()) != b'\n' { return None; }
+    let rest = &r.buf[r.pos..];
+    let len = try_opt!(rest.iter().position(|&c| c == b'\n'));
+    let s = try_opt!(str::from_utf8(&rest[..len]).ok());
+    PosixTz::parse(s)
+}
+
+impl TzData {
+    /// Returns the number of transitions at or before given UTC time.
+    fn count_transitions(&self, secs: i64) -> usize {
+        let (mut lo, mut hi) = (0, self.transitions.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.transitions[mid] <= secs { lo = mid + 1; } else { hi = mid; }
+        }
+        lo
+    }
+
+    /// Returns the index to the local time type in effect at given UTC time.
+    fn type_at(&self, secs: i64) -> usize {
+        let n = self.count_transitions(secs);
+        if n == self.transitions.len() {
+            match self.footer {
+                Some((ref rule, std, dst)) => {
+                    let utc = NaiveDateTime::from_num_seconds_from_unix_epoch_opt(secs, 0);
+                    return if utc.map_or(false, |utc| rule.is_dst_at(&utc)) { dst } else { std };
+                }
+                None => {}
+            }
+        }
+        if n == 0 { 0 } else { self.transition_types[n - 1] }
+    }
+
+    /// Returns the index to the local time type with given properties,
+    /// adding a new type if there is no such one.
+    fn find_or_add_type(&mut self, offset: FixedOffset, isdst: bool, abbr: &str) -> usize {
+        let ty = LocalTimeType { utoff: offset.local_minus_utc().num_seconds() as i32,
+                                 isdst: isdst, abbr: abbr.to_string() };
+        match self.types.iter().position(|t| *t == ty) {
+            Some(idx) => idx,
+            None => { self.types.push(ty); self.types.len() - 1 }
+        }
+    }
+
+    /// Sets the footer rule, so that it applies after the last transition.
+    fn set_footer(&mut self, rule: PosixTz) {
+        let std = self.find_or_add_type(rule.std_offset(), false, rule.std_name());
+        let dst = match (rule.dst_offset(), rule.dst_name()) {
+            (Some(offset), Some(name)) => self.find_or_add_type(offset, true, name),
+            _ => std,
+        };
+        self.footer = Some((rule, std, dst));
+    }
+}
+
+impl Tz {
+    /// Makes a new `Tz` out of the contents of the TZif file (version 1, 2 or 3).
+    ///
+    /// Returns `None` when the data is not a valid TZif file.
+    pub fn from_tzif(buf: &[u8]) -> Option<Tz> {
+        let mut r = Reader { buf: buf, pos: 0 };
+        let header = try_opt!(Header::read(&mut r));
+        let data = if header.version == 0 {
+            try_opt!(read_data(&mut r, &header, 4))
+        } else {
+            // version 2 and later repeat the data with 64-bit transition times
+            try_opt!(r.bytes(header.data_len(4)));
+            let header = try_opt!(Header::read(&mut r));
+            let mut data = try_opt!(read_data(&mut r, &header, 8));
+            match read_footer(&mut r) {
+                Some(rule) => data.set_footer(rule),
+                None => {}
+            }
+            data
+        };
+        Some(Tz { data: Arc::new(data), cached: 0 })
+    }
+
+    /// Makes a new `Tz` out of the TZif file at given path.
+    ///
+    /// Returns `None` when the file cannot be read or is not a valid TZif file.
+    pub fn from_file(path: &str) -> Option<Tz> {
+        match File::open(&Path::new(path)).and_then(|mut f| f.read_to_end()) {
+            Ok(buf) => Tz::from_tzif(&buf[..]),
+            Err(_) => None,
+        }
+    }
+
+    /// Makes a new `Tz` for given IANA time zone name (e.g. `Europe/Berlin`)
+    /// out of the system time zone database in `ZONEINFO_DIR`.
+    ///
+    /// Returns `None` when the time zone cannot be found or read.
+    pub fn from_zoneinfo(name: &str) -> Option<Tz> {
+        // do not allow escaping from the database directory
+        if name.is_empty() || name.starts_with("/") || name.split('/').any(|c| c == "..") {
+            return None;
+        }
+        Tz::from_file(&format!("{}/{}", ZONEINFO_DIR, name)[..])
+    }
+
+    /// Returns the cached local time type.
+    fn cached_type(&self) -> &LocalTimeType {
+        &self.data.types[self.cached]
+    }
+
+    /// Returns the abbreviated name of the cached local time type, e.g. `CEST`.
+    pub fn abbreviation(&self) -> &str {
+        &self.cached_type().abbr[..]
+    }
+
+    /// Returns true if the cached local time type is the daylight saving time.
+    pub fn is_dst(&self) -> bool {
+        self.cached_type().isdst
+    }
+
+    /// Returns the fixed offset in effect at given UTC `NaiveDateTime`.
+    pub fn offset_at(&self, utc: &NaiveDateTime) -> FixedOffset {
+        let idx = self.data.type_at(utc.num_seconds_from_unix_epoch());
+        FixedOffset::east(self.data.types[idx].utoff)
+    }
+}
+
+impl Offset for Tz {
+    fn local_minus_utc(&self) -> Duration {
+        Duration::seconds(self.cached_type().utoff as i64)
+    }
+
+    fn from_local_date(&self, local: &NaiveDate) -> LocalResult<Date<Tz>> {
+        // the date itself does not need the offset; the offset at the local noon is cached.
+        let offset = self.at_utc(&local.and_hms(12, 0, 0));
+        LocalResult::Single(Date::from_utc(local.clone(), offset))
+    }
+
+    fn from_local_time(&self, local: &NaiveTime) -> LocalResult<Time<Tz>> {
+        // we don't have enough information here, so we assume that the cached type remains same
+        LocalResult::Single(Time::from_utc(*local - self.local_minus_utc(), self.clone()))
+    }
+
+    fn from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<DateTime<Tz>> {
+        let data = &*self.data;
+        let secs = local.num_seconds_from_unix_epoch();
+
+        // every offset is less than a day, so only the local time types in effect
+        // within a day from `secs` can map to the given local datetime.
+        let lo = data.count_transitions(secs - 86400);
+        let hi = data.count_transitions(secs + 86400);
+        let mut candidates = vec![data.type_at(secs - 86400)];
+        candidates.push_all(&data.transition_types[lo..hi]);
+        match data.footer {
+            Some((_, std, dst)) if hi == data.transitions.len() => {
+                candidates.push(std);
+                candidates.push(dst);
+            }
+            _ => {}
+        }
+
+        let mut utcs: Vec<NaiveDateTime> = Vec::new();
+        for &idx in candidates.iter() {
+            let utoff = data.types[idx].utoff;
+            if data.types[data.type_at(secs - utoff as i64)].utoff == utoff {
+                let utc = *local - Duration::seconds(utoff as i64);
+                if !utcs.contains(&utc) { utcs.push(utc); }
+            }
+        }
+        utcs.sort();
+
+        match utcs.len() {
+            0 => LocalResult::None,
+            1 => LocalResult::Single(DateTime::from_utc(utcs[0], self.clone())),
+            n => LocalResult::Ambiguous(DateTime::from_utc(utcs[0], self.clone()),
+                                        DateTime::from_utc(utcs[n - 1], self.clone())),
+        }
+    }
+
+    fn to_local_date(&self, utc: &NaiveDate) -> NaiveDate {
+        utc.clone()
+    }
+
+    fn to_local_time(&self, utc: &NaiveTime) -> NaiveTime {
+        *utc + self.local_minus_utc()
+    }
+
+    fn to_local_datetime(&self, utc: &NaiveDateTime) -> NaiveDateTime {
+        *utc + self.offset_at(utc).local_minus_utc()
+    }
+
+    fn at_utc(&self, utc: &NaiveDateTime) -> Tz {
+        let idx = self.data.type_at(utc.num_seconds_from_unix_epoch());
+        Tz { data: self.data.clone(), cached: idx }
+    }
+}
+
+impl fmt::Debug for Tz {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&FixedOffset::east(self.cached_type().utoff), f)
+    }
+}
+
+impl fmt::Display for Tz {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tz;
+    use duration::Duration;
+    use offset::{Offset, LocalResult, UTC};
+    use naive::date::NaiveDate;
+
+    /// Builds a TZif file with given transitions `(time, type)` and types `(utoff, isdst, abbr)`.
+    fn make_tzif(version: u8, transitions: &[(i64, u8)], types: &[(i32, bool, &str)]) -> Vec<u8> {
+        fn push32(buf: &mut Vec<u8>, v: i32) {
+            buf.push_all(&[(v >> 24) as u8, (v >> 16) as u8, (v >> 8) as u8, v as u8]);
+        }
+
+        let mut chars = Vec::new();
+        let mut abbrinds = Vec::new();
+        for &(_, _, abbr) in types.iter() {
+            abbrinds.push(chars.len() as u8);
+            chars.push_all(abbr.as_bytes());
+            chars.push(0);
+        }
+
+        let mut buf = Vec::new();
+        let write_block = |&: buf: &mut Vec<u8>, timesize: usize| {
+            buf.push_all(b"TZif");
+            buf.push(version);
+            buf.push_all(&[0; 15]);
+            for &count in [0, 0, 0, transitions.len(), types.len(), chars.len()].iter() {
+                push32(buf, count as i32);
+            }
+            for &(t, _) in transitions.iter() {
+                if timesize == 8 { push32(buf, (t >> 32) as i32); }
+                push32(buf, t as i32);
+            }
+            for &(_, idx) in transitions.iter() { buf.push(idx); }
+            for (&(utoff, isdst, _), &abbrind) in types.iter().zip(abbrinds.iter()) {
+                push32(buf, utoff);
+                buf.push(isdst as u8);
+                buf.push(abbrind);
+            }
+            buf.push_all(&chars[..]);
+        };
+        write_block(&mut buf, 4);
+        if version != 0 {
+            write_block(&mut buf, 8);
+            buf.push_all(b"\nCET-1CEST,M3.5.0,M10.5.0/3\n");
+        }
+        buf
+    }
+
+    fn berlin_2015(version: u8) -> Tz {
+        let tzif = make_tzif(version,
+                             &[(1427590800, 1), (1445734800, 0)], // 2015-03-29, 2015-10-25
+                             &[(3600, false, "CET"), (7200, true, "CEST")]);
+        Tz::from_tzif(&tzif[..]).unwrap()
+    }
+
+    #[test]
+    fn test_tz_from_tzif() {
+        for &version in [0, b'2', b'3'].iter() {
+            let tz = berlin_2015(version);
+            assert_eq!(format!("{:?}", tz.ymd(2015, 1, 20).and_hms(17, 35, 20)),
+                       "2015-01-20T17:35:20+01:00");
+            assert_eq!(format!("{:?}", tz.ymd(2015, 7, 20).and_hms(17, 35, 20)),
+                       "2015-07-20T17:35:20+02:00");
+        }
+
+        assert!(Tz::from_tzif(b"").is_none());
+        assert!(Tz::from_tzif(b"TZif2").is_none());
+        let tzif = make_tzif(0, &[(0, 0)], &[(3600, false, "CET")]);
+        assert!(Tz::from_tzif(&tzif[..]).is_some());
+        assert!(Tz::from_tzif(&tzif[..tzif.len() - 1]).is_none());
+    }
+
+    #[test]
+    fn test_tz_from_local_datetime() {
+        let tz = berlin_2015(b'2');
+        let ymdhms = |&: y,m,d,h,n,s| NaiveDate::from_ymd(y, m, d).and_hms(h, n, s);
+
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 29, 1, 59, 59)).unwrap(),
+                   UTC.ymd(2015, 3, 29).and_hms(0, 59, 59));
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 29, 2, 30, 0)), LocalResult::None);
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 29, 3, 0, 0)).unwrap(),
+                   UTC.ymd(2015, 3, 29).and_hms(1, 0, 0));
+
+        match tz.from_local_datetime(&ymdhms(2015, 10, 25, 2, 30, 0)) {
+            LocalResult::Ambiguous(earlier, later) => {
+                assert_eq!(format!("{:?}", earlier), "2015-10-25T02:30:00+02:00");
+                assert_eq!(format!("{:?}", later), "2015-10-25T02:30:00+01:00");
+                assert_eq!(later - earlier, Duration::hours(1));
+            }
+            r => panic!("expected an ambiguous result, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_tz_footer() {
+        let ymdhms = |&: y,m,d,h,n,s| NaiveDate::from_ymd(y, m, d).and_hms(h, n, s);
+
+        // version 1 has no footer, so the last type remains forever
+        let tz = berlin_2015(0);
+        assert_eq!(format!("{:?}", tz.ymd(2016, 7, 20).and_hms(12, 0, 0)),
+                   "2016-07-20T12:00:00+01:00");
+
+        let tz = berlin_2015(b'2');
+        assert_eq!(format!("{:?}", tz.ymd(2016, 1, 20).and_hms(12, 0, 0)),
+                   "2016-01-20T12:00:00+01:00");
+        assert_eq!(tz.ymd(2016, 7, 20).and_hms(12, 0, 0).format("%Z %z").to_string(),
+                   "CEST +0200");
+        assert_eq!(tz.from_local_datetime(&ymdhms(2016, 3, 27, 2, 30, 0)), LocalResult::None);
+        match tz.from_local_datetime(&ymdhms(2016, 10, 30, 2, 30, 0)) {
+            LocalResult::Ambiguous(earlier, later) => {
+                assert_eq!(earlier, UTC.ymd(2016, 10, 30).and_hms(0, 30, 0));
+                assert_eq!(later, UTC.ymd(2016, 10, 30).and_hms(1, 30, 0));
+            }
+            r => panic!("expected an ambiguous result, got {:?}", r),
+        }
+
+        // the slim files end the transitions early and leave the rest to the footer
+        let tzif = make_tzif(b'2', &[(828234000, 1), (846378000, 0)], // 1996-03-31, 1996-10-27
+                             &[(3600, false, "CET"), (7200, true, "CEST")]);
+        let tz = Tz::from_tzif(&tzif[..]).unwrap();
+        assert_eq!(format!("{:?}", tz.ymd(2015, 1, 20).and_hms(12, 0, 0)),
+                   "2015-01-20T12:00:00+01:00");
+        assert_eq!(format!("{:?}", tz.ymd(2015, 3, 29).and_hms(12, 0, 0)),
+                   "2015-03-29T12:00:00+02:00");
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 29, 2, 30, 0)), LocalResult::None);
+        assert_eq!(tz.from_local_datetime(&ymdhms(2015, 3, 29, 3, 0, 0)).unwrap(),
+                   UTC.ymd(2015, 3, 29).and_hms(1, 0, 0));
+    }
+
+    #[test]
+    fn test_tz_offset_follows_instant() {
+        let tz = berlin_2015(b'2');
+        let dt = tz.ymd(2015, 3, 28).and_hms(12, 0, 0);
+        let dt2 = dt.clone() + Duration::days(1);
+        assert_eq!(format!("{}", dt.format("%H:%M %Z %z")), "12:00 CET +0100");
+        assert_eq!(format!("{}", dt2.format("%H:%M %Z %z")), "13:00 CEST +0200");
+        assert!(!dt.offset().is_dst());
+        assert!(dt2.offset().is_dst());
+    }
+}